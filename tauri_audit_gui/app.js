@@ -1,6 +1,16 @@
 const state = {
-  events: [],
-  filtered: [],
+  // The page on screen and how many events match the filters; the store holds the rest.
+  rows: [],
+  total: 0,
+  // Without the desktop backend there is no store, so the browser preview keeps its events here.
+  preview: [],
+  summary: null,
+  // A collector, database, large-file or index page shown instead of the loaded events:
+  // { source, label, rejects }. The rows live in the backend's result view.
+  results: null,
+  rejects: [],
+  loadedPaths: [],
+  schemaReports: [],
   page: 1,
   pageSize: 15,
   selectedRaw: null,
@@ -10,11 +20,20 @@ const state = {
   views: {},
   bookmarks: {},
   notes: {},
+  jobId: null,
+  collector: { page: 1, pages: 1 },
+  health: { monitorId: null, unlisten: null },
+  sql: { page: 1, pages: 1 },
+  database: { path: null, page: 1, pages: 1 },
+  largeFile: { path: null, page: 1, pages: 1 },
+  // Where each event of the latest index search was read from, by event_id.
+  indexHits: null,
   tail: {
     path: null,
-    offset: 0,
-    buffer: "",
-    timer: null,
+    cursor: null,
+    sessionId: null,
+    remoteId: null,
+    unlisten: null,
   },
 };
 
@@ -30,6 +49,7 @@ const fieldGuide = {
   system: "Host and runtime metadata.",
   metadata: "Application context (env, app name).",
   custom: "Additional custom fields.",
+  location: "File, line and byte offset the full-text index read this event from.",
 };
 
 const $ = (id) => document.getElementById(id);
@@ -50,135 +70,247 @@ function setStatus(text) {
   $("loadStatus").textContent = text;
 }
 
+function formatProgress(label, progress) {
+  if (progress.kind === "bytes" && progress.total > 0) {
+    return `${label}: ${Math.floor((progress.read / progress.total) * 100)}%`;
+  }
+  if (progress.kind === "events") return `${label}: ${progress.parsed} events parsed`;
+  if (progress.kind === "sent") return `${label}: ${progress.sent} events sent`;
+  if (progress.kind === "pages") return `${label}: ${progress.rendered}/${progress.total} pages`;
+  return `${label}...`;
+}
+
+async function runJob(command, args, label) {
+  const { invoke, event } = window.__TAURI__;
+  const jobId = `job-${Date.now()}-${Math.random().toString(16).slice(2)}`;
+  state.jobId = jobId;
+  $("cancelJob").disabled = false;
+  setStatus(`${label}...`);
+  const unlisten = await event.listen("job://progress", ({ payload }) => {
+    if (payload.job_id === jobId && payload.kind !== "done") {
+      setStatus(formatProgress(label, payload));
+    }
+  });
+  try {
+    return await invoke(command, { ...args, jobId });
+  } finally {
+    unlisten();
+    if (state.jobId === jobId) {
+      state.jobId = null;
+      $("cancelJob").disabled = true;
+    }
+  }
+}
+
+async function cancelJob() {
+  if (!window.__TAURI__ || !state.jobId) return;
+  await window.__TAURI__.invoke("cancel_job", { jobId: state.jobId });
+}
+
 function setEventCount() {
-  $("eventCount").textContent = `${state.filtered.length} events`;
+  $("eventCount").textContent = `${state.total} events`;
 }
 
-function refreshUI() {
-  setEventCount();
+async function refreshUI() {
+  renderResultBar();
   renderChips();
-  renderTable();
-  renderSummary();
-  renderInsights();
-  renderTimeline();
-  renderTimelineBySource();
-  renderValidation();
-  computeIntegrity();
   renderBookmarks();
+  if (!(await renderPage())) return;
+  renderAggregates();
+  computeIntegrity();
 }
 
-function renderSummary() {
-  const panel = $("summaryPanel");
-  panel.innerHTML = "";
-  const total = state.filtered.length;
-  const success = state.filtered.filter((e) => e.status === "SUCCESS").length;
-  const failure = state.filtered.filter((e) => e.status === "FAILURE").length;
-  const byCategory = {};
-  const byUser = {};
-  const byAction = {};
-  state.filtered.forEach((e) => {
-    const key = e.category || "UNSPECIFIED";
-    byCategory[key] = (byCategory[key] || 0) + 1;
-    const userKey = e.user || "UNKNOWN";
-    byUser[userKey] = (byUser[userKey] || 0) + 1;
-    const actionKey = e.action || "UNKNOWN";
-    byAction[actionKey] = (byAction[actionKey] || 0) + 1;
+const sortSpec = () => ({ key: state.sortKey, dir: state.sortDir });
+
+// Browser-preview stand-in for the store's filter and sort.
+function previewMatches(filter) {
+  const term = filter.search.toLowerCase();
+  const matched = state.preview.filter((evt) => {
+    const ts = evt.timestamp ? new Date(evt.timestamp) : null;
+    return (
+      matchSearch(evt, term, filter.regex) &&
+      matchQuery(evt, filter.query) &&
+      (!filter.category || evt.category === filter.category) &&
+      (!filter.status || evt.status === filter.status) &&
+      (!filter.errors_only || evt.raw?.error?.occurred === true) &&
+      (!filter.date_from || (ts && ts >= new Date(filter.date_from))) &&
+      (!filter.date_to || (ts && ts <= new Date(filter.date_to + "T23:59:59")))
+    );
   });
+  sortPreview(matched);
+  return matched;
+}
+
+async function fetchPage(filter, page, pageSize) {
+  if (window.__TAURI__) {
+    return window.__TAURI__.invoke("query_page", {
+      results: Boolean(state.results),
+      filter,
+      sort: sortSpec(),
+      page,
+      pageSize,
+    });
+  }
+  const matched = previewMatches(filter);
+  const start = (page - 1) * pageSize;
+  return { total: matched.length, page, page_size: pageSize, rows: matched.slice(start, start + pageSize) };
+}
 
-  const cards = [
-    ["Total Events", total],
-    ["Success", success],
-    ["Failure", failure],
-    ["Categories", Object.keys(byCategory).length],
+let pageSeq = 0;
+
+// Fetches the current page; false when the filters do not compile.
+async function renderPage() {
+  const seq = ++pageSeq;
+  const error = $("queryError");
+  let page;
+  try {
+    page = await fetchPage(panelFilter(), state.page, state.pageSize);
+  } catch (err) {
+    if (seq !== pageSeq) return false;
+    error.textContent = String(err);
+    error.hidden = false;
+    return false;
+  }
+  if (seq !== pageSeq) return false;
+  error.hidden = true;
+  state.rows = page.rows;
+  state.total = page.total;
+  setEventCount();
+  renderTable();
+  return true;
+}
+
+let refreshTimer = null;
+
+// Tail batches can arrive many times a second; redraw at most twice a second.
+function refreshSoon() {
+  if (refreshTimer) return;
+  refreshTimer = setTimeout(() => {
+    refreshTimer = null;
+    refreshUI();
+  }, 500);
+}
+
+const summarySpecs = () => {
+  const bucket = $("timelineBucket").value;
+  const timeZone = $("timelineZone").value.trim() || "UTC";
+  return [
+    { group_by: ["status"] },
+    { group_by: ["category"] },
+    { group_by: ["user"] },
+    { group_by: ["action"] },
+    { group_by: ["error"] },
+    { metrics: ["performance.duration_ms"], percentiles: [50, 95] },
+    { bucket, time_zone: timeZone },
+    { bucket, time_zone: timeZone, group_by: ["source"] },
   ];
+};
 
-  cards.forEach(([label, value]) => {
-    const card = document.createElement("div");
-    card.className = "summary-card";
-    card.innerHTML = `<h3>${label}</h3><div class="value">${value}</div>`;
-    panel.appendChild(card);
-  });
+async function aggregateEvents(specs) {
+  return window.__TAURI__.invoke("aggregate", { results: Boolean(state.results), filter: panelFilter(), specs });
+}
 
-  Object.entries(byCategory)
-    .sort((a, b) => b[1] - a[1])
-    .slice(0, 4)
-    .forEach(([cat, count]) => {
-      const card = document.createElement("div");
-      card.className = "summary-card";
-      card.innerHTML = `<h3>${cat}</h3><div class="value">${count}</div>`;
-      panel.appendChild(card);
-    });
+let aggregateSeq = 0;
 
-  const topUser = Object.entries(byUser).sort((a, b) => b[1] - a[1])[0];
-  if (topUser) {
-    const card = document.createElement("div");
-    card.className = "summary-card";
-    card.innerHTML = `<h3>Top User</h3><div class="value">${topUser[0]} (${topUser[1]})</div>`;
-    panel.appendChild(card);
+async function renderAggregates() {
+  const seq = ++aggregateSeq;
+  if (!window.__TAURI__) {
+    state.summary = null;
+    $("summaryPanel").innerHTML = "<div class='meta'>Summaries, insights and timelines need the desktop app.</div>";
+    renderValidation();
+    return;
   }
-
-  const topAction = Object.entries(byAction).sort((a, b) => b[1] - a[1])[0];
-  if (topAction) {
-    const card = document.createElement("div");
-    card.className = "summary-card";
-    card.innerHTML = `<h3>Top Action</h3><div class="value">${topAction[0]} (${topAction[1]})</div>`;
-    panel.appendChild(card);
+  let result;
+  try {
+    result = await aggregateEvents(summarySpecs());
+  } catch (err) {
+    setStatus(`Aggregation failed: ${err}`);
+    return;
   }
+  if (seq !== aggregateSeq) return;
+  state.summary = result;
+  const [byStatus, byCategory, byUser, byAction, byError, durations, timeline, bySource] = result;
+  renderSummary(byStatus, byCategory, byUser, byAction);
+  renderInsights(byStatus, byUser, byAction, byError, durations);
+  renderTimeline(timeline);
+  renderTimelineBySource(bySource);
+  renderValidation();
+}
+
+const countOf = (agg, key) => agg.groups.find((g) => g.keys[0] === key)?.count || 0;
+
+function summaryCard(panel, label, value) {
+  const card = document.createElement("div");
+  card.className = "summary-card";
+  card.innerHTML = `<h3>${label}</h3><div class="value">${value}</div>`;
+  panel.appendChild(card);
+}
+
+function renderSummary(byStatus, byCategory, byUser, byAction) {
+  const panel = $("summaryPanel");
+  panel.innerHTML = "";
+  if (!byStatus) return;
+
+  summaryCard(panel, "Total Events", byStatus.matched);
+  summaryCard(panel, "Success", countOf(byStatus, "SUCCESS"));
+  summaryCard(panel, "Failure", countOf(byStatus, "FAILURE"));
+  summaryCard(panel, "Categories", byCategory.group_count);
+
+  byCategory.groups
+    .slice(0, 4)
+    .forEach((g) => summaryCard(panel, g.keys[0] || "UNSPECIFIED", g.count));
+
+  const topUser = byUser.groups[0];
+  if (topUser) summaryCard(panel, "Top User", `${topUser.keys[0] || "UNKNOWN"} (${topUser.count})`);
+
+  const topAction = byAction.groups[0];
+  if (topAction) summaryCard(panel, "Top Action", `${topAction.keys[0] || "UNKNOWN"} (${topAction.count})`);
 }
 
-function renderInsights() {
+function renderInsights(byStatus, byUser, byAction, byError, durations) {
   const panel = $("insightsPanel");
   panel.innerHTML = "";
-  const total = state.filtered.length || 1;
-  const failureCount = state.filtered.filter((e) => e.status === "FAILURE").length;
-  const errorCount = state.filtered.filter((e) => e.raw?.error?.occurred).length;
-  const failureRate = Math.round((failureCount / total) * 100);
-  const errorRate = Math.round((errorCount / total) * 100);
+  if (!byStatus) return;
+  const total = byStatus.matched || 1;
+  const failureRate = Math.round((countOf(byStatus, "FAILURE") / total) * 100);
+  const errorRate = Math.round((countOf(byError, true) / total) * 100);
+  const duration = durations.groups[0]?.metrics["performance.duration_ms"];
 
   const cards = [
     ["Failure Rate", `${failureRate}%`],
     ["Error Rate", `${errorRate}%`],
-    ["Unique Users", new Set(state.filtered.map((e) => e.user || "UNKNOWN")).size],
-    ["Unique Actions", new Set(state.filtered.map((e) => e.action || "UNKNOWN")).size],
+    ["Unique Users", byUser.group_count],
+    ["Unique Actions", byAction.group_count],
   ];
-
-  cards.forEach(([label, value]) => {
-    const card = document.createElement("div");
-    card.className = "summary-card";
-    card.innerHTML = `<h3>${label}</h3><div class="value">${value}</div>`;
-    panel.appendChild(card);
-  });
+  if (duration) {
+    cards.push(["Median Duration", `${Math.round(duration.percentiles.p50)} ms`]);
+    cards.push(["p95 Duration", `${Math.round(duration.percentiles.p95)} ms`]);
+  }
+  cards.forEach(([label, value]) => summaryCard(panel, label, value));
 }
 
-function renderTimeline() {
+function renderTimeline(timeline) {
   const chart = $("timelineChart");
   chart.innerHTML = "";
-  if (state.filtered.length === 0) return;
-  const buckets = {};
-  state.filtered.forEach((e) => {
-    const ts = e.timestamp ? new Date(e.timestamp) : null;
-    if (!ts || Number.isNaN(ts.getTime())) return;
-    const key = ts.toISOString().slice(0, 10);
-    buckets[key] = (buckets[key] || 0) + 1;
-  });
-  const values = Object.values(buckets);
-  const max = Math.max(...values, 1);
-  Object.values(buckets).forEach((count) => {
+  if (!timeline || timeline.groups.length === 0) return;
+  const max = Math.max(...timeline.groups.map((g) => g.count), 1);
+  timeline.groups.forEach((g) => {
     const bar = document.createElement("div");
     bar.className = "bar";
-    bar.style.height = `${Math.max(8, (count / max) * 80)}px`;
+    bar.title = `${g.bucket}: ${g.count}`;
+    bar.style.height = `${Math.max(8, (g.count / max) * 80)}px`;
     chart.appendChild(bar);
   });
 }
 
-function renderTimelineBySource() {
+function renderTimelineBySource(bySource) {
   const chart = $("timelineBySource");
   const legend = $("sourceLegend");
   chart.innerHTML = "";
   legend.innerHTML = "";
-  if (state.filtered.length === 0) return;
+  if (!bySource || bySource.groups.length === 0) return;
 
-  const sources = [...new Set(state.filtered.map((e) => e.source || "unknown"))];
+  const sources = [...new Set(bySource.groups.map((g) => g.keys[0] || "unknown"))];
   const colors = [
     "#38bdf8",
     "#fb7185",
@@ -196,22 +328,18 @@ function renderTimelineBySource() {
   });
 
   const buckets = {};
-  state.filtered.forEach((e) => {
-    const ts = e.timestamp ? new Date(e.timestamp) : null;
-    if (!ts || Number.isNaN(ts.getTime())) return;
-    const key = ts.toISOString().slice(0, 10);
-    buckets[key] = buckets[key] || {};
-    const src = e.source || "unknown";
-    buckets[key][src] = (buckets[key][src] || 0) + 1;
+  bySource.groups.forEach((g) => {
+    buckets[g.bucket] = buckets[g.bucket] || {};
+    buckets[g.bucket][g.keys[0] || "unknown"] = g.count;
   });
 
-  const days = Object.keys(buckets).sort();
   chart.classList.add("stack");
-  days.forEach((day) => {
+  Object.keys(buckets).forEach((bucket) => {
     const col = document.createElement("div");
     col.className = "stack-col";
+    col.title = bucket;
     sources.forEach((s) => {
-      const count = buckets[day][s] || 0;
+      const count = buckets[bucket][s] || 0;
       const bar = document.createElement("div");
       const height = Math.max(4, count * 6);
       bar.className = "bar";
@@ -226,27 +354,43 @@ function renderTimelineBySource() {
 function renderValidation() {
   const panel = $("validationPanel");
   panel.innerHTML = "";
-  if (state.filtered.length === 0) {
+  if (state.total === 0) {
     panel.innerHTML = "<li>No data loaded.</li>";
     return;
   }
-  let missingTimestamp = 0;
-  let missingAction = 0;
-  let badTimestamp = 0;
-  state.filtered.forEach((e) => {
-    if (!e.timestamp) missingTimestamp += 1;
-    if (!e.action) missingAction += 1;
-    if (e.timestamp) {
-      const ts = new Date(e.timestamp);
-      if (Number.isNaN(ts.getTime())) badTimestamp += 1;
-    }
-  });
+  const items = [];
+  const [, , , byAction, , , timeline] = state.summary || [];
+  if (timeline) {
+    items.push(`Missing or malformed timestamp: ${timeline.unbucketed}`);
+    items.push(`Missing action: ${countOf(byAction, "")}`);
+  }
 
-  const items = [
-    `Missing timestamp: ${missingTimestamp}`,
-    `Malformed timestamp: ${badTimestamp}`,
-    `Missing action: ${missingAction}`,
-  ];
+  const rejects = state.results ? state.results.rejects : state.rejects;
+  if (rejects.length > 0) {
+    items.push(`Rejected lines: ${rejects.length}`);
+    rejects.slice(0, 20).forEach((r) => {
+      items.push(`${r.source}:${r.line} (byte ${r.byte_offset}) ${r.error} | ${r.snippet}`);
+    });
+  }
+
+  if (!state.results && state.schemaReports.length > 0) {
+    const invalid = state.schemaReports.flatMap((r) => r.invalid);
+    const checked = state.schemaReports.reduce((sum, r) => sum + r.checked, 0);
+    items.push(`Schema violations: ${invalid.length} of ${checked} events`);
+    state.schemaReports.forEach((r) => {
+      if (r.skipped) items.push(r.skipped);
+    });
+    const byPointer = {};
+    invalid.forEach((evt) =>
+      evt.errors.forEach((err) => {
+        byPointer[err.pointer] = (byPointer[err.pointer] || 0) + 1;
+      })
+    );
+    Object.entries(byPointer)
+      .sort((a, b) => b[1] - a[1])
+      .slice(0, 10)
+      .forEach(([pointer, count]) => items.push(`${pointer || "/"}: ${count}`));
+  }
 
   items.forEach((text) => {
     const li = document.createElement("li");
@@ -255,6 +399,27 @@ function renderValidation() {
   });
 }
 
+async function validateSchema() {
+  if (!window.__TAURI__) {
+    setStatus("Schema validation is available in Tauri desktop mode.");
+    return;
+  }
+  if (state.loadedPaths.length === 0) {
+    setStatus("Open files with the desktop loader to validate them.");
+    return;
+  }
+  try {
+    const reports = [];
+    for (const path of state.loadedPaths) {
+      reports.push(await runJob("validate_audit_file", { path }, "Validating"));
+    }
+    state.schemaReports = reports;
+    renderValidation();
+  } catch (err) {
+    setStatus(`Schema validation failed: ${err}`);
+  }
+}
+
 function renderFieldGuide() {
   const container = $("fieldGuide");
   container.innerHTML = "";
@@ -311,10 +476,9 @@ function normalizeEvent(raw, source = "") {
 function renderTable() {
   const tbody = $("eventsBody");
   tbody.innerHTML = "";
-  const start = (state.page - 1) * state.pageSize;
-  const pageItems = state.filtered.slice(start, start + state.pageSize);
+  updatePageInfo();
 
-  if (state.filtered.length === 0) {
+  if (state.rows.length === 0) {
     const row = document.createElement("tr");
     row.className = "empty";
     row.innerHTML = `<td colspan="5">No events match the current filters.</td>`;
@@ -322,7 +486,7 @@ function renderTable() {
     return;
   }
 
-  pageItems.forEach((evt) => {
+  state.rows.forEach((evt) => {
     const row = document.createElement("tr");
     if (evt.raw?.error?.occurred) row.classList.add("error-row");
     const star = isBookmarked(evt) ? "★ " : "";
@@ -335,14 +499,13 @@ function renderTable() {
       <td>${renderStatus(evt.status)}</td>
     `;
     row.addEventListener("click", () => {
-      renderDetail(evt.raw);
+      if (state.results?.source === "collector") openCollectorEvent(evt);
+      else renderDetail(evt.raw);
       tbody.querySelectorAll("tr").forEach((r) => r.classList.remove("selected"));
       row.classList.add("selected");
     });
     tbody.appendChild(row);
   });
-
-  updatePageInfo();
 }
 
 function renderStatus(status) {
@@ -372,6 +535,11 @@ function renderDetail(raw) {
     ["metadata", raw.metadata],
     ["custom", raw.custom],
   ];
+  const hit = state.indexHits?.get(raw.event_id);
+  if (hit) {
+    const at = hit.line ? `${hit.path}:${hit.line} (byte ${hit.byte_offset})` : hit.path;
+    sections.push(["location", at]);
+  }
 
   sections.forEach(([key, value]) => {
     const card = document.createElement("div");
@@ -461,41 +629,23 @@ function matchQuery(evt, queryText) {
 }
 
 function applyFilters() {
-  const term = $("searchInput").value.toLowerCase();
-  const regexOn = $("regexToggle").value === "true";
-  const queryText = $("queryInput").value.trim();
-  const category = $("categoryFilter").value;
-  const status = $("statusFilter").value;
-  const onlyErrors = $("errorFilter").value === "true";
-  const dateFrom = $("dateFrom").value;
-  const dateTo = $("dateTo").value;
-
-  state.filtered = state.events.filter((evt) => {
-    const matchesTerm = matchSearch(evt, term, regexOn);
-    const matchesQuery = matchQuery(evt, queryText);
-
-    const matchesCategory = !category || evt.category === category;
-    const matchesStatus = !status || evt.status === status;
-    const matchesError = !onlyErrors || evt.raw?.error?.occurred === true;
-    const ts = evt.timestamp ? new Date(evt.timestamp) : null;
-    const afterFrom = !dateFrom || (ts && ts >= new Date(dateFrom));
-    const beforeTo =
-      !dateTo || (ts && ts <= new Date(dateTo + "T23:59:59"));
-
-    return (
-      matchesTerm &&
-      matchesQuery &&
-      matchesCategory &&
-      matchesStatus &&
-      matchesError &&
-      afterFrom &&
-      beforeTo
-    );
-  });
+  state.page = 1;
+  refreshUI();
+}
 
+// Rows parsed in the webview go to the store in desktop mode; the browser preview keeps them.
+async function useParsedRows(rows) {
+  if (window.__TAURI__) {
+    await window.__TAURI__.invoke("store_add_rows", { rows, append: false });
+  } else {
+    state.preview = rows;
+  }
+  state.results = null;
+  state.rejects = [];
+  state.indexHits = null;
+  state.loadedPaths = [];
+  state.schemaReports = [];
   state.page = 1;
-  sortFiltered();
-  renderChips(term, category, status, onlyErrors);
   refreshUI();
 }
 
@@ -527,22 +677,88 @@ function handleFiles(files) {
         }
         parsed.forEach((raw) => merged.push(normalizeEvent(raw, file.name)));
       });
-
-      state.events = merged;
-      state.filtered = [...state.events];
-      sortFiltered();
-      state.page = 1;
-      setStatus(`Loaded ${state.events.length} events from ${files.length} file(s)`);
-      refreshUI();
+      return useParsedRows(merged).then(() =>
+        setStatus(`Loaded ${merged.length} events from ${files.length} file(s)`)
+      );
     })
     .catch((err) => {
-      setStatus(`Failed to parse file: ${err.message}`);
+      setStatus(`Failed to parse file: ${err.message || err}`);
     });
 }
 
-function clearData() {
-  state.events = [];
-  state.filtered = [];
+async function openNativeFiles() {
+  if (!window.__TAURI__) {
+    setStatus("Native loading is available in Tauri desktop mode.");
+    return;
+  }
+  const { dialog } = window.__TAURI__;
+  const selected = await dialog.open({
+    multiple: true,
+    filters: [{ name: "Logs", extensions: ["json", "jsonl", "csv", "log", "txt", "gz", "zst", "bz2"] }],
+  });
+  if (!selected) return;
+  const paths = Array.isArray(selected) ? selected : [selected];
+  try {
+    applyLoadResults(await runJob("store_open_files", { paths, append: false }, "Loading"));
+  } catch (err) {
+    setStatus(`Failed to load file: ${err}`);
+  }
+}
+
+async function openNativeDirectory() {
+  if (!window.__TAURI__) {
+    setStatus("Directory loading is available in Tauri desktop mode.");
+    return;
+  }
+  const { dialog } = window.__TAURI__;
+  const directory = await dialog.open({ directory: true });
+  if (!directory) return;
+  const category = $("categoryFilter").value;
+  const selection = {
+    filename_pattern: $("filenamePattern").value || null,
+    date_from: $("dateFrom").value || null,
+    date_to: $("dateTo").value || null,
+    categories: category ? [category] : [],
+  };
+  try {
+    const result = await runJob("load_audit_directory", { directory, selection }, "Loading directory");
+    applyLoadResults(result);
+    if (result.failures.length > 0) {
+      setStatus(`${$("loadStatus").textContent}; ${result.failures.length} file(s) failed`);
+    }
+  } catch (err) {
+    setStatus(`Failed to load directory: ${err}`);
+  }
+}
+
+// `summary` describes what the store now holds; the events themselves stay in the backend.
+function applyLoadResults(summary) {
+  const rejects = [];
+  summary.files.forEach((file) => {
+    file.rejects.forEach((r) => rejects.push({ ...r, source: file.path }));
+  });
+  state.results = null;
+  state.rejects = rejects;
+  state.indexHits = null;
+  state.loadedPaths = summary.files.map((file) => file.path);
+  state.schemaReports = [];
+  state.page = 1;
+  const skipped = rejects.length ? `, skipped ${rejects.length} bad line(s)` : "";
+  setStatus(`Loaded ${summary.total} events from ${summary.files.length} file(s)${skipped}`);
+  refreshUI();
+}
+
+async function clearData() {
+  if (window.__TAURI__) {
+    await window.__TAURI__.invoke("store_clear");
+    await window.__TAURI__.invoke("results_clear");
+  }
+  state.preview = [];
+  state.results = null;
+  state.rejects = [];
+  state.indexHits = null;
+  state.loadedPaths = [];
+  state.schemaReports = [];
   state.page = 1;
   stopTail();
   refreshUI();
@@ -552,6 +768,28 @@ function clearData() {
   setStatus("No file loaded.");
 }
 
+// Collector, database, large-file and index pages open in the result view, so browsing them
+// never discards the loaded events.
+function showResults(source, label, rejects) {
+  state.results = { source, label, rejects };
+  state.page = 1;
+  refreshUI();
+}
+
+async function closeResults() {
+  if (!state.results) return;
+  state.results = null;
+  state.indexHits = null;
+  state.page = 1;
+  if (window.__TAURI__) await window.__TAURI__.invoke("results_clear");
+  refreshUI();
+}
+
+function renderResultBar() {
+  $("resultBar").hidden = !state.results;
+  $("resultLabel").textContent = state.results ? state.results.label : "";
+}
+
 function toggleTutorial() {
   state.tutorialOn = !state.tutorialOn;
   const panel = $("tutorialPanel");
@@ -565,27 +803,19 @@ function toggleTutorial() {
   }
 }
 
-function renderChips(
-  term = "",
-  category = "",
-  status = "",
-  onlyErrors = false
-) {
+function renderChips() {
   const chips = $("activeChips");
   chips.innerHTML = "";
   const items = [];
-  if (term) items.push(`Search: ${term}`);
-  const regexOn = $("regexToggle").value === "true";
-  if (regexOn && term) items.push("Regex: on");
-  const queryText = $("queryInput").value.trim();
-  if (queryText) items.push(`Query: ${queryText}`);
-  if (category) items.push(`Category: ${category}`);
-  if (status) items.push(`Status: ${status}`);
-  if (onlyErrors) items.push("Errors Only");
-  const dateFrom = $("dateFrom").value;
-  const dateTo = $("dateTo").value;
-  if (dateFrom) items.push(`From: ${dateFrom}`);
-  if (dateTo) items.push(`To: ${dateTo}`);
+  const filter = panelFilter();
+  if (filter.search) items.push(`Search: ${filter.search}`);
+  if (filter.regex && filter.search) items.push("Regex: on");
+  if (filter.query) items.push(`Query: ${filter.query}`);
+  if (filter.category) items.push(`Category: ${filter.category}`);
+  if (filter.status) items.push(`Status: ${filter.status}`);
+  if (filter.errors_only) items.push("Errors Only");
+  if (filter.date_from) items.push(`From: ${filter.date_from}`);
+  if (filter.date_to) items.push(`To: ${filter.date_to}`);
   if (state.sortKey) {
     items.push(`Sort: ${state.sortKey} (${state.sortDir})`);
   }
@@ -610,25 +840,26 @@ function resetFilters() {
   applyFilters();
 }
 
+const pageCount = () => Math.max(1, Math.ceil(state.total / state.pageSize));
+
 function updatePageInfo() {
-  const pages = Math.max(1, Math.ceil(state.filtered.length / state.pageSize));
+  const pages = pageCount();
   $("pageInfo").textContent = `Page ${state.page} of ${pages}`;
   $("prevPage").disabled = state.page <= 1;
   $("nextPage").disabled = state.page >= pages;
 }
 
 function nextPage() {
-  const pages = Math.max(1, Math.ceil(state.filtered.length / state.pageSize));
-  if (state.page < pages) {
+  if (state.page < pageCount()) {
     state.page += 1;
-    renderTable();
+    renderPage();
   }
 }
 
 function prevPage() {
   if (state.page > 1) {
     state.page -= 1;
-    renderTable();
+    renderPage();
   }
 }
 
@@ -683,16 +914,13 @@ function loadSample() {
   fetch("sample_logs.jsonl")
     .then((res) => res.text())
     .then((text) => {
-      const parsed = parseJSONL(text);
-      state.events = parsed.map((raw) => normalizeEvent(raw, "sample_logs.jsonl"));
-      state.filtered = [...state.events];
-      state.page = 1;
-      sortFiltered();
-      setStatus(`Loaded ${state.events.length} events from sample_logs.jsonl`);
-      refreshUI();
+      const rows = parseJSONL(text).map((raw) => normalizeEvent(raw, "sample_logs.jsonl"));
+      return useParsedRows(rows).then(() =>
+        setStatus(`Loaded ${rows.length} events from sample_logs.jsonl`)
+      );
     })
     .catch((err) => {
-      setStatus(`Failed to load sample: ${err.message}`);
+      setStatus(`Failed to load sample: ${err.message || err}`);
     });
 }
 
@@ -707,46 +935,51 @@ async function selectTailFile() {
   });
   if (path) {
     state.tail.path = path;
-    state.tail.offset = 0;
-    state.tail.buffer = "";
+    // No cursor: the backend resumes from the checkpoint saved by an earlier run, if any.
+    state.tail.cursor = null;
     $("tailStatus").textContent = `Tail: ready (${path})`;
   }
 }
 
-async function tailTick() {
-  if (!window.__TAURI__ || !state.tail.path) return;
-  const { invoke } = window.__TAURI__;
-  const result = await invoke("read_tail_chunk", {
-    path: state.tail.path,
-    offset: state.tail.offset,
-  });
-  const chunk = result[0];
-  const newOffset = result[1];
-  if (!chunk) return;
-  state.tail.offset = newOffset;
-  const combined = state.tail.buffer + chunk;
-  const lines = combined.split("\n");
-  state.tail.buffer = lines.pop() || "";
-  const newEvents = [];
-  for (const line of lines) {
-    const trimmed = line.trim();
-    if (!trimmed) continue;
-    try {
-      const raw = JSON.parse(trimmed);
-      newEvents.push(normalizeEvent(raw, state.tail.path));
-    } catch {
-      // ignore malformed lines
-    }
+function applyTailEvents(payload) {
+  if (payload.session_id) {
+    if (payload.session_id !== state.tail.sessionId) return;
+  } else {
+    if (payload.path !== state.tail.path) return;
+    state.tail.cursor = payload.cursor;
   }
-  if (newEvents.length > 0) {
-    state.events = state.events.concat(newEvents);
-    state.filtered = [...state.events];
-    sortFiltered();
-    refreshUI();
+  if (payload.transition) {
+    reportTailTransition(payload.transition, payload);
+  }
+  payload.rejects.forEach((r) => state.rejects.push({ ...r, source: payload.path }));
+  // The backend appended the batch to the store before emitting it.
+  if (payload.events > 0) refreshSoon();
+}
+
+function reportTailTransition(transition, payload) {
+  const time = new Date().toLocaleTimeString();
+  if (transition.kind === "rolled_over") {
+    $("tailStatus").textContent = `Tail: ${payload.stream} rolled over to ${payload.path} at ${time}`;
+  } else if (transition.kind === "replaced") {
+    $("tailStatus").textContent = `Tail: file replaced at ${time}, reading new file from start`;
+  } else if (transition.kind === "truncated") {
+    $("tailStatus").textContent =
+      `Tail: file truncated at ${time} (${transition.previous_offset} -> ${transition.size} bytes), restarting`;
+  } else if (transition.kind === "missing") {
+    $("tailStatus").textContent = `Tail: waiting for ${payload.path} to reappear`;
+  } else if (transition.kind === "unreachable") {
+    $("tailStatus").textContent = `Tail: ${payload.stream} unreachable at ${time} (${transition.error}), retrying`;
+  } else if (transition.kind === "unreadable") {
+    $("tailStatus").textContent = `Tail: cannot read ${payload.path} at ${time} (${transition.error}), retrying`;
   }
 }
 
-function startTail() {
+function formatMissed(resumed) {
+  const since = new Date(resumed.saved_at).toLocaleString();
+  return `${resumed.missed_lines} line(s) written since ${since}`;
+}
+
+async function startTail() {
   if (!window.__TAURI__) {
     setStatus("Live tail is available in Tauri desktop mode.");
     return;
@@ -755,22 +988,634 @@ function startTail() {
     setStatus("Select a file to tail first.");
     return;
   }
-  const interval = parseInt($("tailInterval").value, 10) || 2000;
-  if (state.tail.timer) clearInterval(state.tail.timer);
-  state.tail.timer = setInterval(tailTick, interval);
-  $("tailStatus").textContent = "Tail: running";
+  const { invoke, event } = window.__TAURI__;
+  const maxBytes = (parseInt($("tailChunkKb").value, 10) || 4096) * 1024;
+  const pollIntervalMs = $("tailForcePoll").checked
+    ? parseInt($("tailInterval").value, 10) || 2000
+    : null;
+  if (!state.tail.unlisten) {
+    state.tail.unlisten = await event.listen("tail://events", ({ payload }) => applyTailEvents(payload));
+  }
+  try {
+    const status = await invoke("tail_follow", {
+      path: state.tail.path,
+      cursor: state.tail.cursor,
+      maxBytes,
+      pollIntervalMs,
+    });
+    const mode = status.polling ? "Tail: running (polling)" : "Tail: running";
+    $("tailStatus").textContent = status.resumed
+      ? `${mode}, resumed at line ${status.cursor.line}; ${formatMissed(status.resumed)}`
+      : mode;
+  } catch (err) {
+    await stopTail();
+    $("tailStatus").textContent = `Tail: ${err}`;
+  }
+}
+
+async function followTailDirectory() {
+  if (!window.__TAURI__) {
+    setStatus("Live tail is available in Tauri desktop mode.");
+    return;
+  }
+  const { dialog, invoke, event } = window.__TAURI__;
+  const directory = await dialog.open({ directory: true });
+  if (!directory) return;
+  if (state.tail.unlisten) await stopTail();
+  const category = $("categoryFilter").value;
+  const sources = [
+    {
+      directory,
+      filename_pattern: $("filenamePattern").value || null,
+      categories: category ? [category] : [],
+    },
+  ];
+  state.tail.sessionId = `session-${Date.now()}`;
+  state.tail.unlisten = await event.listen("tail://events", ({ payload }) => applyTailEvents(payload));
+  try {
+    const status = await invoke("tail_session_start", {
+      sessionId: state.tail.sessionId,
+      sources,
+      maxBytes: (parseInt($("tailChunkKb").value, 10) || 4096) * 1024,
+      pollIntervalMs: $("tailForcePoll").checked ? parseInt($("tailInterval").value, 10) || 2000 : null,
+    });
+    const names = status.streams.map((s) => s.stream).join(", ") || "no matching files yet";
+    const resumed = status.streams.filter((s) => s.resumed);
+    const missed = resumed.length
+      ? `; resumed ${resumed.length} from checkpoint, ${formatMissed({
+          missed_lines: resumed.reduce((sum, s) => sum + s.resumed.missed_lines, 0),
+          saved_at: resumed.map((s) => s.resumed.saved_at).sort()[0],
+        })}`
+      : "";
+    $("tailStatus").textContent = `Tail: following ${status.streams.length} stream(s): ${names}${missed}`;
+  } catch (err) {
+    await stopTail();
+    $("tailStatus").textContent = `Tail: ${err}`;
+  }
+}
+
+function collectorConfig() {
+  const baseUrl = $("collectorUrl").value.trim();
+  localStorage.setItem("audit_collector_url", baseUrl);
+  return { base_url: baseUrl, api_key: $("collectorKey").value || null };
+}
+
+function collectorFilters() {
+  return {
+    action_category: $("categoryFilter").value || null,
+    result_status: $("statusFilter").value || null,
+    application: $("collectorApplication").value.trim() || null,
+    environment: $("collectorEnvironment").value.trim() || null,
+    actor_username: $("collectorActor").value.trim() || null,
+  };
+}
+
+function panelFilter() {
+  return {
+    search: $("searchInput").value,
+    regex: $("regexToggle").value === "true",
+    query: $("queryInput").value.trim(),
+    category: $("categoryFilter").value || null,
+    status: $("statusFilter").value || null,
+    errors_only: $("errorFilter").value === "true",
+    date_from: $("dateFrom").value || null,
+    date_to: $("dateTo").value || null,
+  };
+}
+
+// Only the latest collector, database, large-file or index request may update the view.
+let loadSeq = 0;
+
+async function browseCollector(page = 1) {
+  if (!window.__TAURI__) {
+    setStatus("Collector browsing is available in Tauri desktop mode.");
+    return;
+  }
+  const seq = ++loadSeq;
+  try {
+    const result = await window.__TAURI__.invoke("collector_query", {
+      collector: collectorConfig(),
+      filter: panelFilter(),
+      extra: collectorFilters(),
+      page,
+      pageSize: parseInt($("collectorPageSize").value, 10) || 200,
+    });
+    if (seq !== loadSeq) return;
+    state.collector = {
+      page: result.page,
+      pages: Math.max(1, Math.ceil(result.total / result.page_size)),
+    };
+    state.indexHits = null;
+    showResults(
+      "collector",
+      `Collector page ${state.collector.page} of ${state.collector.pages}`,
+      result.rejects.map((r) => ({ ...r, source: "collector" })),
+    );
+    const ignored = result.ignored.length ? ` · applied locally only: ${result.ignored.join(", ")}` : "";
+    $("collectorStatus").textContent =
+      `Collector: page ${state.collector.page} of ${state.collector.pages} (${result.total} events)${ignored}`;
+    $("collectorPrev").disabled = state.collector.page <= 1;
+    $("collectorNext").disabled = state.collector.page >= state.collector.pages;
+  } catch (err) {
+    if (seq === loadSeq) $("collectorStatus").textContent = `Collector: ${err}`;
+  }
+}
+
+async function openDatabase() {
+  if (!window.__TAURI__) {
+    setStatus("SQL storage databases can be opened in Tauri desktop mode.");
+    return;
+  }
+  const path = await window.__TAURI__.dialog.open({
+    filters: [{ name: "SQLite", extensions: ["db", "sqlite", "sqlite3"] }],
+  });
+  if (!path) return;
+  state.database = { path, page: 1, pages: 1 };
+  await browseDatabase(1);
+}
+
+async function browseDatabase(page = 1) {
+  if (!state.database.path) return;
+  const seq = ++loadSeq;
+  try {
+    const result = await runJob(
+      "sqlite_query",
+      {
+        path: state.database.path,
+        filter: panelFilter(),
+        extra: {
+          application: $("dbApplication").value.trim() || null,
+          environment: $("dbEnvironment").value.trim() || null,
+          actor_username: $("dbActor").value.trim() || null,
+        },
+        page,
+        pageSize: parseInt($("dbPageSize").value, 10) || 1000,
+      },
+      "Reading database",
+    );
+    if (seq !== loadSeq) return;
+    state.database.page = result.page;
+    state.database.pages = Math.max(1, Math.ceil(result.total / result.page_size));
+    state.indexHits = null;
+    showResults(
+      "database",
+      `Database page ${state.database.page} of ${state.database.pages}: ${result.path}`,
+      result.rejects.map((r) => ({ ...r, source: result.path })),
+    );
+    const ignored = result.ignored.length ? ` · applied locally only: ${result.ignored.join(", ")}` : "";
+    $("dbStatus").textContent =
+      `Database: page ${state.database.page} of ${state.database.pages} (${result.total} events)${ignored}`;
+    $("dbQuery").disabled = false;
+    $("dbPrev").disabled = state.database.page <= 1;
+    $("dbNext").disabled = state.database.page >= state.database.pages;
+  } catch (err) {
+    if (seq === loadSeq) $("dbStatus").textContent = `Database: ${err}`;
+  }
+}
+
+async function openLargeFile() {
+  if (!window.__TAURI__) {
+    setStatus("Large files can be paged in Tauri desktop mode.");
+    return;
+  }
+  const path = await window.__TAURI__.dialog.open({
+    filters: [{ name: "JSON Lines", extensions: ["jsonl", "log", "txt"] }],
+  });
+  if (!path) return;
+  const seq = ++loadSeq;
+  try {
+    const summary = await runJob("index_audit_file", { path }, "Indexing");
+    if (seq !== loadSeq) return;
+    state.largeFile = { path, page: 1, pages: 1 };
+    const cached = summary.from_cache ? " (cached index)" : "";
+    setStatus(`Indexed ${summary.lines} lines of ${path}${cached}`);
+  } catch (err) {
+    if (seq === loadSeq) $("largeStatus").textContent = `Large file: ${err}`;
+    return;
+  }
+  await browseLargeFile(1);
+}
+
+async function browseLargeFile(page = 1) {
+  if (!state.largeFile.path) return;
+  const seq = ++loadSeq;
+  try {
+    const result = await runJob(
+      "read_indexed_page",
+      {
+        path: state.largeFile.path,
+        page,
+        pageSize: parseInt($("largePageSize").value, 10) || 1000,
+      },
+      "Reading page",
+    );
+    if (seq !== loadSeq) return;
+    state.largeFile.page = result.page;
+    state.largeFile.pages = Math.max(1, Math.ceil(result.total / result.page_size));
+    state.indexHits = null;
+    showResults(
+      "large-file",
+      `Large file page ${state.largeFile.page} of ${state.largeFile.pages}: ${result.path}`,
+      result.rejects.map((r) => ({ ...r, source: result.path })),
+    );
+    $("largeStatus").textContent =
+      `Large file: page ${state.largeFile.page} of ${state.largeFile.pages} (${result.total} lines)`;
+    $("largePrev").disabled = state.largeFile.page <= 1;
+    $("largeNext").disabled = state.largeFile.page >= state.largeFile.pages;
+  } catch (err) {
+    if (seq === loadSeq) $("largeStatus").textContent = `Large file: ${err}`;
+  }
+}
+
+async function openCollectorEvent(evt) {
+  try {
+    const full = await window.__TAURI__.invoke("collector_get_event", {
+      collector: collectorConfig(),
+      eventId: evt.event_id,
+    });
+    renderDetail(full.raw);
+  } catch (err) {
+    renderDetail(evt.raw);
+    setStatus(`Could not fetch ${evt.event_id} from the collector: ${err}`);
+  }
+}
+
+function formatSilence(secs) {
+  if (secs === null || secs === undefined) return "unknown";
+  if (secs < 120) return `${secs}s ago`;
+  if (secs < 7200) return `${Math.round(secs / 60)}m ago`;
+  return `${Math.round(secs / 3600)}h ago`;
+}
+
+async function refreshFleet() {
+  if (!window.__TAURI__) {
+    setStatus("Fleet view is available in Tauri desktop mode.");
+    return;
+  }
+  const panel = $("fleetPanel");
+  const list = $("fleetList");
+  try {
+    const fleet = await window.__TAURI__.invoke("fleet_status", {
+      collector: collectorConfig(),
+      staleAfter: parseFloat($("fleetStaleAfter").value) || null,
+    });
+    const stale = fleet.agents.filter((a) => a.stale).length;
+    panel.innerHTML = "";
+    [
+      ["Agents", fleet.agents.length],
+      ["Reporting", fleet.agents.length - stale],
+      ["Stale", stale],
+    ].forEach(([label, value]) => {
+      const card = document.createElement("div");
+      card.className = "summary-card";
+      card.innerHTML = `<h3>${label}</h3><div class="value">${value}</div>`;
+      panel.appendChild(card);
+    });
+    list.innerHTML = "";
+    $("fleetHistory").innerHTML = "";
+    fleet.agents.forEach((agent) => {
+      const row = document.createElement("div");
+      row.className = "integrity-row fleet-row" + (agent.stale ? " bad" : "");
+      const interval = agent.metrics_error
+        ? "metrics unavailable"
+        : `every ${Math.round(agent.interval_secs)}s${agent.interval_estimated ? "" : " (default)"}`;
+      row.innerHTML = `<div>${agent.hostname}</div><div>${formatSilence(agent.silent_secs)}</div><div>${interval}</div><div>${agent.stale ? "STALE" : "OK"}</div>`;
+      row.title = agent.metrics_error ? `${agent.agent_id}: ${agent.metrics_error}` : agent.agent_id;
+      row.addEventListener("click", () => showAgentMetrics(agent));
+      list.appendChild(row);
+    });
+    $("fleetTitle").textContent = `Checked ${new Date(fleet.checked_at).toLocaleTimeString()}`;
+  } catch (err) {
+    $("fleetTitle").textContent = `Fleet: ${err}`;
+  }
+}
+
+let metricsSeq = 0;
+
+async function showAgentMetrics(agent) {
+  const history = $("fleetHistory");
+  const seq = ++metricsSeq;
+  try {
+    const samples = await window.__TAURI__.invoke("fleet_agent_metrics", {
+      collector: collectorConfig(),
+      agentId: agent.agent_id,
+      limit: 200,
+    });
+    if (seq !== metricsSeq) return;
+    history.innerHTML = "";
+    if (samples.length === 0) {
+      history.innerHTML = `<div class='meta'>${agent.hostname} has not sent any metrics.</div>`;
+      return;
+    }
+    const pct = (v) => (typeof v === "number" ? `${v.toFixed(1)}%` : "-");
+    samples.slice().reverse().forEach((s) => {
+      const m = s.metrics || {};
+      const row = document.createElement("div");
+      row.className = "integrity-row";
+      row.innerHTML = `<div>${s.timestamp}</div><div>CPU ${pct(m.cpu_percent)}</div><div>Mem ${pct(m.memory_percent)}</div><div>Disk ${pct(m.disk_percent)}</div>`;
+      history.appendChild(row);
+    });
+  } catch (err) {
+    if (seq !== metricsSeq) return;
+    history.innerHTML = "";
+    setStatus(`Could not fetch metrics for ${agent.hostname}: ${err}`);
+  }
+}
+
+function indexWorkspace() {
+  return $("indexWorkspace").value.trim() || "default";
+}
+
+function showIndexStatus(status) {
+  const error = status.error ? ` (live updates failed: ${status.error})` : "";
+  $("indexStatus").textContent =
+    `Index "${status.workspace}": ${status.documents} events from ${status.sources.length} file(s)${error}`;
+}
+
+async function indexLoadedFiles() {
+  if (!window.__TAURI__) {
+    setStatus("The full-text index is available in Tauri desktop mode.");
+    return;
+  }
+  let paths = state.loadedPaths.concat(state.tail.path ? [state.tail.path] : []);
+  if (paths.length === 0) {
+    const selected = await window.__TAURI__.dialog.open({
+      multiple: true,
+      filters: [{ name: "Logs", extensions: ["json", "jsonl", "csv", "log", "txt", "gz", "zst", "bz2"] }],
+    });
+    if (!selected) return;
+    paths = Array.isArray(selected) ? selected : [selected];
+  }
+  try {
+    const status = await runJob(
+      "search_index_files",
+      { workspace: indexWorkspace(), paths: [...new Set(paths)] },
+      "Indexing",
+    );
+    showIndexStatus(status);
+    setStatus("Indexing finished.");
+  } catch (err) {
+    $("indexStatus").textContent = `Index: ${err}`;
+  }
+}
+
+async function clearIndex() {
+  if (!window.__TAURI__) return;
+  try {
+    showIndexStatus(await window.__TAURI__.invoke("search_index_clear", { workspace: indexWorkspace() }));
+  } catch (err) {
+    $("indexStatus").textContent = `Index: ${err}`;
+  }
+}
+
+async function searchIndex() {
+  if (!window.__TAURI__) {
+    setStatus("The full-text index is available in Tauri desktop mode.");
+    return;
+  }
+  const seq = ++loadSeq;
+  try {
+    const result = await window.__TAURI__.invoke("search_events", {
+      workspace: indexWorkspace(),
+      query: $("indexQuery").value.trim(),
+      limit: 5000,
+    });
+    if (seq !== loadSeq) return;
+    state.indexHits = new Map(result.hits.map((hit) => [hit.event_id, hit]));
+    showResults(
+      "index",
+      `Index search: ${result.events} of ${result.total} matches`,
+      result.rejects.map((r) => ({ ...r, source: "index" })),
+    );
+    const unreadable = result.rejects.length ? `, ${result.rejects.length} unreadable` : "";
+    $("indexStatus").textContent =
+      `Index: showing ${result.events} of ${result.total} matches, newest first${unreadable}`;
+  } catch (err) {
+    if (seq === loadSeq) $("indexStatus").textContent = `Index: ${err}`;
+  }
+}
+
+function renderSqlResult(result) {
+  const table = $("sqlResult");
+  table.innerHTML = "";
+  const head = table.createTHead().insertRow();
+  result.columns.forEach((name) => {
+    const th = document.createElement("th");
+    th.textContent = name;
+    head.appendChild(th);
+  });
+  const body = table.createTBody();
+  result.rows.forEach((values) => {
+    const tr = body.insertRow();
+    values.forEach((value) => {
+      tr.insertCell().textContent = value === null ? "" : String(value);
+    });
+  });
+  state.sql.page = result.page;
+  state.sql.pages = Math.max(1, Math.ceil(result.total / result.page_size));
+  $("sqlPageInfo").textContent = `Page ${result.page} of ${state.sql.pages} (${result.total} rows)`;
+  $("sqlTitle").textContent = `${result.table_rows} events, ${result.elapsed_ms} ms`;
+}
+
+let sqlSeq = 0;
+
+async function runSql(page = 1) {
+  if (!window.__TAURI__) {
+    setStatus("The SQL console is available in Tauri desktop mode.");
+    return;
+  }
+  const seq = ++sqlSeq;
+  try {
+    const result = await runJob("run_sql", { sql: $("sqlInput").value, page, pageSize: 50 }, "Running SQL");
+    if (seq !== sqlSeq) return;
+    renderSqlResult(result);
+    setStatus("SQL finished.");
+  } catch (err) {
+    if (seq !== sqlSeq) return;
+    $("sqlPageInfo").textContent = `SQL: ${err}`;
+    setStatus("SQL failed.");
+  }
+}
+
+async function exportSql() {
+  if (!window.__TAURI__) {
+    setStatus("The SQL console is available in Tauri desktop mode.");
+    return;
+  }
+  const path = await window.__TAURI__.dialog.save({
+    defaultPath: "audit_query.csv",
+    filters: [
+      { name: "CSV", extensions: ["csv"] },
+      { name: "JSON", extensions: ["json"] },
+      { name: "JSON Lines", extensions: ["jsonl"] },
+    ],
+  });
+  if (!path) return;
+  try {
+    const result = await runJob("export_sql", { sql: $("sqlInput").value, path }, "Exporting SQL result");
+    setStatus(`Exported ${result.rows} rows to ${result.path}.`);
+  } catch (err) {
+    $("sqlPageInfo").textContent = `SQL: ${err}`;
+    setStatus("SQL export failed.");
+  }
+}
+
+async function replayToCollector() {
+  if (!window.__TAURI__) {
+    setStatus("Collector replay is available in Tauri desktop mode.");
+    return;
+  }
+  const { dialog, event } = window.__TAURI__;
+  let paths = state.loadedPaths;
+  if (paths.length === 0) {
+    const selected = await dialog.open({
+      multiple: true,
+      filters: [{ name: "Logs", extensions: ["json", "jsonl", "csv", "log", "txt", "gz", "zst", "bz2"] }],
+    });
+    if (!selected) return;
+    paths = Array.isArray(selected) ? selected : [selected];
+  }
+  let failed = 0;
+  const unlisten = await event.listen("replay://batch", ({ payload }) => {
+    failed += payload.errors.length;
+    const name = payload.path.split(/[\\/]/).pop();
+    $("collectorStatus").textContent =
+      `Replay: ${name} batch ${payload.batch}, ${payload.accepted}/${payload.sent} accepted` +
+      (failed ? ` · ${failed} rejected so far` : "");
+  });
+  try {
+    const summary = await runJob(
+      "collector_replay",
+      { collector: collectorConfig(), paths, restart: $("replayRestart").checked },
+      "Replaying",
+    );
+    const resumed = summary.files.filter((f) => f.resumed_at > 0).length;
+    $("collectorStatus").textContent =
+      `Replay: ${summary.accepted} accepted, ${summary.failed} rejected across ${summary.files.length} file(s)` +
+      (resumed ? ` (${resumed} resumed from the journal)` : "");
+    setStatus(`Replayed ${paths.length} file(s) to ${summary.base_url}`);
+  } catch (err) {
+    $("collectorStatus").textContent = `Replay stopped: ${err}. Run it again to continue.`;
+  } finally {
+    unlisten();
+  }
+}
+
+function renderHealth(summary, samples) {
+  const panel = $("healthPanel");
+  const latest = samples[samples.length - 1];
+  const fmt = (v, unit) => (v === null || v === undefined ? "-" : `${v.toFixed(1)}${unit}`);
+  panel.innerHTML = "";
+  [
+    ["Ready", latest ? (latest.ready ? "yes" : "NO") : "-"],
+    ["Requests/s", fmt(summary.avg_request_rate, "")],
+    ["5xx errors", summary.new_errors],
+    ["Latency", `${fmt(summary.avg_latency_ms, " ms")} (max ${summary.max_latency_ms ?? "-"})`],
+    ["Ready flaps", summary.ready_flaps],
+    ["Unreachable", summary.unreachable],
+  ].forEach(([label, value]) => {
+    const card = document.createElement("div");
+    card.className = "summary-card";
+    card.innerHTML = `<h3>${label}</h3><div class="value">${value}</div>`;
+    panel.appendChild(card);
+  });
+  const list = $("healthList");
+  list.innerHTML = "";
+  samples.slice(-50).reverse().forEach((s) => {
+    const row = document.createElement("div");
+    row.className = "integrity-row" + (s.ready && !s.error ? "" : " bad");
+    const status = s.error || (s.ready ? "ready" : `not ready: ${s.database}`);
+    const rate = s.request_rate === null ? "-" : `${s.request_rate.toFixed(2)} req/s`;
+    row.innerHTML = `<div>${new Date(s.at).toLocaleTimeString()}</div><div>${status}</div><div>${rate}</div><div>${s.latency_ms ?? "-"} ms</div>`;
+    list.appendChild(row);
+  });
+}
+
+async function toggleHealthMonitor() {
+  if (!window.__TAURI__) {
+    setStatus("Collector health is available in Tauri desktop mode.");
+    return;
+  }
+  const { invoke, event } = window.__TAURI__;
+  if (state.health.monitorId) {
+    await invoke("collector_health_stop", { monitorId: state.health.monitorId });
+    state.health.unlisten?.();
+    state.health = { monitorId: null, unlisten: null };
+    $("healthToggle").textContent = "Start Monitoring";
+    $("healthTitle").textContent = "Not monitoring";
+    return;
+  }
+  const monitorId = `health-${Date.now()}`;
+  const samples = [];
+  state.health.monitorId = monitorId;
+  state.health.unlisten = await event.listen("collector://health", ({ payload }) => {
+    if (payload.monitor_id !== monitorId) return;
+    samples.push(payload.sample);
+    if (samples.length > 50) samples.shift();
+    renderHealth(payload.summary, samples);
+  });
+  try {
+    const collector = collectorConfig();
+    await invoke("collector_health_start", {
+      monitorId,
+      collector,
+      pollIntervalMs: parseInt($("healthInterval").value, 10) || 10000,
+    });
+    $("healthToggle").textContent = "Stop Monitoring";
+    $("healthTitle").textContent = `Polling ${collector.base_url}`;
+  } catch (err) {
+    state.health.unlisten();
+    state.health = { monitorId: null, unlisten: null };
+    $("healthTitle").textContent = `Health: ${err}`;
+  }
 }
 
-function stopTail() {
-  if (state.tail.timer) {
-    clearInterval(state.tail.timer);
-    state.tail.timer = null;
+async function tailCollector() {
+  if (!window.__TAURI__) {
+    setStatus("Collector tail is available in Tauri desktop mode.");
+    return;
+  }
+  const { invoke, event } = window.__TAURI__;
+  if (state.tail.unlisten) await stopTail();
+  const tailId = `remote-${Date.now()}`;
+  state.tail.sessionId = tailId;
+  state.tail.remoteId = tailId;
+  state.tail.unlisten = await event.listen("tail://events", ({ payload }) => applyTailEvents(payload));
+  try {
+    const status = await invoke("remote_tail_start", {
+      tailId,
+      collector: collectorConfig(),
+      filters: collectorFilters(),
+      pollIntervalMs: parseInt($("tailInterval").value, 10) || 5000,
+    });
+    $("tailStatus").textContent = `Tail: polling ${status.base_url} every ${status.poll_interval_ms} ms`;
+  } catch (err) {
+    await stopTail();
+    $("tailStatus").textContent = `Tail: ${err}`;
+  }
+}
+
+async function stopTail() {
+  if (state.tail.unlisten) {
+    state.tail.unlisten();
+    state.tail.unlisten = null;
+  }
+  if (window.__TAURI__ && state.tail.remoteId) {
+    await window.__TAURI__.invoke("remote_tail_stop", { tailId: state.tail.remoteId });
+    state.tail.remoteId = null;
+    state.tail.sessionId = null;
+  } else if (window.__TAURI__ && state.tail.sessionId) {
+    await window.__TAURI__.invoke("tail_session_stop", { sessionId: state.tail.sessionId });
+    state.tail.sessionId = null;
+  } else if (window.__TAURI__ && state.tail.path) {
+    const status = await window.__TAURI__.invoke("tail_unfollow", { path: state.tail.path });
+    if (status) state.tail.cursor = status.cursor;
   }
   $("tailStatus").textContent = "Tail: stopped";
 }
 
 function toggleTail() {
-  if (state.tail.timer) stopTail();
+  if (state.tail.unlisten) stopTail();
   else startTail();
 }
 
@@ -944,9 +1789,9 @@ function toggleHelp(force) {
   else modal.classList.toggle("active");
 }
 
-function sortFiltered() {
+function sortPreview(rows) {
   const { sortKey, sortDir } = state;
-  state.filtered.sort((a, b) => {
+  rows.sort((a, b) => {
     const av = (a[sortKey] || "").toString();
     const bv = (b[sortKey] || "").toString();
     if (sortKey === "timestamp") {
@@ -994,18 +1839,8 @@ function fnv1aHex(input) {
   return hash.toString(16).padStart(8, "0");
 }
 
-async function computeIntegrity() {
-  const panel = $("integrityPanel");
-  const list = $("integrityList");
-  panel.innerHTML = "";
-  list.innerHTML = "";
-  const data = state.filtered.length ? state.filtered : state.events;
-  if (data.length === 0) {
-    panel.innerHTML = "<div class='meta'>No data loaded.</div>";
-    return;
-  }
-  const hashMode = isCryptoAvailable() ? "SHA-256" : "FNV-1a (fallback)";
-
+// Browser-preview stand-in for the `integrity_chain` command.
+async function previewIntegrity(data) {
   const ordered = [...data].sort((a, b) => {
     const ad = new Date(a.timestamp || 0).getTime() || 0;
     const bd = new Date(b.timestamp || 0).getTime() || 0;
@@ -1014,27 +1849,58 @@ async function computeIntegrity() {
 
   let prev = "0";
   let mismatches = 0;
-  const rows = [];
+  const links = [];
   for (const evt of ordered) {
     const payload = stableStringify({ prev, event: evt.raw });
     const hash = await sha256Hex(payload);
-    evt.computed_hash = hash;
     const prevMismatch = evt.raw?.prev_hash && evt.raw.prev_hash !== prev;
     const hashMismatch = evt.raw?.hash && evt.raw.hash !== hash;
     if (prevMismatch || hashMismatch) mismatches += 1;
-    rows.push({
-      ts: evt.timestamp || "",
+    links.push({
+      timestamp: evt.timestamp || "",
       prev: prev.slice(0, 12),
       hash: hash.slice(0, 12),
       ok: !(prevMismatch || hashMismatch),
     });
     prev = hash;
   }
+  return { chain_hash: prev, events: ordered.length, mismatches, links: links.slice(-100) };
+}
+
+let integritySeq = 0;
+
+async function computeIntegrity() {
+  const seq = ++integritySeq;
+  const panel = $("integrityPanel");
+  const list = $("integrityList");
+  let report;
+  let hashMode = "SHA-256";
+  try {
+    if (window.__TAURI__) {
+      report = await window.__TAURI__.invoke("integrity_chain", {
+        results: Boolean(state.results),
+        filter: panelFilter(),
+      });
+    } else {
+      report = await previewIntegrity(previewMatches(panelFilter()));
+      if (!isCryptoAvailable()) hashMode = "FNV-1a (fallback)";
+    }
+  } catch (err) {
+    setStatus(`Integrity check failed: ${err}`);
+    return;
+  }
+  if (seq !== integritySeq) return;
+  panel.innerHTML = "";
+  list.innerHTML = "";
+  if (report.events === 0) {
+    panel.innerHTML = "<div class='meta'>No data loaded.</div>";
+    return;
+  }
 
   const cards = [
-    ["Chain Hash", prev.slice(0, 16) + "..."],
-    ["Events", ordered.length],
-    ["Mismatches", mismatches],
+    ["Chain Hash", report.chain_hash.slice(0, 16) + "..."],
+    ["Events", report.events],
+    ["Mismatches", report.mismatches],
     [
       "Hash Mode <span class=\"info\" title=\"If SHA-256 is unavailable, the app uses a non-cryptographic fallback for display-only integrity.\"><svg viewBox=\"0 0 24 24\" class=\"info-icon\" aria-hidden=\"true\"><circle cx=\"12\" cy=\"12\" r=\"10\" stroke=\"currentColor\" stroke-width=\"2\" fill=\"none\" /><line x1=\"12\" y1=\"10\" x2=\"12\" y2=\"16\" stroke=\"currentColor\" stroke-width=\"2\" /><circle cx=\"12\" cy=\"7\" r=\"1\" fill=\"currentColor\" /></svg></span>",
       hashMode,
@@ -1047,10 +1913,10 @@ async function computeIntegrity() {
     panel.appendChild(card);
   });
 
-  rows.slice(-100).forEach((r) => {
+  report.links.forEach((r) => {
     const row = document.createElement("div");
     row.className = "integrity-row" + (r.ok ? "" : " bad");
-    row.innerHTML = `<div>${r.ts || "-"}</div><div>${r.prev}</div><div>${r.hash}</div><div>${r.ok ? "OK" : "BAD"}</div>`;
+    row.innerHTML = `<div>${r.timestamp || "-"}</div><div>${r.prev}</div><div>${r.hash}</div><div>${r.ok ? "OK" : "BAD"}</div>`;
     list.appendChild(row);
   });
 }
@@ -1070,45 +1936,54 @@ function setupSorting() {
         .forEach((el) => el.classList.remove("sorted", "asc"));
       th.classList.add("sorted");
       if (state.sortDir === "asc") th.classList.add("asc");
-      sortFiltered();
-      renderTable();
+      renderPage();
     });
   });
 }
 
-function buildReportHtml() {
-  const total = state.filtered.length;
-  const success = state.filtered.filter((e) => e.status === "SUCCESS").length;
-  const failure = state.filtered.filter((e) => e.status === "FAILURE").length;
-  const byCategory = {};
-  const byUser = {};
-  const byAction = {};
-  state.filtered.forEach((e) => {
-    const cat = e.category || "UNSPECIFIED";
-    byCategory[cat] = (byCategory[cat] || 0) + 1;
-    const user = e.user || "UNKNOWN";
-    byUser[user] = (byUser[user] || 0) + 1;
-    const action = e.action || "UNKNOWN";
-    byAction[action] = (byAction[action] || 0) + 1;
+// Counts for the whole filtered set plus its first `limit` rows in table order.
+async function reportData(limit) {
+  const fields = ["status", "category", "user", "action"];
+  const [byStatus, byCategory, byUser, byAction] = window.__TAURI__
+    ? await aggregateEvents(fields.map((field) => ({ group_by: [field] })))
+    : previewGroups(fields);
+  const entries = (agg, fallback) => agg.groups.map((g) => [g.keys[0] || fallback, g.count]);
+  const page = await fetchPage(panelFilter(), 1, limit);
+  return {
+    total: byStatus.matched,
+    success: countOf(byStatus, "SUCCESS"),
+    failure: countOf(byStatus, "FAILURE"),
+    top_categories: entries(byCategory, "UNSPECIFIED"),
+    top_users: entries(byUser, "UNKNOWN"),
+    top_actions: entries(byAction, "UNKNOWN"),
+    rows: page.rows.map((e) => ({
+      timestamp: e.timestamp || "",
+      action: e.action || "",
+      category: e.category || "",
+      user: e.user || "",
+      status: e.status || "",
+    })),
+  };
+}
+
+// The printable report still works in the browser preview: plain counts of its rows.
+function previewGroups(fields) {
+  const rows = previewMatches(panelFilter());
+  return fields.map((field) => {
+    const counts = new Map();
+    rows.forEach((evt) => counts.set(evt[field], (counts.get(evt[field]) || 0) + 1));
+    const groups = [...counts].map(([key, count]) => ({ keys: [key], count }));
+    groups.sort((a, b) => b.count - a.count);
+    return { matched: rows.length, groups };
   });
+}
 
-  const topCats = Object.entries(byCategory)
-    .sort((a, b) => b[1] - a[1])
-    .slice(0, 5);
-  const topUsers = Object.entries(byUser)
-    .sort((a, b) => b[1] - a[1])
-    .slice(0, 5);
-  const topActions = Object.entries(byAction)
-    .sort((a, b) => b[1] - a[1])
-    .slice(0, 5);
-
-  const rows = state.filtered.slice(0, 200).map((e) => ({
-    timestamp: e.timestamp,
-    action: e.action,
-    category: e.category,
-    user: e.user,
-    status: e.status,
-  }));
+async function buildReportHtml() {
+  const report = await reportData(200);
+  const { total, success, failure, rows } = report;
+  const topCats = report.top_categories.slice(0, 5);
+  const topUsers = report.top_users.slice(0, 5);
+  const topActions = report.top_actions.slice(0, 5);
 
   const tableRows = rows
     .map(
@@ -1147,7 +2022,7 @@ function buildReportHtml() {
     <div class="card"><h3>Total Events</h3><div class="value">${total}</div></div>
     <div class="card"><h3>Success</h3><div class="value">${success}</div></div>
     <div class="card"><h3>Failure</h3><div class="value">${failure}</div></div>
-    <div class="card"><h3>Categories</h3><div class="value">${Object.keys(byCategory).length}</div></div>
+    <div class="card"><h3>Categories</h3><div class="value">${report.top_categories.length}</div></div>
   </div>
 
   <div class="section">
@@ -1176,10 +2051,11 @@ function buildReportHtml() {
 </html>`;
 }
 
-function printReport() {
+async function printReport() {
+  const html = await buildReportHtml();
   const printWindow = window.open("", "print");
   if (!printWindow) return;
-  printWindow.document.write(buildReportHtml());
+  printWindow.document.write(html);
   printWindow.document.close();
   printWindow.focus();
   printWindow.print();
@@ -1195,13 +2071,34 @@ function downloadFile(name, content, type) {
   document.body.removeChild(link);
 }
 
+// Desktop mode writes the store's filtered events straight to disk.
+async function exportEvents(format, name) {
+  const path = await window.__TAURI__.dialog.save({
+    defaultPath: name,
+    filters: [{ name: format.toUpperCase(), extensions: [format] }],
+  });
+  if (!path) return;
+  try {
+    const count = await runJob(
+      "export_events",
+      { results: Boolean(state.results), filter: panelFilter(), sort: sortSpec(), path, format },
+      "Exporting",
+    );
+    setStatus(`Exported ${count} events to ${path}.`);
+  } catch (err) {
+    setStatus(`Export failed: ${err}`);
+  }
+}
+
 function exportJson() {
-  const payload = state.filtered.map((e) => e.raw);
+  if (window.__TAURI__) return exportEvents("json", "audit_export.json");
+  const payload = previewMatches(panelFilter()).map((e) => e.raw);
   downloadFile("audit_export.json", JSON.stringify(payload, null, 2), "application/json");
 }
 
 function exportCsv() {
-  const rows = state.filtered.map((e) => ({
+  if (window.__TAURI__) return exportEvents("csv", "audit_export.csv");
+  const rows = previewMatches(panelFilter()).map((e) => ({
     event_id: e.event_id,
     timestamp: e.timestamp,
     action: e.action,
@@ -1216,8 +2113,18 @@ function exportCsv() {
   downloadFile("audit_export.csv", lines.join("\n"), "text/csv");
 }
 
-function exportReportHtml() {
-  const html = buildReportHtml();
+function exportRejects() {
+  const rejects = state.results ? state.results.rejects : state.rejects;
+  downloadFile("audit_rejects.json", JSON.stringify(rejects, null, 2), "application/json");
+}
+
+function exportSchemaReport() {
+  const invalid = state.schemaReports.flatMap((r) => r.invalid);
+  downloadFile("audit_schema_report.json", JSON.stringify(invalid, null, 2), "application/json");
+}
+
+async function exportReportHtml() {
+  const html = await buildReportHtml();
   downloadFile("audit_report.html", html, "text/html");
 }
 
@@ -1226,43 +2133,21 @@ async function exportPdf() {
     alert("PDF export is available in the Tauri desktop app.");
     return;
   }
-  const { dialog, invoke } = window.__TAURI__;
+  const { dialog } = window.__TAURI__;
   const path = await dialog.save({
     defaultPath: "audit_report.pdf",
     filters: [{ name: "PDF", extensions: ["pdf"] }],
   });
   if (!path) return;
 
-  const byCategory = {};
-  const byUser = {};
-  const byAction = {};
-  state.filtered.forEach((e) => {
-    const cat = e.category || "UNSPECIFIED";
-    byCategory[cat] = (byCategory[cat] || 0) + 1;
-    const user = e.user || "UNKNOWN";
-    byUser[user] = (byUser[user] || 0) + 1;
-    const action = e.action || "UNKNOWN";
-    byAction[action] = (byAction[action] || 0) + 1;
-  });
-
-  const payload = {
-    total: state.filtered.length,
-    success: state.filtered.filter((e) => e.status === "SUCCESS").length,
-    failure: state.filtered.filter((e) => e.status === "FAILURE").length,
-    top_categories: Object.entries(byCategory),
-    top_users: Object.entries(byUser),
-    top_actions: Object.entries(byAction),
-    rows: state.filtered.slice(0, 100).map((e) => ({
-      timestamp: e.timestamp || "",
-      action: e.action || "",
-      category: e.category || "",
-      user: e.user || "",
-      status: e.status || "",
-    })),
-  };
-
-  await invoke("generate_pdf_report", { path, payload });
-  alert("PDF report saved.");
+  try {
+    const payload = await reportData(1000);
+    await runJob("generate_pdf_report", { path, payload }, "Rendering PDF");
+    setStatus("PDF report saved.");
+    alert("PDF report saved.");
+  } catch (err) {
+    setStatus(`PDF export failed: ${err}`);
+  }
 }
 
 function generateWizardYaml() {
@@ -1305,14 +2190,21 @@ function downloadWizardYaml() {
 document.addEventListener("DOMContentLoaded", () => {
   renderFieldGuide();
   $("fileInput").addEventListener("change", (e) => handleFiles(e.target.files));
+  $("openNative").addEventListener("click", openNativeFiles);
+  $("openDirectory").addEventListener("click", openNativeDirectory);
   $("applyFilters").addEventListener("click", applyFilters);
   $("clearData").addEventListener("click", clearData);
+  $("closeResults").addEventListener("click", closeResults);
   $("resetFilters").addEventListener("click", resetFilters);
   $("tutorialToggle").addEventListener("click", toggleTutorial);
   $("exportJson").addEventListener("click", exportJson);
   $("exportCsv").addEventListener("click", exportCsv);
   $("exportReport").addEventListener("click", exportReportHtml);
   $("exportPdf").addEventListener("click", exportPdf);
+  $("cancelJob").addEventListener("click", cancelJob);
+  $("exportRejects").addEventListener("click", exportRejects);
+  $("validateSchema").addEventListener("click", validateSchema);
+  $("exportSchemaReport").addEventListener("click", exportSchemaReport);
   $("wizardGenerate").addEventListener("click", generateWizardYaml);
   $("wizardDownload").addEventListener("click", downloadWizardYaml);
   $("prevPage").addEventListener("click", prevPage);
@@ -1327,6 +2219,37 @@ document.addEventListener("DOMContentLoaded", () => {
   $("noteEvent").addEventListener("click", renderNoteBox);
   $("tailSelect").addEventListener("click", selectTailFile);
   $("tailToggle").addEventListener("click", toggleTail);
+  $("tailDirectory").addEventListener("click", followTailDirectory);
+  $("collectorTail").addEventListener("click", tailCollector);
+  $("collectorBrowse").addEventListener("click", () => browseCollector(1));
+  $("collectorPrev").addEventListener("click", () => browseCollector(state.collector.page - 1));
+  $("collectorNext").addEventListener("click", () => browseCollector(state.collector.page + 1));
+  $("collectorReplay").addEventListener("click", replayToCollector);
+  $("dbOpen").addEventListener("click", openDatabase);
+  $("dbQuery").addEventListener("click", () => browseDatabase(1));
+  $("dbPrev").addEventListener("click", () => browseDatabase(state.database.page - 1));
+  $("dbNext").addEventListener("click", () => browseDatabase(state.database.page + 1));
+  $("largeOpen").addEventListener("click", openLargeFile);
+  $("largePrev").addEventListener("click", () => browseLargeFile(state.largeFile.page - 1));
+  $("largeNext").addEventListener("click", () => browseLargeFile(state.largeFile.page + 1));
+  $("fleetRefresh").addEventListener("click", refreshFleet);
+  $("indexFiles").addEventListener("click", indexLoadedFiles);
+  $("indexClear").addEventListener("click", clearIndex);
+  $("indexSearch").addEventListener("click", searchIndex);
+  $("indexQuery").addEventListener("keyup", (e) => {
+    if (e.key === "Enter") searchIndex();
+  });
+  $("sqlRun").addEventListener("click", () => runSql(1));
+  $("sqlPrev").addEventListener("click", () => runSql(Math.max(1, state.sql.page - 1)));
+  $("sqlNext").addEventListener("click", () => runSql(Math.min(state.sql.pages, state.sql.page + 1)));
+  $("sqlExport").addEventListener("click", exportSql);
+  $("sqlInput").addEventListener("keydown", (e) => {
+    // Keep the single-key shortcuts below from firing while typing SQL.
+    e.stopPropagation();
+    if (e.key === "Enter" && (e.ctrlKey || e.metaKey)) runSql(1);
+  });
+  $("healthToggle").addEventListener("click", toggleHealthMonitor);
+  $("collectorUrl").value = localStorage.getItem("audit_collector_url") || "http://localhost:8080";
   $("presetFailures").addEventListener("click", () => applyPreset("failures"));
   $("presetLogins").addEventListener("click", () => applyPreset("logins"));
   $("presetErrors").addEventListener("click", () => applyPreset("errors"));
@@ -1340,10 +2263,11 @@ document.addEventListener("DOMContentLoaded", () => {
   $("dateFrom").addEventListener("change", applyFilters);
   $("dateTo").addEventListener("change", applyFilters);
   setupSorting();
+  $("timelineZone").value = Intl.DateTimeFormat().resolvedOptions().timeZone || "UTC";
+  $("timelineBucket").addEventListener("change", renderAggregates);
+  $("timelineZone").addEventListener("change", renderAggregates);
   setEventCount();
-  renderSummary();
-  renderInsights();
-  renderTimeline();
+  renderAggregates();
   renderValidation();
   loadViews();
   loadNotes();
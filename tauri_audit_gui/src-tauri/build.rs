@@ -1,6 +1,46 @@
 fn main() {
-    let manifest = tauri_build::AppManifest::new()
-        .commands(&["generate_pdf_report", "read_tail_chunk"]);
+    let manifest = tauri_build::AppManifest::new().commands(&[
+        "generate_pdf_report",
+        "read_tail_chunk",
+        "tail_follow",
+        "tail_unfollow",
+        "tail_session_start",
+        "tail_session_status",
+        "tail_session_stop",
+        "remote_tail_start",
+        "remote_tail_stop",
+        "collector_query",
+        "collector_get_event",
+        "fleet_status",
+        "fleet_agent_metrics",
+        "collector_replay",
+        "collector_health_start",
+        "collector_health_history",
+        "collector_health_stop",
+        "load_audit_file",
+        "scan_audit_directory",
+        "load_audit_directory",
+        "sqlite_query",
+        "validate_audit_file",
+        "store_open_files",
+        "store_add_rows",
+        "store_clear",
+        "results_clear",
+        "query_page",
+        "count_events",
+        "aggregate",
+        "integrity_chain",
+        "export_events",
+        "run_sql",
+        "export_sql",
+        "index_audit_file",
+        "read_indexed_page",
+        "search_index_files",
+        "search_index_status",
+        "search_index_clear",
+        "search_events",
+        "cancel_job",
+    ]);
     tauri_build::try_build(tauri_build::Attributes::new().app_manifest(manifest))
         .expect("error while building tauri application");
 }
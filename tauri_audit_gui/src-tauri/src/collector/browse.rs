@@ -0,0 +1,371 @@
+//! Collector browsing: the explorer's filter panel translated into `GET /api/v1/events`
+//! parameters, server-side paging, and single-event fetch.
+
+use super::{to_rows, Client, CollectorConfig, EventFilters, MAX_PAGE_LIMIT};
+use crate::event::EventRow;
+use crate::jobs::run_blocking;
+use crate::loader::RejectedLine;
+use crate::store::query::{Alias, Expr, Field, Op, Query};
+use crate::store::{EventFilter, ResultView};
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Serialize)]
+pub struct CollectorPage {
+    pub total: u64,
+    pub page: usize,
+    pub page_size: usize,
+    /// Events of this page, now held by the result view.
+    pub events: usize,
+    pub rejects: Vec<RejectedLine>,
+    /// Parts of the filter the collector cannot evaluate, so they were not applied.
+    pub ignored: Vec<String>,
+}
+
+/// The indexed column a `field:value` term can be sent as, and whether its values are
+/// upper case (the panel compares them case-insensitively, the collector exactly).
+fn server_column<'f>(
+    filters: &'f mut EventFilters,
+    field: &Field,
+) -> Option<(&'f mut Option<String>, bool)> {
+    let path = match field {
+        Field::Alias(Alias::Category) => "action.category".to_string(),
+        Field::Alias(Alias::Status) => "action.result.status".to_string(),
+        Field::Alias(_) => return None,
+        Field::Path(path) => path.join("."),
+    };
+    Some(match path.as_str() {
+        "action.category" => (&mut filters.action_category, true),
+        "action.result.status" => (&mut filters.result_status, true),
+        "action.type" => (&mut filters.action_type, true),
+        "actor.username" => (&mut filters.actor_username, false),
+        "metadata.application" => (&mut filters.application, false),
+        "metadata.environment" => (&mut filters.environment, false),
+        _ => return None,
+    })
+}
+
+/// Exact matches on the indexed `audit_events` columns. Category and status from the
+/// dropdowns map directly, as do `field:value` terms on those columns that the whole
+/// query requires; search, errors-only, substring terms and anything under OR or NOT
+/// have no column equivalent and are listed instead. A term that asks a column for
+/// another value than the dropdowns or an earlier term is an error, since no event could
+/// match. The date range is left to callers.
+pub fn server_filters(
+    filter: &EventFilter,
+    mut base: EventFilters,
+) -> Result<(EventFilters, Vec<String>), String> {
+    let mut ignored = Vec::new();
+    let upper = |value: &Option<String>| {
+        value
+            .as_deref()
+            .filter(|v| !v.is_empty())
+            .map(str::to_uppercase)
+    };
+    base.action_category = upper(&filter.category).or(base.action_category);
+    base.result_status = upper(&filter.status).or(base.result_status);
+
+    let query = Query::parse(&filter.query)?;
+    for conjunct in query.conjuncts() {
+        let term = match conjunct {
+            Expr::Term(term) if term.op == Op::Match && !term.value.contains(['*', '?']) => term,
+            other => {
+                ignored.push(other.to_string());
+                continue;
+            }
+        };
+        let Some((slot, upper)) = server_column(&mut base, &term.field) else {
+            ignored.push(term.text.clone());
+            continue;
+        };
+        let value = match upper {
+            true => term.value.to_uppercase(),
+            false => term.value.clone(),
+        };
+        match slot {
+            Some(current) if *current != value => {
+                return Err(format!(
+                    "`{}` contradicts the filter `{}`, so no event can match",
+                    term.text, current
+                ))
+            }
+            _ => *slot = Some(value),
+        }
+    }
+    if !filter.search.is_empty() {
+        ignored.push("search".to_string());
+    }
+    if filter.errors_only {
+        ignored.push("errors only".to_string());
+    }
+    Ok((base, ignored))
+}
+
+fn query(
+    client: &Client,
+    filter: &EventFilter,
+    extra: EventFilters,
+    page: usize,
+    page_size: usize,
+) -> Result<(CollectorPage, Vec<EventRow>), String> {
+    let (filters, mut ignored) = server_filters(filter, extra)?;
+    if [&filter.date_from, &filter.date_to]
+        .iter()
+        .any(|day| day.as_deref().is_some_and(|d| !d.is_empty()))
+    {
+        ignored.push("date range".to_string());
+    }
+    let page = page.max(1);
+    let page_size = page_size.clamp(1, MAX_PAGE_LIMIT);
+    let result = client.events(&filters, page_size, (page - 1) * page_size)?;
+    let (rows, rejects) = to_rows(result.events, client.base_url());
+    let page = CollectorPage {
+        total: result.total,
+        page,
+        page_size,
+        events: rows.len(),
+        rejects,
+        ignored,
+    };
+    Ok((page, rows))
+}
+
+/// One page (1-based) of collector events, newest first, loaded into the result view.
+/// `extra` carries the filters the panel has no control for, such as application and
+/// environment.
+#[tauri::command]
+pub async fn collector_query(
+    app: AppHandle,
+    collector: CollectorConfig,
+    filter: EventFilter,
+    extra: Option<EventFilters>,
+    page: usize,
+    page_size: usize,
+    job_id: Option<String>,
+) -> Result<CollectorPage, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        let client = Client::new(&collector)?;
+        let (page, rows) = query(&client, &filter, extra.unwrap_or_default(), page, page_size)?;
+        job.check()?;
+        handle.state::<ResultView>().0.replace(rows);
+        Ok(page)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn collector_get_event(
+    app: AppHandle,
+    collector: CollectorConfig,
+    event_id: String,
+) -> Result<EventRow, String> {
+    run_blocking(app, None, move |_| {
+        let client = Client::new(&collector)?;
+        let raw = client.event(&event_id)?;
+        let (mut rows, rejects) = to_rows(vec![raw], client.base_url());
+        match rejects.into_iter().next() {
+            Some(reject) => Err(reject.error),
+            None => Ok(rows.remove(0)),
+        }
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+
+    fn event(id: &str, category: &str, status: &str, user: &str) -> Value {
+        json!({
+            "event_id": id,
+            "timestamp": "2026-01-01T00:00:00Z",
+            "actor": {"type": "user", "username": user},
+            "action": {"type": "READ", "category": category, "result": {"status": status}},
+        })
+    }
+
+    /// The `audit_events` column a query parameter filters on.
+    fn column<'e>(event: &'e Value, key: &str) -> &'e Value {
+        match key {
+            "action_category" => &event["action"]["category"],
+            "action_type" => &event["action"]["type"],
+            "result_status" => &event["action"]["result"]["status"],
+            "actor_username" => &event["actor"]["username"],
+            "application" => &event["metadata"]["application"],
+            "environment" => &event["metadata"]["environment"],
+            other => panic!("unexpected parameter {}", other),
+        }
+    }
+
+    fn respond(events: &[Value], target: &str) -> (&'static str, String) {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        if let Some(id) = path.strip_prefix("/api/v1/events/") {
+            return match events.iter().find(|event| event["event_id"] == id) {
+                Some(event) => ("200 OK", event.to_string()),
+                None => (
+                    "404 Not Found",
+                    json!({"detail": "Event not found"}).to_string(),
+                ),
+            };
+        }
+        assert_eq!(path, "/api/v1/events");
+        let (mut limit, mut offset) = (100, 0);
+        let mut matching: Vec<&Value> = events.iter().collect();
+        for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+            match key {
+                "limit" => limit = value.parse().unwrap(),
+                "offset" => offset = value.parse().unwrap(),
+                _ => matching.retain(|event| column(event, key) == value),
+            }
+        }
+        let page: Vec<&Value> = matching.iter().skip(offset).take(limit).copied().collect();
+        let body = json!({"total": matching.len(), "events": page});
+        ("200 OK", body.to_string())
+    }
+
+    /// A collector on a local port serving `events` (already newest first). Returns its
+    /// base URL and the request targets it received.
+    fn serve(events: Vec<Value>) -> (String, Arc<Mutex<Vec<String>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let log = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                let target = request_line.split_whitespace().nth(1).unwrap().to_string();
+                log.lock().unwrap().push(target.clone());
+                let (status, body) = respond(&events, &target);
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (base_url, requests)
+    }
+
+    fn client(base_url: &str) -> Client {
+        Client::new(&CollectorConfig {
+            base_url: base_url.to_string(),
+            api_key: None,
+        })
+        .unwrap()
+    }
+
+    fn filter(value: Value) -> EventFilter {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[test]
+    fn panel_and_query_terms_become_columns() {
+        let filter = filter(json!({
+            "category": "auth",
+            "query": "actor.username:alice status:failure action.type:read category:AUTH",
+        }));
+        let (filters, ignored) = server_filters(&filter, EventFilters::default()).unwrap();
+        assert_eq!(
+            filters.query(),
+            vec![
+                ("action_category", "AUTH"),
+                ("action_type", "READ"),
+                ("actor_username", "alice"),
+                ("result_status", "FAILURE"),
+            ]
+        );
+        assert!(ignored.is_empty(), "{:?}", ignored);
+    }
+
+    #[test]
+    fn terms_without_a_column_are_ignored() {
+        let filter = filter(json!({
+            "category": "AUTH",
+            "search": "timeout",
+            "errors_only": true,
+            "query": "user:a* (status:failure OR status:error) action.operation:login error.type:Timeout",
+        }));
+        let (filters, ignored) = server_filters(&filter, EventFilters::default()).unwrap();
+        assert_eq!(filters.query(), vec![("action_category", "AUTH")]);
+        assert_eq!(ignored.len(), 6, "{:?}", ignored);
+        assert!(ignored.contains(&"error.type:Timeout".to_string()));
+        assert!(ignored.contains(&"action.operation:login".to_string()));
+        assert!(ignored.contains(&"search".to_string()));
+        assert!(ignored.contains(&"errors only".to_string()));
+    }
+
+    #[test]
+    fn contradicting_terms_are_refused() {
+        let panel = filter(json!({"category": "AUTH", "query": "category:DATABASE"}));
+        let err = server_filters(&panel, EventFilters::default()).unwrap_err();
+        assert_eq!(
+            err,
+            "`category:DATABASE` contradicts the filter `AUTH`, so no event can match"
+        );
+
+        let panel = filter(json!({"query": "status:failure action.result.status:success"}));
+        assert!(server_filters(&panel, EventFilters::default()).is_err());
+
+        let extra = EventFilters {
+            environment: Some("prod".to_string()),
+            ..EventFilters::default()
+        };
+        let panel = filter(json!({"query": "metadata.environment:staging"}));
+        assert!(server_filters(&panel, extra).is_err());
+    }
+
+    #[test]
+    fn pages_through_the_collector() {
+        let events: Vec<Value> = (0..7)
+            .map(|i| {
+                let category = if i % 2 == 0 { "AUTH" } else { "DATABASE" };
+                event(&format!("e{}", i), category, "SUCCESS", "alice")
+            })
+            .collect();
+        let (base_url, requests) = serve(events);
+        let client = client(&base_url);
+        let filter = filter(json!({"category": "AUTH", "date_from": "2026-01-01"}));
+
+        let (page, rows) = query(&client, &filter, EventFilters::default(), 2, 3).unwrap();
+        assert_eq!(page.total, 4);
+        assert_eq!(page.events, 1);
+        assert_eq!(rows[0].event_id, "e6");
+        assert_eq!(page.ignored, vec!["date range"]);
+        assert_eq!(
+            requests.lock().unwrap().last().unwrap(),
+            "/api/v1/events?action_category=AUTH&limit=3&offset=3"
+        );
+
+        let extra = EventFilters {
+            actor_username: Some("bob".to_string()),
+            ..EventFilters::default()
+        };
+        let (page, rows) = query(&client, &filter, extra, 1, 10).unwrap();
+        assert_eq!(page.total, 0);
+        assert!(rows.is_empty());
+
+        assert_eq!(
+            client.event("e3").unwrap()["action"]["category"],
+            "DATABASE"
+        );
+        assert!(client.event("missing").unwrap_err().contains("404"));
+    }
+}
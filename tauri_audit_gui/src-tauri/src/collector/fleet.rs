@@ -0,0 +1,230 @@
+//! Fleet view: the collector's agents and their metrics, with agents that stopped
+//! reporting marked stale.
+
+use super::{AgentInfo, Client, CollectorConfig, MetricSample};
+use crate::event::parse_timestamp;
+use crate::jobs::run_blocking;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use tauri::AppHandle;
+
+/// `INTERVAL_SECONDS` default in `agent/config.py`, assumed until an agent has two samples.
+const DEFAULT_INTERVAL_SECS: f64 = 30.0;
+const DEFAULT_STALE_AFTER: f64 = 3.0;
+/// Recent samples used to estimate each agent's reporting interval.
+const INTERVAL_SAMPLES: usize = 20;
+/// Largest `limit` that `GET /api/v1/metrics/{agent_id}` accepts.
+const MAX_METRICS_LIMIT: usize = 1000;
+
+#[derive(Debug, Serialize)]
+pub struct AgentHealth {
+    #[serde(flatten)]
+    pub agent: AgentInfo,
+    /// Median gap between recent samples, or the agent default without enough samples.
+    pub interval_secs: f64,
+    pub interval_estimated: bool,
+    /// Seconds since `last_seen`; `None` when the collector sent an unreadable time.
+    pub silent_secs: Option<i64>,
+    pub stale: bool,
+    pub latest: Option<MetricSample>,
+    /// Why the agent's metrics could not be fetched; staleness then rests on `last_seen`.
+    pub metrics_error: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FleetStatus {
+    pub checked_at: String,
+    /// How many reporting intervals of silence make an agent stale.
+    pub stale_after: f64,
+    pub agents: Vec<AgentHealth>,
+}
+
+fn median_gap(samples: &[MetricSample]) -> Option<f64> {
+    let mut times: Vec<DateTime<Utc>> = samples
+        .iter()
+        .filter_map(|sample| parse_timestamp(&sample.timestamp))
+        .collect();
+    times.sort();
+    let mut gaps: Vec<f64> = times
+        .windows(2)
+        .map(|pair| (pair[1] - pair[0]).num_milliseconds() as f64 / 1000.0)
+        .filter(|gap| *gap > 0.0)
+        .collect();
+    gaps.sort_by(f64::total_cmp);
+    gaps.get(gaps.len() / 2).copied()
+}
+
+/// `samples` are newest first, as the collector returns them.
+pub fn assess(
+    agent: AgentInfo,
+    samples: Vec<MetricSample>,
+    now: DateTime<Utc>,
+    stale_after: f64,
+) -> AgentHealth {
+    let estimated = median_gap(&samples);
+    let interval_secs = estimated.unwrap_or(DEFAULT_INTERVAL_SECS);
+    let silent_secs = parse_timestamp(&agent.last_seen).map(|seen| (now - seen).num_seconds());
+    let stale = silent_secs.is_none_or(|silent| silent as f64 > stale_after * interval_secs);
+    AgentHealth {
+        agent,
+        interval_secs,
+        interval_estimated: estimated.is_some(),
+        silent_secs,
+        stale,
+        latest: samples.into_iter().next(),
+        metrics_error: None,
+    }
+}
+
+/// Every agent with its latest sample. An agent is stale once it has been silent for
+/// more than `stale_after` (default 3) of its own reporting intervals. An agent whose
+/// metrics fail to load is still listed, with the error.
+#[tauri::command]
+pub async fn fleet_status(
+    app: AppHandle,
+    collector: CollectorConfig,
+    stale_after: Option<f64>,
+    job_id: Option<String>,
+) -> Result<FleetStatus, String> {
+    run_blocking(app, job_id, move |job| {
+        let client = Client::new(&collector)?;
+        let stale_after = stale_after
+            .filter(|n| *n > 0.0)
+            .unwrap_or(DEFAULT_STALE_AFTER);
+        let now = Utc::now();
+        let mut agents = Vec::new();
+        for agent in client.agents()? {
+            job.check()?;
+            let (samples, error) =
+                match client.agent_metrics(&agent.agent_id, None, None, INTERVAL_SAMPLES) {
+                    Ok(samples) => (samples, None),
+                    Err(e) => (Vec::new(), Some(e)),
+                };
+            let mut health = assess(agent, samples, now, stale_after);
+            health.metrics_error = error;
+            agents.push(health);
+        }
+        Ok(FleetStatus {
+            checked_at: now.to_rfc3339(),
+            stale_after,
+            agents,
+        })
+    })
+    .await
+}
+
+/// An agent's metrics history between the optional ISO 8601 bounds, oldest first.
+#[tauri::command]
+pub async fn fleet_agent_metrics(
+    app: AppHandle,
+    collector: CollectorConfig,
+    agent_id: String,
+    start: Option<String>,
+    end: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<MetricSample>, String> {
+    run_blocking(app, None, move |_| {
+        let client = Client::new(&collector)?;
+        let limit = limit
+            .unwrap_or(MAX_METRICS_LIMIT)
+            .clamp(1, MAX_METRICS_LIMIT);
+        let mut samples =
+            client.agent_metrics(&agent_id, start.as_deref(), end.as_deref(), limit)?;
+        samples.reverse();
+        Ok(samples)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn agent(last_seen: &str) -> AgentInfo {
+        AgentInfo {
+            agent_id: "agent-1".to_string(),
+            hostname: "web-1".to_string(),
+            first_seen: "2026-01-01T00:00:00Z".to_string(),
+            last_seen: last_seen.to_string(),
+            status: "active".to_string(),
+        }
+    }
+
+    /// Samples at the given times, newest first as the collector sends them.
+    fn samples(times: &[&str]) -> Vec<MetricSample> {
+        times
+            .iter()
+            .rev()
+            .map(|time| MetricSample {
+                timestamp: time.to_string(),
+                metrics: json!({"cpu_percent": 1.0}),
+            })
+            .collect()
+    }
+
+    fn at(time: &str) -> DateTime<Utc> {
+        parse_timestamp(time).unwrap()
+    }
+
+    #[test]
+    fn the_median_gap_ignores_outliers_and_duplicates() {
+        let gaps = samples(&[
+            "2026-01-01T00:00:00Z",
+            "2026-01-01T00:00:10Z",
+            "2026-01-01T00:00:10Z",
+            "2026-01-01T00:00:20Z",
+            "2026-01-01T00:05:00Z",
+            "2026-01-01T00:05:10Z",
+        ]);
+        assert_eq!(median_gap(&gaps), Some(10.0));
+        // Order and unreadable times do not matter.
+        let mut shuffled = samples(&["2026-01-01T00:01:00Z", "garbage", "2026-01-01T00:00:00Z"]);
+        shuffled.swap(0, 2);
+        assert_eq!(median_gap(&shuffled), Some(60.0));
+    }
+
+    #[test]
+    fn too_few_samples_have_no_gap() {
+        assert_eq!(median_gap(&[]), None);
+        assert_eq!(median_gap(&samples(&["2026-01-01T00:00:00Z"])), None);
+        let same = samples(&["2026-01-01T00:00:00Z", "2026-01-01T00:00:00Z"]);
+        assert_eq!(median_gap(&same), None);
+    }
+
+    #[test]
+    fn agents_go_stale_after_missed_intervals() {
+        let now = at("2026-01-01T00:10:00Z");
+        let history = samples(&[
+            "2026-01-01T00:08:00Z",
+            "2026-01-01T00:09:00Z",
+            "2026-01-01T00:09:50Z",
+        ]);
+
+        let health = assess(agent("2026-01-01T00:09:50Z"), history.clone(), now, 3.0);
+        // With an even count the larger of the middle gaps is taken.
+        assert_eq!(health.interval_secs, 60.0);
+        assert!(health.interval_estimated);
+        assert_eq!(health.silent_secs, Some(10));
+        assert!(!health.stale);
+        assert_eq!(health.latest.unwrap().timestamp, "2026-01-01T00:09:50Z");
+
+        assert!(!assess(agent("2026-01-01T00:07:00Z"), history.clone(), now, 3.0).stale);
+        let health = assess(agent("2026-01-01T00:06:59Z"), history, now, 3.0);
+        assert_eq!(health.silent_secs, Some(181));
+        assert!(health.stale);
+
+        // Without samples the agent default applies.
+        let health = assess(agent("2026-01-01T00:08:40Z"), Vec::new(), now, 3.0);
+        assert_eq!(health.interval_secs, DEFAULT_INTERVAL_SECS);
+        assert!(!health.interval_estimated);
+        assert!(!health.stale);
+        assert!(health.latest.is_none());
+        assert!(assess(agent("2026-01-01T00:08:20Z"), Vec::new(), now, 3.0).stale);
+
+        // An unreadable last_seen counts as stale.
+        let health = assess(agent("yesterday"), Vec::new(), now, 3.0);
+        assert_eq!(health.silent_secs, None);
+        assert!(health.stale);
+    }
+}
@@ -0,0 +1,458 @@
+//! Collector health monitor: polls `/api/v1/health`, `/api/v1/ready` and
+//! `/api/v1/internal/metrics`, keeps a short history and pushes each sample as
+//! `collector://health`.
+
+use super::{spawn_poller, Client, CollectorConfig, InternalMetrics, PollStop};
+use chrono::Utc;
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, State};
+
+pub const HEALTH_EVENT: &str = "collector://health";
+
+const DEFAULT_POLL_MS: u64 = 10_000;
+/// Samples kept per monitor: an hour at the default interval.
+const DEFAULT_HISTORY: usize = 360;
+/// Our own `/health` and `/ready` calls, which the collector counts as requests.
+const PROBE_REQUESTS: u64 = 2;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthSample {
+    pub at: String,
+    pub healthy: bool,
+    pub ready: bool,
+    /// `ok`, or the database error `/api/v1/ready` reported.
+    pub database: Option<String>,
+    /// Why the collector could not be asked at all.
+    pub error: Option<String>,
+    /// Round trip of the `/api/v1/health` call.
+    pub latency_ms: Option<u64>,
+    pub request_count: Option<u64>,
+    pub error_count: Option<u64>,
+    pub uptime_seconds: Option<f64>,
+    /// Requests per second since the previous sample, not counting this monitor's probes.
+    pub request_rate: Option<f64>,
+    /// Responses with a 5xx status since the previous sample.
+    pub new_errors: Option<u64>,
+    /// The uptime went backwards, so the counters started over.
+    pub restarted: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct HealthSummary {
+    pub samples: usize,
+    pub unreachable: usize,
+    /// Changes of readiness between consecutive samples.
+    pub ready_flaps: usize,
+    pub new_errors: u64,
+    pub restarts: usize,
+    pub avg_latency_ms: Option<f64>,
+    pub max_latency_ms: Option<u64>,
+    pub avg_request_rate: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HealthHistory {
+    pub monitor_id: String,
+    pub base_url: String,
+    pub poll_interval_ms: u64,
+    pub summary: HealthSummary,
+    /// Oldest first.
+    pub samples: Vec<HealthSample>,
+}
+
+struct History {
+    capacity: usize,
+    samples: VecDeque<HealthSample>,
+}
+
+impl History {
+    fn push(&mut self, sample: HealthSample) {
+        self.samples.push_back(sample);
+        while self.samples.len() > self.capacity {
+            self.samples.pop_front();
+        }
+    }
+
+    fn summary(&self) -> HealthSummary {
+        let mut summary = HealthSummary {
+            samples: self.samples.len(),
+            ..HealthSummary::default()
+        };
+        let latencies: Vec<u64> = self.samples.iter().filter_map(|s| s.latency_ms).collect();
+        let rates: Vec<f64> = self.samples.iter().filter_map(|s| s.request_rate).collect();
+        for (i, sample) in self.samples.iter().enumerate() {
+            summary.unreachable += sample.error.is_some() as usize;
+            summary.restarts += sample.restarted as usize;
+            summary.new_errors += sample.new_errors.unwrap_or(0);
+            if i > 0 && self.samples[i - 1].ready != sample.ready {
+                summary.ready_flaps += 1;
+            }
+        }
+        summary.max_latency_ms = latencies.iter().copied().max();
+        summary.avg_latency_ms = (!latencies.is_empty())
+            .then(|| latencies.iter().sum::<u64>() as f64 / latencies.len() as f64);
+        summary.avg_request_rate =
+            (!rates.is_empty()).then(|| rates.iter().sum::<f64>() / rates.len() as f64);
+        summary
+    }
+}
+
+struct Prober {
+    client: Client,
+    /// Counters of the previous sample, for rates.
+    previous: Option<InternalMetrics>,
+}
+
+impl Prober {
+    fn sample(&mut self) -> HealthSample {
+        let at = Utc::now().to_rfc3339();
+        let started = Instant::now();
+        let health = match self.client.health() {
+            Ok(health) => health,
+            Err(error) => {
+                self.previous = None;
+                return HealthSample {
+                    at,
+                    healthy: false,
+                    ready: false,
+                    database: None,
+                    error: Some(error),
+                    latency_ms: None,
+                    request_count: None,
+                    error_count: None,
+                    uptime_seconds: None,
+                    request_rate: None,
+                    new_errors: None,
+                    restarted: false,
+                };
+            }
+        };
+        let latency_ms = started.elapsed().as_millis() as u64;
+        let (ready, database) = match self.client.ready() {
+            Ok(ready) => (ready.status == "ready", ready.database),
+            Err(error) => (false, Some(error)),
+        };
+        // Collectors without the internal endpoint still report health and readiness.
+        let metrics = self.client.internal_metrics().ok();
+
+        let mut sample = HealthSample {
+            at,
+            healthy: health.status == "ok",
+            ready,
+            database,
+            error: None,
+            latency_ms: Some(latency_ms),
+            request_count: metrics.as_ref().map(|m| m.request_count),
+            error_count: metrics.as_ref().map(|m| m.error_count),
+            uptime_seconds: metrics.as_ref().map(|m| m.uptime_seconds),
+            request_rate: None,
+            new_errors: None,
+            restarted: false,
+        };
+        if let (Some(previous), Some(current)) = (&self.previous, &metrics) {
+            sample.restarted = current.uptime_seconds < previous.uptime_seconds;
+            // After a restart the counters start from zero, so all of them are new.
+            let (requests, errors, elapsed) = match sample.restarted {
+                true => (
+                    current.request_count,
+                    current.error_count,
+                    current.uptime_seconds,
+                ),
+                false => (
+                    current.request_count.saturating_sub(previous.request_count),
+                    current.error_count.saturating_sub(previous.error_count),
+                    current.uptime_seconds - previous.uptime_seconds,
+                ),
+            };
+            if elapsed > 0.0 {
+                sample.request_rate =
+                    Some(requests.saturating_sub(PROBE_REQUESTS) as f64 / elapsed);
+            }
+            sample.new_errors = Some(errors);
+        }
+        self.previous = metrics;
+        sample
+    }
+}
+
+struct Monitor {
+    _poller: PollStop,
+    base_url: String,
+    poll_interval_ms: u64,
+    history: Arc<Mutex<History>>,
+}
+
+impl Monitor {
+    fn snapshot(&self, monitor_id: &str) -> HealthHistory {
+        let history = self.history.lock().unwrap();
+        HealthHistory {
+            monitor_id: monitor_id.to_string(),
+            base_url: self.base_url.clone(),
+            poll_interval_ms: self.poll_interval_ms,
+            summary: history.summary(),
+            samples: history.samples.iter().cloned().collect(),
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct HealthMonitors {
+    monitors: Mutex<HashMap<String, Monitor>>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct HealthUpdate {
+    monitor_id: String,
+    sample: HealthSample,
+    summary: HealthSummary,
+}
+
+/// Polls the collector every `poll_interval_ms` (default 10 s), keeping the last
+/// `history` samples (default 360).
+#[tauri::command]
+pub fn collector_health_start(
+    app: AppHandle,
+    monitors: State<'_, HealthMonitors>,
+    monitor_id: String,
+    collector: CollectorConfig,
+    poll_interval_ms: Option<u64>,
+    history: Option<usize>,
+) -> Result<(), String> {
+    let client = Client::new(&collector)?;
+    let base_url = client.base_url().to_string();
+    let poll_interval_ms = poll_interval_ms.unwrap_or(DEFAULT_POLL_MS).max(1);
+    let key = monitor_id.clone();
+    let history = Arc::new(Mutex::new(History {
+        capacity: history.unwrap_or(DEFAULT_HISTORY).max(1),
+        samples: VecDeque::new(),
+    }));
+
+    let mut prober = Prober {
+        client,
+        previous: None,
+    };
+    let samples = history.clone();
+    let tick = move || {
+        let sample = prober.sample();
+        let summary = {
+            let mut history = samples.lock().unwrap();
+            history.push(sample.clone());
+            history.summary()
+        };
+        let _ = app.emit(
+            HEALTH_EVENT,
+            HealthUpdate {
+                monitor_id: monitor_id.clone(),
+                sample,
+                summary,
+            },
+        );
+    };
+    // Replacing an existing monitor with the same id drops its `PollStop` and stops it.
+    monitors.monitors.lock().unwrap().insert(
+        key,
+        Monitor {
+            _poller: spawn_poller(Duration::from_millis(poll_interval_ms), tick),
+            base_url,
+            poll_interval_ms,
+            history,
+        },
+    );
+    Ok(())
+}
+
+#[tauri::command]
+pub fn collector_health_history(
+    monitors: State<'_, HealthMonitors>,
+    monitor_id: String,
+) -> Option<HealthHistory> {
+    let monitors = monitors.monitors.lock().unwrap();
+    Some(monitors.get(&monitor_id)?.snapshot(&monitor_id))
+}
+
+/// Stops polling and returns the history collected so far.
+#[tauri::command]
+pub fn collector_health_stop(
+    monitors: State<'_, HealthMonitors>,
+    monitor_id: String,
+) -> Option<HealthHistory> {
+    let monitor = monitors.monitors.lock().unwrap().remove(&monitor_id)?;
+    Some(monitor.snapshot(&monitor_id))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn sample(ready: bool, latency_ms: Option<u64>) -> HealthSample {
+        HealthSample {
+            at: Utc::now().to_rfc3339(),
+            healthy: latency_ms.is_some(),
+            ready,
+            database: None,
+            error: latency_ms
+                .is_none()
+                .then(|| "connection refused".to_string()),
+            latency_ms,
+            request_count: None,
+            error_count: None,
+            uptime_seconds: None,
+            request_rate: None,
+            new_errors: None,
+            restarted: false,
+        }
+    }
+
+    /// A collector on a local port whose `/api/v1/internal/metrics` answers with whatever
+    /// `metrics` holds at the time.
+    fn serve(metrics: Arc<Mutex<Value>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                let body = match request_line.split_whitespace().nth(1).unwrap() {
+                    "/api/v1/health" => json!({"status": "ok"}),
+                    "/api/v1/ready" => json!({"status": "ready", "database": "ok"}),
+                    "/api/v1/internal/metrics" => metrics.lock().unwrap().clone(),
+                    other => panic!("unexpected request {}", other),
+                }
+                .to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        base_url
+    }
+
+    fn prober(base_url: &str) -> Prober {
+        Prober {
+            client: Client::new(&CollectorConfig {
+                base_url: base_url.to_string(),
+                api_key: None,
+            })
+            .unwrap(),
+            previous: None,
+        }
+    }
+
+    #[test]
+    fn history_keeps_the_newest_samples() {
+        let mut history = History {
+            capacity: 3,
+            samples: VecDeque::new(),
+        };
+        for latency in 1..=5 {
+            history.push(sample(true, Some(latency)));
+        }
+        let kept: Vec<_> = history.samples.iter().map(|s| s.latency_ms).collect();
+        assert_eq!(kept, [Some(3), Some(4), Some(5)]);
+    }
+
+    #[test]
+    fn summary_counts_flaps_outages_restarts_and_errors() {
+        let mut history = History {
+            capacity: 10,
+            samples: VecDeque::new(),
+        };
+        let empty = history.summary();
+        assert_eq!((empty.samples, empty.ready_flaps), (0, 0));
+        assert_eq!((empty.avg_latency_ms, empty.max_latency_ms), (None, None));
+        assert_eq!(empty.avg_request_rate, None);
+
+        let mut restarted = sample(true, Some(30));
+        restarted.restarted = true;
+        restarted.new_errors = Some(4);
+        restarted.request_rate = Some(1.0);
+        let mut busy = sample(true, Some(20));
+        busy.new_errors = Some(1);
+        busy.request_rate = Some(3.0);
+        for sample in [
+            sample(true, Some(10)),
+            sample(false, None),
+            restarted,
+            busy,
+            sample(false, Some(40)),
+        ] {
+            history.push(sample);
+        }
+        let summary = history.summary();
+        assert_eq!(summary.samples, 5);
+        assert_eq!(summary.unreachable, 1);
+        // ready → not ready → ready → ready → not ready
+        assert_eq!(summary.ready_flaps, 3);
+        assert_eq!(summary.restarts, 1);
+        assert_eq!(summary.new_errors, 5);
+        assert_eq!(summary.avg_latency_ms, Some(25.0));
+        assert_eq!(summary.max_latency_ms, Some(40));
+        assert_eq!(summary.avg_request_rate, Some(2.0));
+    }
+
+    #[test]
+    fn a_drop_in_uptime_is_reported_as_a_restart() {
+        let metrics = Arc::new(Mutex::new(json!({
+            "request_count": 50, "error_count": 1, "uptime_seconds": 100.0,
+        })));
+        let mut prober = prober(&serve(metrics.clone()));
+
+        let first = prober.sample();
+        assert!(first.healthy && first.ready);
+        assert_eq!(first.database.as_deref(), Some("ok"));
+        assert_eq!((first.request_rate, first.new_errors), (None, None));
+        assert!(!first.restarted);
+
+        // 22 requests in 10 s, two of them the probes themselves.
+        *metrics.lock().unwrap() =
+            json!({"request_count": 72, "error_count": 3, "uptime_seconds": 110.0});
+        let second = prober.sample();
+        assert!(!second.restarted);
+        assert_eq!(second.request_rate, Some(2.0));
+        assert_eq!(second.new_errors, Some(2));
+
+        // Uptime went backwards: the counters restarted, so all of them are new.
+        *metrics.lock().unwrap() =
+            json!({"request_count": 12, "error_count": 1, "uptime_seconds": 5.0});
+        let third = prober.sample();
+        assert!(third.restarted);
+        assert_eq!(third.request_rate, Some(2.0));
+        assert_eq!(third.new_errors, Some(1));
+    }
+
+    #[test]
+    fn an_unreachable_collector_resets_the_counters() {
+        let closed = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", closed.local_addr().unwrap());
+        drop(closed);
+        let mut prober = prober(&base_url);
+        prober.previous = Some(InternalMetrics {
+            request_count: 1,
+            error_count: 0,
+            uptime_seconds: 1.0,
+        });
+        let sample = prober.sample();
+        assert!(sample.error.is_some());
+        assert!(!sample.healthy && !sample.ready && !sample.restarted);
+        assert!(prober.previous.is_none());
+    }
+}
@@ -0,0 +1,392 @@
+//! Client for a Vigil collector's REST API (`collector/api`), authenticated with the
+//! same Bearer API keys that `collector/auth/api_keys.py` checks.
+
+pub mod browse;
+pub mod fleet;
+pub mod health;
+pub mod replay;
+pub mod tail;
+
+use crate::event::{AuditEvent, EventRow};
+use crate::loader::RejectedLine;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::mpsc::{self, RecvTimeoutError, Sender};
+use std::thread;
+use std::time::Duration;
+
+/// Largest `limit` that `GET /api/v1/events` accepts.
+pub const MAX_PAGE_LIMIT: usize = 500;
+/// Most events `POST /api/v1/events/batch` takes in one request.
+pub const MAX_BATCH_SIZE: usize = 100;
+
+const TIMEOUT: Duration = Duration::from_secs(15);
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct CollectorConfig {
+    /// e.g. `http://localhost:8080`; the `/api/v1/...` paths are appended.
+    pub base_url: String,
+    /// Sent as `Authorization: Bearer ...`. Leave empty for a collector with `AUTH_DISABLED`.
+    #[serde(default)]
+    pub api_key: Option<String>,
+}
+
+/// The equality filters `GET /api/v1/events` understands.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFilters {
+    pub action_category: Option<String>,
+    pub action_type: Option<String>,
+    pub actor_username: Option<String>,
+    pub application: Option<String>,
+    pub environment: Option<String>,
+    pub result_status: Option<String>,
+}
+
+impl EventFilters {
+    /// The filters that are set, keyed by their `audit_events` column.
+    pub fn query(&self) -> Vec<(&'static str, &str)> {
+        [
+            ("action_category", &self.action_category),
+            ("action_type", &self.action_type),
+            ("actor_username", &self.actor_username),
+            ("application", &self.application),
+            ("environment", &self.environment),
+            ("result_status", &self.result_status),
+        ]
+        .into_iter()
+        .filter_map(|(key, value)| value.as_deref().filter(|v| !v.is_empty()).map(|v| (key, v)))
+        .collect()
+    }
+}
+
+/// One page of `GET /api/v1/events`, newest first.
+#[derive(Debug, Deserialize)]
+pub struct EventPage {
+    pub total: u64,
+    pub events: Vec<Value>,
+}
+
+/// An entry of `GET /api/v1/agents` (`collector/models/agent_model.py`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentInfo {
+    pub agent_id: String,
+    pub hostname: String,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub status: String,
+}
+
+#[derive(Deserialize)]
+struct AgentList {
+    agents: Vec<AgentInfo>,
+}
+
+/// One sample of `GET /api/v1/metrics/{agent_id}`; `metrics` is passed through as sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetricSample {
+    pub timestamp: String,
+    pub metrics: Value,
+}
+
+#[derive(Deserialize)]
+struct MetricsPage {
+    metrics: Vec<MetricSample>,
+}
+
+/// Body of `/api/v1/health` and `/api/v1/ready`; only the latter reports `database`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceStatus {
+    pub status: String,
+    #[serde(default)]
+    pub database: Option<String>,
+}
+
+/// Counters of `/api/v1/internal/metrics` (`collector/middleware/metrics_tracker.py`),
+/// reset whenever the collector restarts.
+#[derive(Debug, Clone, Deserialize)]
+pub struct InternalMetrics {
+    pub request_count: u64,
+    pub error_count: u64,
+    pub uptime_seconds: f64,
+}
+
+/// A per-event storage failure in a batch response; `index` is the position in the request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchError {
+    pub index: usize,
+    pub error: String,
+}
+
+/// Response of `POST /api/v1/events/batch`.
+#[derive(Debug, Deserialize)]
+pub struct BatchResult {
+    pub accepted: usize,
+    #[serde(default)]
+    pub errors: Vec<BatchError>,
+}
+
+/// Why a POST failed, split by whether sending it again can help.
+#[derive(Debug)]
+pub enum PostError {
+    /// Transport errors, 429 and 5xx. `retry_after` is the collector's `Retry-After`.
+    Transient {
+        error: String,
+        retry_after: Option<Duration>,
+    },
+    /// A 422 with FastAPI's list of validation errors.
+    Invalid(Vec<Value>),
+    Rejected(String),
+}
+
+pub struct Client {
+    agent: ureq::Agent,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Client {
+    pub fn new(config: &CollectorConfig) -> Result<Client, String> {
+        let base_url = config.base_url.trim().trim_end_matches('/');
+        if !base_url.starts_with("http://") && !base_url.starts_with("https://") {
+            return Err(format!(
+                "Collector URL must start with http:// or https://: {}",
+                base_url
+            ));
+        }
+        Ok(Client {
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+            base_url: base_url.to_string(),
+            api_key: config.api_key.clone().filter(|key| !key.is_empty()),
+        })
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    fn request(&self, method: &str, path: &str) -> ureq::Request {
+        let request = self
+            .agent
+            .request(method, &format!("{}{}", self.base_url, path));
+        match &self.api_key {
+            Some(key) => request.set("Authorization", &format!("Bearer {}", key)),
+            None => request,
+        }
+    }
+
+    /// GETs `path`; with `missing_ok` a 404 is `Ok(None)` so "nothing there" is not an error.
+    fn fetch<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+        missing_ok: bool,
+    ) -> Result<Option<T>, String> {
+        let mut request = self.request("GET", path);
+        for (key, value) in query {
+            request = request.query(key, value);
+        }
+        match request.call() {
+            Ok(response) => response.into_json().map(Some).map_err(|e| e.to_string()),
+            Err(ureq::Error::Status(404, _)) if missing_ok => Ok(None),
+            Err(ureq::Error::Status(code, response)) => Err(status_error(path, code, response)),
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    fn post<T: DeserializeOwned>(&self, path: &str, body: Value) -> Result<T, PostError> {
+        match self.request("POST", path).send_json(body) {
+            Ok(response) => response
+                .into_json()
+                .map_err(|e| PostError::Rejected(e.to_string())),
+            Err(ureq::Error::Status(code, response)) if code == 429 || code >= 500 => {
+                let retry_after = response
+                    .header("Retry-After")
+                    .and_then(|secs| secs.trim().parse().ok())
+                    .map(Duration::from_secs);
+                Err(PostError::Transient {
+                    error: status_error(path, code, response),
+                    retry_after,
+                })
+            }
+            Err(ureq::Error::Status(422, response)) => {
+                match response
+                    .into_json::<Value>()
+                    .ok()
+                    .and_then(|body| body.get("detail").and_then(Value::as_array).cloned())
+                {
+                    Some(details) => Err(PostError::Invalid(details)),
+                    None => Err(PostError::Rejected(format!(
+                        "Collector returned 422 for {}",
+                        path
+                    ))),
+                }
+            }
+            Err(ureq::Error::Status(code, response)) => {
+                Err(PostError::Rejected(status_error(path, code, response)))
+            }
+            Err(e) => Err(PostError::Transient {
+                error: e.to_string(),
+                retry_after: None,
+            }),
+        }
+    }
+
+    fn get<T: DeserializeOwned>(&self, path: &str, query: &[(&str, &str)]) -> Result<T, String> {
+        self.fetch(path, query, false)?
+            .ok_or_else(|| format!("Collector returned nothing for {}", path))
+    }
+
+    pub fn events(
+        &self,
+        filters: &EventFilters,
+        limit: usize,
+        offset: usize,
+    ) -> Result<EventPage, String> {
+        let limit = limit.clamp(1, MAX_PAGE_LIMIT).to_string();
+        let offset = offset.to_string();
+        let mut query = filters.query();
+        query.push(("limit", &limit));
+        query.push(("offset", &offset));
+        self.get("/api/v1/events", &query)
+    }
+
+    /// The full stored record; a missing id is the collector's 404.
+    pub fn event(&self, event_id: &str) -> Result<Value, String> {
+        self.get(&format!("/api/v1/events/{}", path_segment(event_id)), &[])
+    }
+
+    /// Stores up to `MAX_BATCH_SIZE` events. The collector validates the whole request
+    /// first, so one malformed event fails all of them with `PostError::Invalid`.
+    pub fn events_batch(&self, events: Vec<Value>) -> Result<BatchResult, PostError> {
+        self.post(
+            "/api/v1/events/batch",
+            serde_json::json!({ "events": events }),
+        )
+    }
+
+    pub fn health(&self) -> Result<ServiceStatus, String> {
+        self.get("/api/v1/health", &[])
+    }
+
+    /// `status` is `not_ready` with the database error while the database is unreachable.
+    pub fn ready(&self) -> Result<ServiceStatus, String> {
+        self.get("/api/v1/ready", &[])
+    }
+
+    pub fn internal_metrics(&self) -> Result<InternalMetrics, String> {
+        self.get("/api/v1/internal/metrics", &[])
+    }
+
+    /// Agents, most recently seen first.
+    pub fn agents(&self) -> Result<Vec<AgentInfo>, String> {
+        self.get::<AgentList>("/api/v1/agents", &[])
+            .map(|list| list.agents)
+    }
+
+    /// Samples newest first. An agent without any metrics (a 404) gives an empty list.
+    pub fn agent_metrics(
+        &self,
+        agent_id: &str,
+        start: Option<&str>,
+        end: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<MetricSample>, String> {
+        let limit = limit.to_string();
+        let mut query = vec![("limit", limit.as_str())];
+        query.extend(start.map(|start| ("start", start)));
+        query.extend(end.map(|end| ("end", end)));
+        let path = format!("/api/v1/metrics/{}", path_segment(agent_id));
+        Ok(self
+            .fetch::<MetricsPage>(&path, &query, true)?
+            .map(|page| page.metrics)
+            .unwrap_or_default())
+    }
+}
+
+fn path_segment(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// FastAPI puts the reason in `detail`: a string for 401/404, a list for validation errors.
+fn status_error(path: &str, code: u16, response: ureq::Response) -> String {
+    let status = response.status_text().to_string();
+    let detail = response
+        .into_json::<Value>()
+        .ok()
+        .and_then(|body| match body.get("detail")? {
+            Value::String(detail) => Some(detail.clone()),
+            other => Some(other.to_string()),
+        })
+        .unwrap_or(status);
+    format!("Collector returned {} for {}: {}", code, path, detail)
+}
+
+/// Turns collector events into rows tagged with `source`. Events that do not fit the
+/// model become rejects numbered by their position in `events`.
+pub fn to_rows(events: Vec<Value>, source: &str) -> (Vec<EventRow>, Vec<RejectedLine>) {
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    for (i, raw) in events.into_iter().enumerate() {
+        match AuditEvent::from_value(&raw) {
+            Ok(event) => rows.push(EventRow::new(&event, raw, source)),
+            Err(e) => rejects.push(RejectedLine::new(i + 1, 0, e.to_string(), &raw.to_string())),
+        }
+    }
+    (rows, rejects)
+}
+
+/// Ends a poller started by `spawn_poller` when dropped, e.g. by being replaced in or
+/// removed from the map that owns it.
+pub struct PollStop {
+    _sender: Sender<()>,
+}
+
+/// Calls `tick` now and then every `interval`. The wait is on a stop channel, so the
+/// thread ends as soon as the `PollStop` goes away rather than after the next tick.
+pub fn spawn_poller<F>(interval: Duration, mut tick: F) -> PollStop
+where
+    F: FnMut() + Send + 'static,
+{
+    let (sender, stop) = mpsc::channel::<()>();
+    thread::spawn(move || loop {
+        tick();
+        match stop.recv_timeout(interval) {
+            Err(RecvTimeoutError::Timeout) => {}
+            Ok(()) | Err(RecvTimeoutError::Disconnected) => return,
+        }
+    });
+    PollStop { _sender: sender }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::time::Instant;
+
+    #[test]
+    fn dropping_the_stop_ends_a_long_wait() {
+        let alive = Arc::new(());
+        let held = alive.clone();
+        let stop = spawn_poller(Duration::from_secs(3600), move || {
+            let _ = &held;
+        });
+        drop(stop);
+        // The thread drops `tick`, and with it `held`, once it returns.
+        let started = Instant::now();
+        while Arc::strong_count(&alive) > 1 {
+            assert!(started.elapsed() < Duration::from_secs(5));
+            thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
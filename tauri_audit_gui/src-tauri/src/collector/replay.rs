@@ -0,0 +1,848 @@
+//! Bulk replay of local audit files into a collector through `POST /api/v1/events/batch`.
+//! Progress is journaled in the app data directory per file (`dev:ino`), so running the
+//! same import again continues after the last batch that was sent, even if the file was
+//! renamed in between.
+
+use super::{Client, CollectorConfig, PostError, MAX_BATCH_SIZE};
+use crate::event::EventRow;
+use crate::jobs::{run_blocking, Job, ProgressKind};
+use crate::loader::{self, Compression, LogFormat};
+use crate::tail::{read_chunk, FileIdentity, TailCursor};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, VecDeque};
+use std::fs::{self, File};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager};
+
+pub const REPLAY_EVENT: &str = "replay://batch";
+
+const JOURNAL_FILE: &str = "replay-journal.json";
+/// Requests per batch for transport errors, 429 and 5xx before the replay gives up.
+const MAX_ATTEMPTS: u32 = 6;
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+/// The collector's default rate-limit window.
+const MAX_BACKOFF: Duration = Duration::from_secs(60);
+const SLEEP_SLICE: Duration = Duration::from_millis(200);
+/// Bytes read at a time while streaming a JSONL file.
+const READ_BYTES: u64 = 1 << 20;
+
+/// How far a file has been replayed into one collector.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JournalEntry {
+    pub base_url: String,
+    /// Where the file was when last replayed.
+    pub path: String,
+    /// Hashes only the bytes before `offset`, so appending keeps the entry valid while
+    /// rewriting what was already sent does not.
+    pub identity: FileIdentity,
+    /// Byte offset and line count just past the last event sent.
+    #[serde(default)]
+    pub offset: u64,
+    #[serde(default)]
+    pub line: usize,
+    /// Parsed events already sent, counted in file order.
+    pub sent: usize,
+    pub accepted: usize,
+    pub failed: usize,
+    pub updated_at: String,
+}
+
+/// Journal entries keyed by collector URL and `dev:ino` (the path where the platform has
+/// no file ids). Read from disk on first use.
+#[derive(Default)]
+pub struct ReplayJournal {
+    entries: Mutex<Option<BTreeMap<String, JournalEntry>>>,
+}
+
+fn journal_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(JOURNAL_FILE))
+}
+
+impl ReplayJournal {
+    fn entries(&self, path: &Path) -> MutexGuard<'_, Option<BTreeMap<String, JournalEntry>>> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.is_none() {
+            // A missing or unreadable journal only means every file starts from the top.
+            *entries = Some(
+                fs::read(path)
+                    .ok()
+                    .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+                    .unwrap_or_default(),
+            );
+        }
+        entries
+    }
+
+    fn get(&self, path: &Path, key: &str) -> Option<JournalEntry> {
+        self.entries(path).as_ref()?.get(key).cloned()
+    }
+
+    fn save(&self, path: &Path, key: &str, entry: &JournalEntry) -> Result<(), String> {
+        let mut guard = self.entries(path);
+        let entries = guard.get_or_insert_with(BTreeMap::new);
+        entries.insert(key.to_string(), entry.clone());
+
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let json = serde_json::to_vec_pretty(entries).map_err(|e| e.to_string())?;
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, json).map_err(|e| e.to_string())?;
+        fs::rename(&tmp, path).map_err(|e| e.to_string())
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReplayError {
+    /// Position among the file's parsed events.
+    pub index: usize,
+    pub event_id: String,
+    pub error: String,
+}
+
+/// Emitted as `replay://batch` after every batch.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchReport {
+    pub path: String,
+    /// 1-based, counted within the file for this run.
+    pub batch: usize,
+    pub first_event: usize,
+    pub sent: usize,
+    pub accepted: usize,
+    pub errors: Vec<ReplayError>,
+    /// Requests it took, including retries and resends without invalid events.
+    pub attempts: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileReplay {
+    pub path: String,
+    pub events: usize,
+    /// Lines that did not parse locally and were not sent.
+    pub unparsed: usize,
+    /// Events skipped because the journal recorded them as sent.
+    pub resumed_at: usize,
+    /// The journaled part of the file was rewritten since, so it was replayed from the top.
+    pub restarted: bool,
+    pub batches: usize,
+    pub accepted: usize,
+    pub failed: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReplaySummary {
+    pub base_url: String,
+    pub files: Vec<FileReplay>,
+    pub accepted: usize,
+    pub failed: usize,
+}
+
+struct Sent {
+    accepted: usize,
+    errors: Vec<(usize, String)>,
+    attempts: u32,
+}
+
+/// Groups FastAPI validation errors (`loc: ["body", "events", i, ...]`) by event position.
+fn invalid_events(details: &[Value]) -> BTreeMap<usize, String> {
+    let mut invalid: BTreeMap<usize, String> = BTreeMap::new();
+    for detail in details {
+        let loc = detail.get("loc").and_then(Value::as_array);
+        let Some(position) = loc
+            .and_then(|loc| loc.get(2))
+            .and_then(Value::as_u64)
+            .map(|i| i as usize)
+        else {
+            continue;
+        };
+        let field = loc
+            .map(|loc| {
+                loc.iter()
+                    .skip(3)
+                    .map(|part| part.as_str().map_or(part.to_string(), str::to_string))
+                    .collect::<Vec<_>>()
+                    .join(".")
+            })
+            .unwrap_or_default();
+        let msg = detail
+            .get("msg")
+            .and_then(Value::as_str)
+            .unwrap_or("invalid");
+        let error = if field.is_empty() {
+            msg.to_string()
+        } else {
+            format!("{}: {}", field, msg)
+        };
+        invalid
+            .entry(position)
+            .and_modify(|errors| {
+                errors.push_str("; ");
+                errors.push_str(&error);
+            })
+            .or_insert(error);
+    }
+    invalid
+}
+
+/// Sleeps in short slices so a cancelled job does not wait out a long backoff.
+fn pause(job: &Job, duration: Duration) -> Result<(), String> {
+    let mut left = duration;
+    while !left.is_zero() {
+        job.check()?;
+        let slice = left.min(SLEEP_SLICE);
+        thread::sleep(slice);
+        left -= slice;
+    }
+    job.check()
+}
+
+/// Sends one batch. Transient failures are retried with exponential backoff (or the
+/// collector's `Retry-After`); events that fail validation are reported and the rest are
+/// sent again without them.
+fn send_batch(client: &Client, job: &Job, events: &[Value]) -> Result<Sent, String> {
+    let mut pending: Vec<usize> = (0..events.len()).collect();
+    let mut sent = Sent {
+        accepted: 0,
+        errors: Vec::new(),
+        attempts: 0,
+    };
+    let mut failures = 0;
+    let mut backoff = INITIAL_BACKOFF;
+
+    while !pending.is_empty() {
+        sent.attempts += 1;
+        let body = pending.iter().map(|&i| events[i].clone()).collect();
+        match client.events_batch(body) {
+            Ok(result) => {
+                sent.accepted += result.accepted;
+                sent.errors.extend(
+                    result
+                        .errors
+                        .into_iter()
+                        .filter_map(|e| pending.get(e.index).map(|&i| (i, e.error))),
+                );
+                break;
+            }
+            Err(PostError::Invalid(details)) => {
+                let invalid: BTreeMap<usize, String> = invalid_events(&details)
+                    .into_iter()
+                    .filter(|(position, _)| *position < pending.len())
+                    .collect();
+                if invalid.is_empty() {
+                    return Err(format!(
+                        "Collector rejected the batch: {}",
+                        Value::Array(details)
+                    ));
+                }
+                for (&position, error) in &invalid {
+                    sent.errors.push((pending[position], error.clone()));
+                }
+                pending = pending
+                    .into_iter()
+                    .enumerate()
+                    .filter(|(position, _)| !invalid.contains_key(position))
+                    .map(|(_, i)| i)
+                    .collect();
+            }
+            Err(PostError::Transient { error, retry_after }) => {
+                failures += 1;
+                if failures >= MAX_ATTEMPTS {
+                    return Err(format!("{} (gave up after {} attempts)", error, failures));
+                }
+                pause(job, retry_after.unwrap_or(backoff).min(MAX_BACKOFF))?;
+                backoff = (backoff * 2).min(MAX_BACKOFF);
+            }
+            Err(PostError::Rejected(error)) => return Err(error),
+        }
+    }
+    sent.errors.sort_by_key(|(i, _)| *i);
+    Ok(sent)
+}
+
+/// The events of one file in order. JSONL is streamed a chunk at a time from the
+/// journaled byte offset; the other formats have no line offsets to resume at, so they
+/// are parsed whole and the events already sent are skipped by count.
+enum Events {
+    Lines {
+        path: PathBuf,
+        cursor: TailCursor,
+        /// Events read but not sent yet, with their line number and byte offset.
+        pending: VecDeque<(EventRow, (usize, u64))>,
+        done: bool,
+    },
+    Parsed {
+        rows: std::vec::IntoIter<EventRow>,
+        len: u64,
+    },
+}
+
+impl Events {
+    /// Up to `n` more events, and how many lines failed to parse on the way.
+    fn take(&mut self, n: usize, job: &Job) -> Result<(Vec<EventRow>, usize), String> {
+        match self {
+            Events::Lines {
+                path,
+                cursor,
+                pending,
+                done,
+            } => {
+                let mut unparsed = 0;
+                while pending.len() < n && !*done {
+                    let chunk = read_chunk(path, cursor, READ_BYTES, job)?;
+                    if chunk.transition.is_some() {
+                        return Err("the file was replaced or truncated during the replay".into());
+                    }
+                    unparsed += chunk.rejects.len();
+                    pending.extend(chunk.rows.into_iter().zip(chunk.positions));
+                    *cursor = chunk.cursor;
+                    *done = !chunk.has_more;
+                }
+                let n = n.min(pending.len());
+                Ok((pending.drain(..n).map(|(row, _)| row).collect(), unparsed))
+            }
+            Events::Parsed { rows, .. } => Ok((rows.by_ref().take(n).collect(), 0)),
+        }
+    }
+
+    /// Byte offset and line count just past the events taken so far.
+    fn position(&self) -> (u64, usize) {
+        match self {
+            Events::Lines {
+                cursor, pending, ..
+            } => match pending.front() {
+                Some((_, (line, offset))) => (*offset, line - 1),
+                None => (cursor.offset, cursor.line),
+            },
+            Events::Parsed { len, .. } => (*len, 0),
+        }
+    }
+}
+
+struct Replay<'a> {
+    client: &'a Client,
+    job: &'a Job,
+    journal: &'a ReplayJournal,
+    journal_file: PathBuf,
+    /// Called after every batch, once the journal has recorded it.
+    report: &'a dyn Fn(BatchReport),
+    batch_size: usize,
+    restart: bool,
+    /// Events sent in this run across all files, for progress.
+    sent: usize,
+}
+
+impl Replay<'_> {
+    fn file(&mut self, path: &str) -> Result<FileReplay, String> {
+        let mut file = File::open(path).map_err(|e| e.to_string())?;
+        let metadata = file.metadata().map_err(|e| e.to_string())?;
+        let identity = FileIdentity::probe(&mut file, &metadata).map_err(|e| e.to_string())?;
+        let key = match identity.file_id.is_empty() {
+            true => format!("{}#{}", self.client.base_url(), path),
+            false => format!("{}#{}", self.client.base_url(), identity.file_id),
+        };
+        let previous = match self.restart {
+            true => None,
+            false => self.journal.get(&self.journal_file, &key),
+        };
+        let size = metadata.len();
+        let mut restarted = false;
+        let mut entry = match previous {
+            Some(entry)
+                if size >= entry.offset
+                    && entry
+                        .identity
+                        .same_head(&mut file, size)
+                        .map_err(|e| e.to_string())? =>
+            {
+                entry
+            }
+            previous => {
+                restarted = previous.is_some();
+                JournalEntry {
+                    base_url: self.client.base_url().to_string(),
+                    path: path.to_string(),
+                    identity,
+                    offset: 0,
+                    line: 0,
+                    sent: 0,
+                    accepted: 0,
+                    failed: 0,
+                    updated_at: Utc::now().to_rfc3339(),
+                }
+            }
+        };
+        entry.path = path.to_string();
+
+        // Appended events are picked up too: only what the journal covers is skipped.
+        let start = entry.sent;
+        let mut summary = FileReplay {
+            path: path.to_string(),
+            events: 0,
+            unparsed: 0,
+            resumed_at: start,
+            restarted,
+            batches: 0,
+            accepted: 0,
+            failed: 0,
+        };
+        let mut events = match loader::format_of(Path::new(path), self.job)? {
+            (LogFormat::Jsonl, Compression::None) => Events::Lines {
+                path: PathBuf::from(path),
+                cursor: TailCursor {
+                    offset: entry.offset,
+                    line: entry.line,
+                    identity: None,
+                },
+                pending: VecDeque::new(),
+                done: false,
+            },
+            _ => {
+                let loaded = loader::load_path(Path::new(path), self.job)?;
+                summary.unparsed = loaded.rejects.len();
+                entry.sent = start.min(loaded.rows.len());
+                summary.resumed_at = entry.sent;
+                let mut rows = loaded.rows.into_iter();
+                rows.by_ref().take(entry.sent).for_each(drop);
+                Events::Parsed { rows, len: size }
+            }
+        };
+
+        loop {
+            self.job.check()?;
+            let (rows, unparsed) = events
+                .take(self.batch_size, self.job)
+                .map_err(|e| format!("{}: {}", path, e))?;
+            summary.unparsed += unparsed;
+            if rows.is_empty() {
+                break;
+            }
+            let first_event = entry.sent;
+            let batch: Vec<Value> = rows.iter().map(|row| row.raw.clone()).collect();
+            let sent = send_batch(self.client, self.job, &batch)
+                .map_err(|e| format!("{}: {}", path, e))?;
+            let errors: Vec<ReplayError> = sent
+                .errors
+                .into_iter()
+                .map(|(i, error)| ReplayError {
+                    index: first_event + i,
+                    event_id: rows[i].event_id.clone(),
+                    error,
+                })
+                .collect();
+
+            let (offset, line) = events.position();
+            let metadata = file.metadata().map_err(|e| e.to_string())?;
+            entry.identity = FileIdentity::probe_prefix(&mut file, &metadata, offset)
+                .map_err(|e| e.to_string())?;
+            entry.offset = offset;
+            entry.line = line;
+            entry.sent = first_event + rows.len();
+            entry.accepted += sent.accepted;
+            entry.failed += errors.len();
+            entry.updated_at = Utc::now().to_rfc3339();
+            self.journal.save(&self.journal_file, &key, &entry)?;
+
+            summary.batches += 1;
+            summary.accepted += sent.accepted;
+            summary.failed += errors.len();
+            self.sent += rows.len();
+            (self.report)(BatchReport {
+                path: path.to_string(),
+                batch: summary.batches,
+                first_event,
+                sent: rows.len(),
+                accepted: sent.accepted,
+                errors,
+                attempts: sent.attempts,
+            });
+            self.job.report(ProgressKind::Sent { sent: self.sent });
+        }
+        summary.events = entry.sent;
+        Ok(summary)
+    }
+}
+
+/// Sends the parsed events of every file to the collector in batches of `batch_size`
+/// (at most 100), reporting each batch as `replay://batch`. Files already in the journal
+/// continue after their last sent batch unless `restart` is set.
+#[tauri::command]
+pub async fn collector_replay(
+    app: AppHandle,
+    collector: CollectorConfig,
+    paths: Vec<String>,
+    batch_size: Option<usize>,
+    restart: Option<bool>,
+    job_id: Option<String>,
+) -> Result<ReplaySummary, String> {
+    run_blocking(app.clone(), job_id, move |job| {
+        let client = Client::new(&collector)?;
+        let report = |report: BatchReport| {
+            let _ = app.emit(REPLAY_EVENT, report);
+        };
+        let mut replay = Replay {
+            client: &client,
+            job,
+            journal: &app.state::<ReplayJournal>(),
+            journal_file: journal_file(&app)?,
+            report: &report,
+            batch_size: batch_size
+                .unwrap_or(MAX_BATCH_SIZE)
+                .clamp(1, MAX_BATCH_SIZE),
+            restart: restart.unwrap_or(false),
+            sent: 0,
+        };
+        let files = paths
+            .iter()
+            .map(|path| replay.file(path))
+            .collect::<Result<Vec<_>, String>>()?;
+        Ok(ReplaySummary {
+            base_url: client.base_url().to_string(),
+            accepted: files.iter().map(|file| file.accepted).sum(),
+            failed: files.iter().map(|file| file.failed).sum(),
+            files,
+        })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::cell::RefCell;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::sync::Arc;
+
+    /// What the stand-in collector did with the events it was sent.
+    #[derive(Default)]
+    struct Collector {
+        /// Event ids of every request, in the order they arrived.
+        requests: Vec<Vec<String>>,
+        stored: Vec<String>,
+        /// Answer the next request with a 429.
+        busy: bool,
+    }
+
+    /// `POST /api/v1/events/batch` as `collector/api` answers it: ids starting with `bad`
+    /// fail validation (422, nothing stored), ids starting with `dup` fail to store.
+    fn respond(collector: &mut Collector, events: &[Value]) -> (&'static str, String) {
+        let ids: Vec<String> = events
+            .iter()
+            .map(|event| event["event_id"].as_str().unwrap().to_string())
+            .collect();
+        collector.requests.push(ids.clone());
+        if std::mem::take(&mut collector.busy) {
+            return (
+                "429 Too Many Requests",
+                json!({"detail": "slow down"}).to_string(),
+            );
+        }
+        let invalid: Vec<Value> = ids
+            .iter()
+            .enumerate()
+            .filter(|(_, id)| id.starts_with("bad"))
+            .map(|(i, _)| json!({"loc": ["body", "events", i, "timestamp"], "msg": "field required"}))
+            .collect();
+        if !invalid.is_empty() {
+            return (
+                "422 Unprocessable Entity",
+                json!({ "detail": invalid }).to_string(),
+            );
+        }
+        let mut errors = Vec::new();
+        for (i, id) in ids.into_iter().enumerate() {
+            match id.starts_with("dup") {
+                true => errors.push(json!({"index": i, "error": "duplicate event_id"})),
+                false => collector.stored.push(id),
+            }
+        }
+        let body = json!({"accepted": events.len() - errors.len(), "errors": errors});
+        ("200 OK", body.to_string())
+    }
+
+    fn serve() -> (String, Arc<Mutex<Collector>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let collector = Arc::new(Mutex::new(Collector::default()));
+        let state = collector.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                assert!(request_line.starts_with("POST /api/v1/events/batch "));
+                let mut length = 0;
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    if let Some((name, value)) = header.split_once(':') {
+                        if name.eq_ignore_ascii_case("content-length") {
+                            length = value.trim().parse().unwrap();
+                        }
+                    }
+                    header.clear();
+                }
+                let mut body = vec![0; length];
+                reader.read_exact(&mut body).unwrap();
+                let body: Value = serde_json::from_slice(&body).unwrap();
+                let events = body["events"].as_array().unwrap();
+                let (status, body) = respond(&mut state.lock().unwrap(), events);
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nRetry-After: 0\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (base_url, collector)
+    }
+
+    fn client(base_url: &str) -> Client {
+        Client::new(&CollectorConfig {
+            base_url: base_url.to_string(),
+            api_key: None,
+        })
+        .unwrap()
+    }
+
+    fn temp(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("replay-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn line(id: &str) -> String {
+        format!(
+            "{}\n",
+            json!({"event_id": id, "timestamp": "2026-01-01T00:00:00Z", "action": {"type": "READ"}})
+        )
+    }
+
+    /// Runs `Replay::file` with batches of three, handing every report to `on_batch`.
+    fn replay(
+        client: &Client,
+        job: &Job,
+        journal: &ReplayJournal,
+        journal_file: &Path,
+        path: &Path,
+        on_batch: impl Fn(&BatchReport),
+    ) -> (Result<FileReplay, String>, Vec<BatchReport>) {
+        let reports = RefCell::new(Vec::new());
+        let report = |report: BatchReport| {
+            on_batch(&report);
+            reports.borrow_mut().push(report);
+        };
+        let mut replay = Replay {
+            client,
+            job,
+            journal,
+            journal_file: journal_file.to_path_buf(),
+            report: &report,
+            batch_size: 3,
+            restart: false,
+            sent: 0,
+        };
+        let result = replay.file(path.to_str().unwrap());
+        (result, reports.into_inner())
+    }
+
+    #[test]
+    fn an_interrupted_replay_resumes_without_skipping_events() {
+        let dir = temp("resume");
+        let path = dir.join("audit.jsonl");
+        let ids = ["e1", "bad2", "e3", "e4", "dup5", "e6", "e7", "e8"];
+        let mut text: String = ids[..4].iter().map(|id| line(id)).collect();
+        text.push_str("not json\n");
+        text.extend(ids[4..].iter().map(|id| line(id)));
+        fs::write(&path, text).unwrap();
+        let (base_url, collector) = serve();
+        collector.lock().unwrap().busy = true;
+        let replay_client = client(&base_url);
+        let journal = ReplayJournal::default();
+        let journal_file = dir.join(JOURNAL_FILE);
+
+        // Cancelled once the first batch is journaled, as the Cancel button would.
+        let job = Job::detached();
+        let (result, reports) =
+            replay(&replay_client, &job, &journal, &journal_file, &path, |_| {
+                job.cancel()
+            });
+        assert_eq!(result.unwrap_err(), crate::jobs::CANCELLED);
+        assert_eq!(reports.len(), 1);
+        let first = &reports[0];
+        assert_eq!((first.first_event, first.sent, first.accepted), (0, 3, 2));
+        // A 429, then the full batch, then the batch without the invalid event.
+        assert_eq!(first.attempts, 3);
+        assert_eq!(first.errors.len(), 1);
+        assert_eq!(first.errors[0].index, 1);
+        assert_eq!(first.errors[0].event_id, "bad2");
+        assert_eq!(first.errors[0].error, "timestamp: field required");
+
+        // The journal is keyed by collector and dev:ino, so a rename keeps the progress.
+        let renamed = dir.join("audit.jsonl.1");
+        fs::rename(&path, &renamed).unwrap();
+        let identity = {
+            let mut file = File::open(&renamed).unwrap();
+            let metadata = file.metadata().unwrap();
+            FileIdentity::probe(&mut file, &metadata).unwrap()
+        };
+        let key = format!("{}#{}", base_url, identity.file_id);
+        let entry = journal.get(&journal_file, &key).unwrap();
+        assert_eq!((entry.sent, entry.accepted, entry.failed), (3, 2, 1));
+        let on_disk: BTreeMap<String, JournalEntry> =
+            serde_json::from_slice(&fs::read(&journal_file).unwrap()).unwrap();
+        assert_eq!(on_disk.keys().collect::<Vec<_>>(), vec![&key]);
+
+        let job = Job::detached();
+        let (result, reports) = replay(
+            &replay_client,
+            &job,
+            &journal,
+            &journal_file,
+            &renamed,
+            |_| {},
+        );
+        let summary = result.unwrap();
+        assert_eq!(summary.resumed_at, 3);
+        assert!(!summary.restarted);
+        assert_eq!(summary.unparsed, 1);
+        assert_eq!(
+            (summary.events, summary.accepted, summary.failed),
+            (8, 4, 1)
+        );
+        let rejected: Vec<_> = reports
+            .iter()
+            .flat_map(|report| &report.errors)
+            .map(|error| (error.index, error.event_id.as_str(), error.error.as_str()))
+            .collect();
+        assert_eq!(rejected, vec![(4, "dup5", "duplicate event_id")]);
+
+        let collector = collector.lock().unwrap();
+        assert_eq!(collector.stored, ["e1", "e3", "e4", "e6", "e7", "e8"]);
+        let sent: Vec<&String> = collector.requests.iter().skip(3).flatten().collect();
+        assert_eq!(sent, ids[3..].iter().collect::<Vec<_>>());
+
+        // Another collector has no progress for the file yet.
+        let (other_url, other) = serve();
+        let other_client = client(&other_url);
+        let (result, _) = replay(
+            &other_client,
+            &job,
+            &journal,
+            &journal_file,
+            &renamed,
+            |_| {},
+        );
+        assert_eq!(result.unwrap().resumed_at, 0);
+        assert_eq!(other.lock().unwrap().stored.len(), 6);
+    }
+
+    #[test]
+    fn positions_point_past_the_events_taken() {
+        let dir = temp("position");
+        let path = dir.join("audit.jsonl");
+        let lines = [
+            line("e1"),
+            "\n".to_string(),
+            "oops\n".to_string(),
+            line("e2"),
+            line("e3"),
+        ];
+        fs::write(&path, lines.concat()).unwrap();
+        let job = Job::detached();
+        let mut events = Events::Lines {
+            path: path.clone(),
+            cursor: TailCursor::default(),
+            pending: VecDeque::new(),
+            done: false,
+        };
+
+        let (rows, unparsed) = events.take(2, &job).unwrap();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(unparsed, 1);
+        // e3 is still pending: resuming starts at its line.
+        let e3 = lines[..4].concat().len() as u64;
+        assert_eq!(events.position(), (e3, 4));
+
+        let mut resumed = Events::Lines {
+            path: path.clone(),
+            cursor: TailCursor {
+                offset: e3,
+                line: 4,
+                identity: None,
+            },
+            pending: VecDeque::new(),
+            done: false,
+        };
+        let (rows, _) = resumed.take(2, &job).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].event_id, "e3");
+        assert_eq!(resumed.position(), (fs::metadata(&path).unwrap().len(), 5));
+    }
+
+    #[test]
+    fn validation_errors_are_grouped_by_event() {
+        let details = vec![
+            json!({"loc": ["body", "events", 2, "actor", "type"], "msg": "field required"}),
+            json!({"loc": ["body", "events", 0], "msg": "not an object"}),
+            json!({"loc": ["body", "events", 2, "tags", 1], "msg": "not a string"}),
+            json!({"loc": ["body"], "msg": "ignored"}),
+        ];
+        let invalid = invalid_events(&details);
+        assert_eq!(
+            invalid.into_iter().collect::<Vec<_>>(),
+            vec![
+                (0, "not an object".to_string()),
+                (
+                    2,
+                    "actor.type: field required; tags.1: not a string".to_string()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_events_are_dropped_and_the_rest_resent() {
+        let (base_url, collector) = serve();
+        let client = client(&base_url);
+        let events: Vec<Value> = ["e1", "bad2", "dup3", "bad4", "e5"]
+            .iter()
+            .map(|id| json!({"event_id": id}))
+            .collect();
+        let sent = send_batch(&client, &Job::detached(), &events).unwrap();
+        assert_eq!(sent.accepted, 2);
+        assert_eq!(sent.attempts, 2);
+        let errors: Vec<(usize, &str)> = sent
+            .errors
+            .iter()
+            .map(|(i, error)| (*i, error.as_str()))
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                (1, "timestamp: field required"),
+                (2, "duplicate event_id"),
+                (3, "timestamp: field required"),
+            ]
+        );
+        let collector = collector.lock().unwrap();
+        assert_eq!(collector.requests[1], ["e1", "dup3", "e5"]);
+        assert_eq!(collector.stored, ["e1", "e5"]);
+    }
+}
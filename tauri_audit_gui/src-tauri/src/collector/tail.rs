@@ -0,0 +1,368 @@
+//! Remote tail: polls `GET /api/v1/events` and pushes the events not seen before as
+//! `tail://events`, so the live view treats a collector like one more tailed stream.
+
+use super::{
+    spawn_poller, to_rows, Client, CollectorConfig, EventFilters, PollStop, MAX_PAGE_LIMIT,
+};
+use crate::tail::watcher::{publish, TailEvents};
+use crate::tail::{TailCursor, TailTransition};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, State};
+
+const DEFAULT_POLL_MS: u64 = 5000;
+/// Newest events shown when a remote tail starts; after that only new ones arrive.
+const DEFAULT_BACKFILL: usize = 100;
+/// How deep one poll pages when a lot arrived since the previous one.
+const MAX_PAGES_PER_POLL: usize = 20;
+/// Event ids remembered for de-duplication.
+const SEEN_CAPACITY: usize = 100_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RemoteTailStatus {
+    pub tail_id: String,
+    pub base_url: String,
+    pub poll_interval_ms: u64,
+    /// Events delivered so far.
+    pub received: usize,
+    /// Matching events on the collector at the last successful poll.
+    pub total: Option<u64>,
+}
+
+/// Ids already delivered; the oldest are forgotten once the capacity is reached.
+#[derive(Default)]
+struct Seen {
+    ids: HashSet<String>,
+    order: VecDeque<String>,
+}
+
+impl Seen {
+    /// Returns false for an id that was delivered before.
+    fn insert(&mut self, id: &str) -> bool {
+        if !self.ids.insert(id.to_string()) {
+            return false;
+        }
+        self.order.push_back(id.to_string());
+        if self.order.len() > SEEN_CAPACITY {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+        true
+    }
+}
+
+struct Poller {
+    client: Client,
+    filters: EventFilters,
+    backfill: usize,
+    seen: Seen,
+    total: Option<u64>,
+    /// The last error reported, so a collector that stays down is reported once.
+    error: Option<String>,
+}
+
+impl Poller {
+    /// Returns the unseen events, oldest first. The API only sorts by event timestamp, so
+    /// paging continues until as many unseen events turned up as `total` grew by; events
+    /// stored late with older timestamps are found that way too. The first poll marks a
+    /// full page as seen but delivers only the newest `backfill` events of it.
+    fn poll(&mut self) -> Result<Vec<Value>, String> {
+        let first = self.total.is_none();
+        let limit = MAX_PAGE_LIMIT;
+        let mut fresh = Vec::new();
+        let mut expected = None;
+        let mut offset = 0;
+
+        for _ in 0..MAX_PAGES_PER_POLL {
+            let page = self.client.events(&self.filters, limit, offset)?;
+            if offset == 0 {
+                expected = self.total.map(|total| page.total.saturating_sub(total));
+                self.total = Some(page.total);
+            }
+            let count = page.events.len();
+            for event in page.events {
+                let unseen = match event.get("event_id").and_then(Value::as_str) {
+                    Some(id) => self.seen.insert(id),
+                    None => true,
+                };
+                if unseen {
+                    fresh.push(event);
+                }
+            }
+            let found_all = expected.is_none_or(|expected| fresh.len() as u64 >= expected);
+            if found_all || count < limit {
+                break;
+            }
+            offset += count;
+        }
+        if first {
+            fresh.truncate(self.backfill);
+        }
+        fresh.reverse();
+        Ok(fresh)
+    }
+}
+
+struct RemoteTail {
+    _poller: PollStop,
+    status: Arc<Mutex<RemoteTailStatus>>,
+}
+
+#[derive(Default)]
+pub struct RemoteTails {
+    tails: Mutex<HashMap<String, RemoteTail>>,
+}
+
+/// Starts polling the collector every `poll_interval_ms`, pushing new events as
+/// `tail://events` tagged with `tail_id` (in `session_id`) and the collector's host.
+#[tauri::command]
+pub fn remote_tail_start(
+    app: AppHandle,
+    tails: State<'_, RemoteTails>,
+    tail_id: String,
+    collector: CollectorConfig,
+    filters: Option<EventFilters>,
+    backfill: Option<usize>,
+    poll_interval_ms: Option<u64>,
+) -> Result<RemoteTailStatus, String> {
+    let client = Client::new(&collector)?;
+    let base_url = client.base_url().to_string();
+    let stream = base_url
+        .split_once("://")
+        .map_or(base_url.as_str(), |(_, host)| host)
+        .to_string();
+    let poll_interval_ms = poll_interval_ms.unwrap_or(DEFAULT_POLL_MS);
+    let status = Arc::new(Mutex::new(RemoteTailStatus {
+        tail_id: tail_id.clone(),
+        base_url: base_url.clone(),
+        poll_interval_ms,
+        received: 0,
+        total: None,
+    }));
+    let mut poller = Poller {
+        client,
+        filters: filters.unwrap_or_default(),
+        backfill: backfill.unwrap_or(DEFAULT_BACKFILL),
+        seen: Seen::default(),
+        total: None,
+        error: None,
+    };
+
+    let started = status.lock().unwrap().clone();
+    let key = tail_id.clone();
+    let tick_status = status.clone();
+    let tick = move || {
+        let (events, transition) = match poller.poll() {
+            Ok(events) => {
+                poller.error = None;
+                (events, None)
+            }
+            Err(error) if poller.error.as_ref() == Some(&error) => return,
+            Err(error) => {
+                poller.error = Some(error.clone());
+                (Vec::new(), Some(TailTransition::Unreachable { error }))
+            }
+        };
+        {
+            let mut status = tick_status.lock().unwrap();
+            status.received += events.len();
+            status.total = poller.total;
+        }
+        if events.is_empty() && transition.is_none() {
+            return;
+        }
+        let (rows, rejects) = to_rows(events, &stream);
+        let batch = TailEvents {
+            session_id: Some(tail_id.clone()),
+            stream: stream.clone(),
+            path: base_url.clone(),
+            events: rows.len(),
+            rejects,
+            transition,
+            cursor: TailCursor::default(),
+        };
+        publish(&app, rows, batch);
+    };
+    // Replacing an existing tail with the same id drops its `PollStop` and stops it.
+    tails.tails.lock().unwrap().insert(
+        key,
+        RemoteTail {
+            _poller: spawn_poller(Duration::from_millis(poll_interval_ms.max(1)), tick),
+            status,
+        },
+    );
+    Ok(started)
+}
+
+#[tauri::command]
+pub fn remote_tail_stop(
+    tails: State<'_, RemoteTails>,
+    tail_id: String,
+) -> Option<RemoteTailStatus> {
+    let tail = tails.tails.lock().unwrap().remove(&tail_id)?;
+    let status = tail.status.lock().unwrap().clone();
+    Some(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{DateTime, Duration as Span, Utc};
+    use serde_json::json;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    fn event(id: &str, secs: i64) -> Value {
+        let base: DateTime<Utc> = "2026-01-01T00:00:00Z".parse().unwrap();
+        json!({"event_id": id, "timestamp": (base + Span::seconds(secs)).to_rfc3339()})
+    }
+
+    /// A collector on a local port answering `GET /api/v1/events` from `events`, newest
+    /// first like the real one. Returns its base URL and the offsets requested.
+    fn serve(events: Arc<Mutex<Vec<Value>>>) -> (String, Arc<Mutex<Vec<usize>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}", listener.local_addr().unwrap());
+        let offsets = Arc::new(Mutex::new(Vec::new()));
+        let log = offsets.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    return;
+                };
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).unwrap();
+                let mut header = String::new();
+                while reader.read_line(&mut header).unwrap() > 2 {
+                    header.clear();
+                }
+                let target = request_line.split_whitespace().nth(1).unwrap();
+                let query = target.strip_prefix("/api/v1/events?").unwrap();
+                let (mut limit, mut offset) = (100, 0);
+                for (key, value) in query.split('&').filter_map(|pair| pair.split_once('=')) {
+                    match key {
+                        "limit" => limit = value.parse().unwrap(),
+                        "offset" => offset = value.parse().unwrap(),
+                        other => panic!("unexpected parameter {}", other),
+                    }
+                }
+                log.lock().unwrap().push(offset);
+                let mut events = events.lock().unwrap().clone();
+                events.sort_by(|a, b| b["timestamp"].as_str().cmp(&a["timestamp"].as_str()));
+                let page: Vec<&Value> = events.iter().skip(offset).take(limit).collect();
+                let body = json!({"total": events.len(), "events": page}).to_string();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        (base_url, offsets)
+    }
+
+    fn ids(events: &[Value]) -> Vec<&str> {
+        events
+            .iter()
+            .map(|event| event["event_id"].as_str().unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn polls_page_until_every_new_event_is_found() {
+        // Ten seconds apart, so late events can be slotted in between.
+        let initial: Vec<Value> = (0..300)
+            .map(|i| event(&format!("a{}", i), i * 10))
+            .collect();
+        let store = Arc::new(Mutex::new(initial));
+        let (base_url, offsets) = serve(store.clone());
+        let mut poller = Poller {
+            client: Client::new(&CollectorConfig {
+                base_url,
+                api_key: None,
+            })
+            .unwrap(),
+            filters: EventFilters::default(),
+            backfill: 5,
+            seen: Seen::default(),
+            total: None,
+            error: None,
+        };
+
+        // The first poll shows the newest few, oldest first.
+        let fresh = poller.poll().unwrap();
+        assert_eq!(ids(&fresh), ["a295", "a296", "a297", "a298", "a299"]);
+        assert_eq!(poller.total, Some(300));
+        assert_eq!(*offsets.lock().unwrap(), [0]);
+
+        // Nothing arrived: one request, nothing delivered.
+        assert!(poller.poll().unwrap().is_empty());
+
+        // More than a page arrived between polls.
+        store
+            .lock()
+            .unwrap()
+            .extend((0..700).map(|i| event(&format!("b{}", i), 3000 + i * 10)));
+        offsets.lock().unwrap().clear();
+        let fresh = poller.poll().unwrap();
+        assert_eq!(fresh.len(), 700);
+        assert_eq!((ids(&fresh)[0], ids(&fresh)[699]), ("b0", "b699"));
+        assert_eq!(*offsets.lock().unwrap(), [0, 500]);
+
+        // New events, plus three stored late with timestamps that sort them deep into
+        // the second and third pages.
+        {
+            let mut store = store.lock().unwrap();
+            store.extend((0..450).map(|i| event(&format!("c{}", i), 10_000 + i * 10)));
+            store.push(event("late1", 3000 + 550 * 10 + 5));
+            store.push(event("late2", 3000 + 150 * 10 + 5));
+            store.push(event("late3", 150 * 10 + 5));
+        }
+        offsets.lock().unwrap().clear();
+        let fresh = poller.poll().unwrap();
+        assert_eq!(poller.total, Some(1453));
+        assert_eq!(*offsets.lock().unwrap(), [0, 500, 1000]);
+        assert_eq!(fresh.len(), 453);
+        assert_eq!(&ids(&fresh)[..3], ["late3", "late2", "late1"]);
+        assert_eq!(ids(&fresh)[452], "c449");
+        let mut unique = ids(&fresh);
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), 453);
+    }
+
+    #[test]
+    fn seen_ids_are_delivered_once() {
+        let mut seen = Seen::default();
+        assert!(seen.insert("a"));
+        assert!(seen.insert("b"));
+        assert!(!seen.insert("a"));
+        assert!(!seen.insert("b"));
+        // A repeat does not count as new again or take another slot.
+        assert_eq!(seen.order.len(), 2);
+    }
+
+    #[test]
+    fn the_oldest_ids_are_forgotten_at_capacity() {
+        let mut seen = Seen::default();
+        for i in 0..SEEN_CAPACITY {
+            assert!(seen.insert(&i.to_string()));
+        }
+        assert!(seen.insert("one more"));
+        assert_eq!(
+            (seen.ids.len(), seen.order.len()),
+            (SEEN_CAPACITY, SEEN_CAPACITY)
+        );
+        // Only the very first id was dropped to make room.
+        assert!(seen.insert("0"));
+        assert!(!seen.insert("2"));
+        assert!(!seen.insert("one more"));
+    }
+}
@@ -0,0 +1,360 @@
+//! Typed audit event model mirroring `schema/audit_event.schema.json`.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SessionContext {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub correlation_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActorContext {
+    #[serde(rename = "type", skip_serializing_if = "String::is_empty")]
+    pub actor_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    pub roles: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ResourceInfo {
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub resource_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionResult {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rows_affected: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub data_size_bytes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ActionContext {
+    #[serde(rename = "type", skip_serializing_if = "String::is_empty")]
+    pub action_type: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub category: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub operation: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    pub resource: ResourceInfo,
+    pub parameters: Map<String, Value>,
+    pub result: ActionResult,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PerformanceMetrics {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_time_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_mb: Option<f64>,
+    pub slow_query: bool,
+    pub threshold_exceeded: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ErrorInfo {
+    pub occurred: bool,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub error_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stack_trace: Option<String>,
+    pub handled: bool,
+}
+
+impl Default for ErrorInfo {
+    fn default() -> Self {
+        ErrorInfo {
+            occurred: false,
+            error_type: None,
+            message: None,
+            stack_trace: None,
+            handled: true,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AuditEvent {
+    pub event_id: String,
+    pub timestamp: String,
+    pub version: String,
+    pub session: SessionContext,
+    pub actor: ActorContext,
+    pub action: ActionContext,
+    pub performance: PerformanceMetrics,
+    pub error: ErrorInfo,
+    pub system: Map<String, Value>,
+    pub custom: Map<String, Value>,
+    pub metadata: Map<String, Value>,
+}
+
+impl Default for AuditEvent {
+    fn default() -> Self {
+        AuditEvent {
+            event_id: String::new(),
+            timestamp: String::new(),
+            version: "1.0.0".to_string(),
+            session: SessionContext::default(),
+            actor: ActorContext::default(),
+            action: ActionContext::default(),
+            performance: PerformanceMetrics::default(),
+            error: ErrorInfo::default(),
+            system: Map::new(),
+            custom: Map::new(),
+            metadata: Map::new(),
+        }
+    }
+}
+
+impl AuditEvent {
+    /// Older lines whose `action` or `actor` is not an object (`"action": "login"`) still
+    /// parse, with that part left empty; `EventRow::new` reads it from the raw value.
+    pub fn from_value(value: &Value) -> Result<AuditEvent, serde_json::Error> {
+        let legacy = |key: &str| value.get(key).is_some_and(|part| !part.is_object());
+        if !legacy("action") && !legacy("actor") {
+            return AuditEvent::deserialize(value);
+        }
+        let mut value = value.clone();
+        if let Some(fields) = value.as_object_mut() {
+            fields.retain(|key, part| {
+                !matches!(key.as_str(), "action" | "actor") || part.is_object()
+            });
+        }
+        AuditEvent::deserialize(&value)
+    }
+}
+
+/// A top-level string or number of `raw`, for the flat fields of older events.
+fn flat(raw: &Value, key: &str) -> Option<String> {
+    match raw.get(key)? {
+        Value::String(s) if !s.is_empty() => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        _ => None,
+    }
+}
+
+fn non_empty(value: &str) -> Option<String> {
+    Some(value.to_string()).filter(|v| !v.is_empty())
+}
+
+/// Normalized table row handed to the webview, same shape as `normalizeEvent` in `app.js`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EventRow {
+    pub event_id: String,
+    pub timestamp: String,
+    pub action: String,
+    pub category: String,
+    pub user: String,
+    pub status: String,
+    pub source: String,
+    pub raw: Value,
+}
+
+impl EventRow {
+    /// Falls back to the flat fields of older events (`id`, `action`, `category`, `user`,
+    /// `status`, a top-level `result`) as `normalizeEvent` does.
+    pub fn new(event: &AuditEvent, raw: Value, source: &str) -> Self {
+        let user = event
+            .actor
+            .username
+            .clone()
+            .or_else(|| event.actor.id.clone())
+            .or_else(|| flat(&raw, "user"))
+            .unwrap_or_default();
+        let status = match raw.pointer("/action/result") {
+            Some(_) => non_empty(&event.action.result.status),
+            None => raw.get("result").and_then(|result| flat(result, "status")),
+        };
+        EventRow {
+            event_id: non_empty(&event.event_id)
+                .or_else(|| flat(&raw, "id"))
+                .unwrap_or_default(),
+            timestamp: event.timestamp.clone(),
+            action: event
+                .action
+                .operation
+                .clone()
+                .or_else(|| flat(&raw, "action"))
+                .unwrap_or_default(),
+            category: non_empty(&event.action.category)
+                .or_else(|| flat(&raw, "category"))
+                .unwrap_or_default(),
+            user,
+            status: status.or_else(|| flat(&raw, "status")).unwrap_or_default(),
+            source: source.to_string(),
+            raw,
+        }
+    }
+
+    pub fn from_event(event: AuditEvent, source: &str) -> Self {
+        let raw = serde_json::to_value(&event).unwrap_or(Value::Null);
+        EventRow::new(&event, raw, source)
+    }
+}
+
+/// Display name used to tag rows with the file they came from.
+pub fn source_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.to_string_lossy().into_owned())
+}
+
+/// Parses the timestamp shapes Vigil writes; naive values are treated as UTC like
+/// `AuditEvent.from_dict` does.
+pub fn parse_timestamp(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(ts) = DateTime::parse_from_rfc3339(value) {
+        return Some(ts.with_timezone(&Utc));
+    }
+    ["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"]
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(value, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+        })
+        .map(|naive| naive.and_utc())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn row(raw: Value) -> EventRow {
+        let event = AuditEvent::from_value(&raw).unwrap();
+        EventRow::new(&event, raw, "audit.log")
+    }
+
+    #[test]
+    fn nested_fields_come_first() {
+        let row = row(json!({
+            "event_id": "e1",
+            "id": "legacy",
+            "actor": {"username": "alice", "id": "u1"},
+            "action": {"operation": "login", "category": "AUTH", "result": {"status": "SUCCESS"}},
+            "user": "bob",
+            "category": "DATABASE",
+            "status": "FAILURE",
+        }));
+        assert_eq!(
+            (
+                row.event_id.as_str(),
+                row.action.as_str(),
+                row.category.as_str()
+            ),
+            ("e1", "login", "AUTH")
+        );
+        assert_eq!(
+            (row.user.as_str(), row.status.as_str()),
+            ("alice", "SUCCESS")
+        );
+    }
+
+    #[test]
+    fn id_falls_back_to_raw_id() {
+        assert_eq!(row(json!({"id": "legacy-1"})).event_id, "legacy-1");
+        assert_eq!(row(json!({"id": 42})).event_id, "42");
+    }
+
+    #[test]
+    fn category_falls_back_to_raw_category() {
+        assert_eq!(row(json!({"category": "AUTH"})).category, "AUTH");
+        let row = row(json!({"action": {"type": "READ"}, "category": "DATA"}));
+        assert_eq!(row.category, "DATA");
+    }
+
+    #[test]
+    fn user_falls_back_to_actor_id_then_raw_user() {
+        assert_eq!(
+            row(json!({"actor": {"id": "u1"}, "user": "bob"})).user,
+            "u1"
+        );
+        assert_eq!(row(json!({"user": "bob"})).user, "bob");
+    }
+
+    #[test]
+    fn status_falls_back_to_raw_status() {
+        assert_eq!(row(json!({"status": "FAILURE"})).status, "FAILURE");
+        let row = row(json!({"action": {"result": {"code": "500"}}, "status": "ERROR"}));
+        assert_eq!(row.status, "ERROR");
+    }
+
+    #[test]
+    fn a_top_level_result_is_used_without_an_action_result() {
+        let raw = json!({"action": {"operation": "fetch"}, "result": {"status": "SUCCESS"}});
+        assert_eq!(row(raw).status, "SUCCESS");
+        // As in `normalizeEvent`, an action result hides the top-level one.
+        let raw = json!({"action": {"result": {}}, "result": {"status": "SUCCESS"}});
+        assert_eq!(row(raw).status, "");
+    }
+
+    #[test]
+    fn a_plain_string_action_is_accepted() {
+        let raw = json!({
+            "event_id": "e1",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "action": "login",
+            "actor": "alice",
+            "user": "alice",
+            "status": "SUCCESS",
+        });
+        let event = AuditEvent::from_value(&raw).unwrap();
+        assert_eq!(event.event_id, "e1");
+        assert!(event.action.category.is_empty());
+        let row = row(raw);
+        assert_eq!(row.action, "login");
+        assert_eq!(
+            (row.user.as_str(), row.status.as_str()),
+            ("alice", "SUCCESS")
+        );
+        assert_eq!(row.raw["action"], "login");
+
+        assert!(AuditEvent::from_value(&json!({"action": {"type": 3}})).is_err());
+    }
+}
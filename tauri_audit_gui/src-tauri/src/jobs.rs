@@ -0,0 +1,228 @@
+//! Background execution for heavy commands: progress events and UI-driven cancellation.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Manager, State};
+
+pub const PROGRESS_EVENT: &str = "job://progress";
+pub const CANCELLED: &str = "cancelled";
+
+const REPORT_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ProgressKind {
+    Bytes { read: u64, total: u64 },
+    Events { parsed: usize },
+    Sent { sent: usize },
+    Pages { rendered: usize, total: usize },
+    Done,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct JobProgress {
+    pub job_id: String,
+    #[serde(flatten)]
+    pub kind: ProgressKind,
+}
+
+struct JobInner {
+    id: Option<String>,
+    /// Absent for detached jobs, which have nobody to report to.
+    app: Option<AppHandle>,
+    cancelled: Arc<AtomicBool>,
+    last_report: Mutex<Option<Instant>>,
+}
+
+/// Handle given to blocking work. Jobs without an id neither report nor can be cancelled.
+#[derive(Clone)]
+pub struct Job {
+    inner: Arc<JobInner>,
+}
+
+impl Job {
+    fn new(app: &AppHandle, id: Option<String>, cancelled: Arc<AtomicBool>) -> Job {
+        Job {
+            inner: Arc::new(JobInner {
+                id,
+                app: Some(app.clone()),
+                cancelled,
+                last_report: Mutex::new(None),
+            }),
+        }
+    }
+
+    /// For background work that is neither reported nor cancellable from the UI.
+    pub fn untracked(app: &AppHandle) -> Job {
+        Job::new(app, None, Arc::new(AtomicBool::new(false)))
+    }
+
+    /// A job outside any app, for unit tests of blocking work.
+    #[cfg(test)]
+    pub fn detached() -> Job {
+        Job {
+            inner: Arc::new(JobInner {
+                id: None,
+                app: None,
+                cancelled: Arc::new(AtomicBool::new(false)),
+                last_report: Mutex::new(None),
+            }),
+        }
+    }
+
+    #[cfg(test)]
+    pub fn cancel(&self) {
+        self.inner.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Relaxed)
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.is_cancelled() {
+            Err(CANCELLED.to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn emit(&self, kind: ProgressKind) {
+        if let (Some(id), Some(app)) = (&self.inner.id, &self.inner.app) {
+            let _ = app.emit(
+                PROGRESS_EVENT,
+                JobProgress {
+                    job_id: id.clone(),
+                    kind,
+                },
+            );
+        }
+    }
+
+    /// Emits at most every `REPORT_INTERVAL` so tight loops can call this freely.
+    pub fn report(&self, kind: ProgressKind) {
+        if self.inner.id.is_none() {
+            return;
+        }
+        let mut last = self.inner.last_report.lock().unwrap();
+        if last.is_some_and(|t| t.elapsed() < REPORT_INTERVAL) {
+            return;
+        }
+        *last = Some(Instant::now());
+        drop(last);
+        self.emit(kind);
+    }
+}
+
+#[derive(Default)]
+pub struct JobRegistry {
+    jobs: Mutex<HashMap<String, Arc<AtomicBool>>>,
+}
+
+impl JobRegistry {
+    fn start(&self, app: &AppHandle, id: Option<String>) -> Job {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if let Some(id) = &id {
+            self.jobs
+                .lock()
+                .unwrap()
+                .insert(id.clone(), cancelled.clone());
+        }
+        Job::new(app, id, cancelled)
+    }
+
+    fn finish(&self, job: &Job) {
+        if let Some(id) = &job.inner.id {
+            self.jobs.lock().unwrap().remove(id);
+        }
+    }
+}
+
+/// Runs `work` on the blocking pool so the window stays responsive.
+pub async fn run_blocking<T, F>(
+    app: AppHandle,
+    job_id: Option<String>,
+    work: F,
+) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce(&Job) -> Result<T, String> + Send + 'static,
+{
+    let job = app.state::<JobRegistry>().start(&app, job_id);
+    let worker = job.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || work(&worker))
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|r| r);
+    app.state::<JobRegistry>().finish(&job);
+    job.emit(ProgressKind::Done);
+    result
+}
+
+#[tauri::command]
+pub fn cancel_job(registry: State<'_, JobRegistry>, job_id: String) -> bool {
+    match registry.jobs.lock().unwrap().get(&job_id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
+
+/// Counts bytes pulled from the underlying file and aborts reads once the job is cancelled.
+pub struct ProgressReader<R> {
+    inner: R,
+    job: Job,
+    read: u64,
+    total: u64,
+}
+
+impl<R> ProgressReader<R> {
+    pub fn new(inner: R, job: &Job, total: u64) -> Self {
+        ProgressReader {
+            inner,
+            job: job.clone(),
+            read: 0,
+            total,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.job.is_cancelled() {
+            return Err(io::Error::other(CANCELLED));
+        }
+        let n = self.inner.read(buf)?;
+        self.read += n as u64;
+        self.job.report(ProgressKind::Bytes {
+            read: self.read,
+            total: self.total,
+        });
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_stop_once_cancelled() {
+        let job = Job::detached();
+        let mut reader = ProgressReader::new(&b"abcdef"[..], &job, 6);
+        let mut buf = [0u8; 4];
+        assert_eq!(reader.read(&mut buf).unwrap(), 4);
+        assert!(job.check().is_ok());
+
+        job.cancel();
+        let err = reader.read(&mut buf).unwrap_err();
+        assert_eq!(err.to_string(), CANCELLED);
+        assert_eq!(job.check().unwrap_err(), CANCELLED);
+    }
+}
@@ -0,0 +1,409 @@
+//! Byte-offset index of JSONL lines so any page of a huge file can be served by seeking.
+
+use crate::event::{source_name, EventRow};
+use crate::jobs::{run_blocking, Job, ProgressReader};
+use crate::loader::{self, Compression, RejectedLine};
+use crate::store::ResultView;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, BufWriter, Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::UNIX_EPOCH;
+use tauri::{AppHandle, Manager};
+
+const INDEX_MAGIC: &[u8; 8] = b"VGLIDX01";
+/// Magic plus size, mtime (two words), indexed length and line count.
+const HEADER_LEN: u64 = 8 + 5 * 8;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct FileStamp {
+    size: u64,
+    mtime_ns: u128,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Result<FileStamp, String> {
+        let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+        let mtime_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos())
+            .unwrap_or(0);
+        Ok(FileStamp {
+            size: metadata.len(),
+            mtime_ns,
+        })
+    }
+}
+
+/// Start offsets of every non-blank, newline-terminated line. A trailing line without
+/// `\n` is still being written and is left out until it is complete.
+pub struct LineIndex {
+    stamp: FileStamp,
+    indexed_len: u64,
+    offsets: Vec<u64>,
+}
+
+impl LineIndex {
+    pub fn len(&self) -> usize {
+        self.offsets.len()
+    }
+
+    fn scan_from(&mut self, path: &Path, start: u64, job: &Job) -> Result<(), String> {
+        let mut file = File::open(path).map_err(|e| e.to_string())?;
+        let remaining = self.stamp.size.saturating_sub(start);
+        file.seek(SeekFrom::Start(start))
+            .map_err(|e| e.to_string())?;
+        let mut reader =
+            BufReader::with_capacity(1 << 20, ProgressReader::new(file, job, remaining));
+        let mut offset = start;
+        let mut buf = Vec::new();
+        loop {
+            buf.clear();
+            let read = reader
+                .read_until(b'\n', &mut buf)
+                .map_err(|e| e.to_string())?;
+            if read == 0 || buf.last() != Some(&b'\n') {
+                break;
+            }
+            if buf.iter().any(|b| !b.is_ascii_whitespace()) {
+                self.offsets.push(offset);
+            }
+            offset += read as u64;
+        }
+        self.indexed_len = offset;
+        Ok(())
+    }
+
+    fn build(path: &Path, stamp: FileStamp, job: &Job) -> Result<LineIndex, String> {
+        let mut index = LineIndex {
+            stamp,
+            indexed_len: 0,
+            offsets: Vec::new(),
+        };
+        index.scan_from(path, 0, job)?;
+        Ok(index)
+    }
+
+    /// Append-only growth keeps the existing offsets; anything else means a rebuild.
+    fn refresh(
+        mut self,
+        path: &Path,
+        stamp: FileStamp,
+        job: &Job,
+    ) -> Result<(LineIndex, bool), String> {
+        if self.stamp == stamp {
+            return Ok((self, true));
+        }
+        if stamp.size >= self.indexed_len && ends_with_newline(path, self.indexed_len)? {
+            self.stamp = stamp;
+            let start = self.indexed_len;
+            self.scan_from(path, start, job)?;
+            return Ok((self, false));
+        }
+        Ok((LineIndex::build(path, stamp, job)?, false))
+    }
+
+    fn read(cache_path: &Path) -> Option<LineIndex> {
+        let mut reader = BufReader::new(File::open(cache_path).ok()?);
+        let mut magic = [0u8; 8];
+        reader.read_exact(&mut magic).ok()?;
+        if &magic != INDEX_MAGIC {
+            return None;
+        }
+        let mut word = [0u8; 8];
+        let mut next = |reader: &mut BufReader<File>| -> Option<u64> {
+            reader.read_exact(&mut word).ok()?;
+            Some(u64::from_le_bytes(word))
+        };
+        let size = next(&mut reader)?;
+        let mtime_hi = next(&mut reader)?;
+        let mtime_lo = next(&mut reader)?;
+        let indexed_len = next(&mut reader)?;
+        let count = next(&mut reader)?;
+        // A truncated or corrupt file must not size the allocation.
+        let file_len = reader.get_ref().metadata().ok()?.len();
+        if count.checked_mul(8)?.checked_add(HEADER_LEN)? != file_len {
+            return None;
+        }
+        let count = count as usize;
+        let mut offsets = Vec::with_capacity(count);
+        for _ in 0..count {
+            offsets.push(next(&mut reader)?);
+        }
+        Some(LineIndex {
+            stamp: FileStamp {
+                size,
+                mtime_ns: ((mtime_hi as u128) << 64) | mtime_lo as u128,
+            },
+            indexed_len,
+            offsets,
+        })
+    }
+
+    fn write(&self, cache_path: &Path) -> Result<(), String> {
+        if let Some(parent) = cache_path.parent() {
+            fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+        }
+        let tmp = cache_path.with_extension("tmp");
+        let mut writer = BufWriter::new(File::create(&tmp).map_err(|e| e.to_string())?);
+        let header = [
+            self.stamp.size,
+            (self.stamp.mtime_ns >> 64) as u64,
+            self.stamp.mtime_ns as u64,
+            self.indexed_len,
+            self.offsets.len() as u64,
+        ];
+        writer.write_all(INDEX_MAGIC).map_err(|e| e.to_string())?;
+        for word in header.iter().chain(self.offsets.iter()) {
+            writer
+                .write_all(&word.to_le_bytes())
+                .map_err(|e| e.to_string())?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+        drop(writer);
+        fs::rename(&tmp, cache_path).map_err(|e| e.to_string())
+    }
+}
+
+fn ends_with_newline(path: &Path, len: u64) -> Result<bool, String> {
+    if len == 0 {
+        return Ok(true);
+    }
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    file.seek(SeekFrom::Start(len - 1))
+        .map_err(|e| e.to_string())?;
+    let mut byte = [0u8; 1];
+    file.read_exact(&mut byte).map_err(|e| e.to_string())?;
+    Ok(byte[0] == b'\n')
+}
+
+/// 64-bit FNV-1a, stable across builds unlike `DefaultHasher`, so cache names survive
+/// an upgrade.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
+fn cache_file(cache_dir: &Path, path: &Path) -> PathBuf {
+    let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let hash = fnv1a(canonical.as_os_str().as_encoded_bytes());
+    let stem = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    cache_dir.join(format!("{}-{:016x}.lidx", stem, hash))
+}
+
+/// In-memory copies of the indexes already opened this session.
+#[derive(Default)]
+pub struct LineIndexCache {
+    indexes: Mutex<HashMap<PathBuf, Arc<LineIndex>>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexSummary {
+    pub path: String,
+    pub lines: usize,
+    pub file_size: u64,
+    pub indexed_len: u64,
+    pub from_cache: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IndexedPage {
+    pub path: String,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    /// Events of this page, now held by the result view.
+    pub events: usize,
+    pub rejects: Vec<RejectedLine>,
+}
+
+fn open_index(
+    app: &AppHandle,
+    cache: &LineIndexCache,
+    path: &Path,
+    job: &Job,
+) -> Result<(Arc<LineIndex>, bool), String> {
+    if loader::compression_of(path)? != Compression::None {
+        return Err("Compressed files cannot be indexed; decompress them first".to_string());
+    }
+    let cache_dir = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join("line-index");
+    let cache_path = cache_file(&cache_dir, path);
+    let stamp = FileStamp::of(path)?;
+
+    let in_memory = cache.indexes.lock().unwrap().get(path).cloned();
+    if let Some(index) = in_memory.as_ref().filter(|index| index.stamp == stamp) {
+        return Ok((index.clone(), true));
+    }
+
+    let (index, from_cache) = match LineIndex::read(&cache_path) {
+        Some(index) => index.refresh(path, stamp, job)?,
+        None => (LineIndex::build(path, stamp, job)?, false),
+    };
+    if !from_cache {
+        index.write(&cache_path)?;
+    }
+    let index = Arc::new(index);
+    cache
+        .indexes
+        .lock()
+        .unwrap()
+        .insert(path.to_path_buf(), index.clone());
+    Ok((index, from_cache))
+}
+
+fn read_page(
+    app: &AppHandle,
+    path: &str,
+    page: usize,
+    page_size: usize,
+    job: &Job,
+) -> Result<(IndexedPage, Vec<EventRow>), String> {
+    let file_path = Path::new(path);
+    let (index, _) = open_index(app, &app.state::<LineIndexCache>(), file_path, job)?;
+    let page = page.max(1);
+    let page_size = page_size.max(1);
+    let start = (page - 1).saturating_mul(page_size);
+    let end = start.saturating_add(page_size).min(index.len());
+
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    if start < end {
+        let source = source_name(file_path);
+        let mut file = File::open(file_path).map_err(|e| e.to_string())?;
+        file.seek(SeekFrom::Start(index.offsets[start]))
+            .map_err(|e| e.to_string())?;
+        let mut reader = BufReader::new(file);
+        let mut pos = index.offsets[start];
+        let mut buf = Vec::new();
+        for (i, &offset) in index.offsets[start..end].iter().enumerate() {
+            while pos < offset {
+                buf.clear();
+                let read = reader
+                    .read_until(b'\n', &mut buf)
+                    .map_err(|e| e.to_string())?;
+                if read == 0 {
+                    break;
+                }
+                pos += read as u64;
+            }
+            buf.clear();
+            let read = reader
+                .read_until(b'\n', &mut buf)
+                .map_err(|e| e.to_string())?;
+            pos += read as u64;
+
+            match loader::parse_line_bytes(&buf, start + i + 1, offset, &source) {
+                Some(Ok(row)) => rows.push(row),
+                Some(Err(reject)) => rejects.push(reject),
+                None => {}
+            }
+        }
+    }
+
+    let page = IndexedPage {
+        path: path.to_string(),
+        total: index.len(),
+        page,
+        page_size,
+        events: rows.len(),
+        rejects,
+    };
+    Ok((page, rows))
+}
+
+#[tauri::command]
+pub async fn index_audit_file(
+    app: AppHandle,
+    path: String,
+    job_id: Option<String>,
+) -> Result<IndexSummary, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        let (index, from_cache) = open_index(
+            &handle,
+            &handle.state::<LineIndexCache>(),
+            Path::new(&path),
+            job,
+        )?;
+        Ok(IndexSummary {
+            path,
+            lines: index.len(),
+            file_size: index.stamp.size,
+            indexed_len: index.indexed_len,
+            from_cache,
+        })
+    })
+    .await
+}
+
+/// One page (1-based) of an indexed file, in file order, loaded into the result view.
+#[tauri::command]
+pub async fn read_indexed_page(
+    app: AppHandle,
+    path: String,
+    page: usize,
+    page_size: usize,
+    job_id: Option<String>,
+) -> Result<IndexedPage, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        let (page, rows) = read_page(&handle, &path, page, page_size, job)?;
+        handle.state::<ResultView>().0.replace(rows);
+        Ok(page)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cache_name_is_stable() {
+        // Reference values for 64-bit FNV-1a.
+        assert_eq!(fnv1a(b""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(fnv1a(b"a"), 0xaf63_dc4c_8601_ec8c);
+        let name = cache_file(Path::new("/cache"), Path::new("/no/such/audit.jsonl"));
+        assert_eq!(
+            name,
+            Path::new("/cache").join(format!(
+                "audit.jsonl-{:016x}.lidx",
+                fnv1a(b"/no/such/audit.jsonl")
+            ))
+        );
+    }
+
+    #[test]
+    fn read_rejects_a_count_the_file_cannot_hold() {
+        let path = std::env::temp_dir().join(format!("lidx-count-{}.lidx", std::process::id()));
+        let index = LineIndex {
+            stamp: FileStamp {
+                size: 20,
+                mtime_ns: 1,
+            },
+            indexed_len: 20,
+            offsets: vec![0, 10],
+        };
+        index.write(&path).unwrap();
+        assert_eq!(LineIndex::read(&path).unwrap().offsets, vec![0, 10]);
+
+        // Claim far more offsets than follow the header.
+        let mut bytes = fs::read(&path).unwrap();
+        bytes[40..48].copy_from_slice(&u64::MAX.to_le_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert!(LineIndex::read(&path).is_none());
+        fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,166 @@
+//! Streaming decompression for rotated and archived logs, detected by magic bytes.
+
+use crate::jobs::{Job, ProgressReader};
+use bzip2::read::MultiBzDecoder;
+use flate2::read::MultiGzDecoder;
+use serde::Serialize;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+const BZIP2_MAGIC: &[u8] = b"BZh";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+    Bzip2,
+}
+
+fn sniff(head: &[u8]) -> Compression {
+    if head.starts_with(GZIP_MAGIC) {
+        Compression::Gzip
+    } else if head.starts_with(ZSTD_MAGIC) {
+        Compression::Zstd
+    } else if head.starts_with(BZIP2_MAGIC) {
+        Compression::Bzip2
+    } else {
+        Compression::None
+    }
+}
+
+pub fn detect(path: &Path) -> Result<Compression, String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file);
+    Ok(sniff(reader.fill_buf().map_err(|e| e.to_string())?))
+}
+
+/// Opens `path` and returns a reader over the decompressed bytes. Progress is reported in
+/// bytes of the file on disk, so compressed archives still track against their real size.
+pub fn open(path: &Path, job: &Job) -> Result<(Box<dyn BufRead>, Compression), String> {
+    let file = File::open(path).map_err(|e| e.to_string())?;
+    let total = file.metadata().map_err(|e| e.to_string())?.len();
+    let mut reader = BufReader::new(ProgressReader::new(file, job, total));
+    let compression = sniff(reader.fill_buf().map_err(|e| e.to_string())?);
+
+    let decoded: Box<dyn BufRead> = match compression {
+        Compression::None => Box::new(reader),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(reader))),
+        Compression::Zstd => {
+            let decoder =
+                zstd::stream::read::Decoder::with_buffer(reader).map_err(|e| e.to_string())?;
+            Box::new(BufReader::new(decoder))
+        }
+        Compression::Bzip2 => Box::new(BufReader::new(MultiBzDecoder::new(reader))),
+    };
+    Ok((decoded, compression))
+}
+
+/// Drops a trailing `.gz`/`.zst`/`.bz2` so `audit.csv.gz` is still recognised as CSV.
+pub fn logical_path(path: &Path) -> PathBuf {
+    let compressed_ext = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| matches!(ext, "gz" | "zst" | "zstd" | "bz2"))
+        .unwrap_or(false);
+    if compressed_ext {
+        path.with_extension("")
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Read, Write};
+
+    const LINES: &str = "{\"event_id\":\"e1\"}\n{\"event_id\":\"e2\"}\n";
+
+    /// Writes `bytes` under a name without a compression extension, so only the magic
+    /// bytes can give the format away.
+    fn file(name: &str, bytes: &[u8]) -> PathBuf {
+        let path =
+            std::env::temp_dir().join(format!("compression-{}-{}.log", name, std::process::id()));
+        std::fs::write(&path, bytes).unwrap();
+        path
+    }
+
+    fn round_trip(name: &str, bytes: &[u8], expected: Compression) -> String {
+        let path = file(name, bytes);
+        assert_eq!(detect(&path).unwrap(), expected);
+        let (mut reader, compression) = open(&path, &Job::detached()).unwrap();
+        assert_eq!(compression, expected);
+        let mut text = String::new();
+        reader.read_to_string(&mut text).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        text
+    }
+
+    fn gzip(text: &str) -> Vec<u8> {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(text.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn gzip_is_detected_and_decoded() {
+        assert_eq!(round_trip("gzip", &gzip(LINES), Compression::Gzip), LINES);
+        // Appending with `gzip >>` leaves several members; all of them are read.
+        let mut members = gzip("{\"event_id\":\"e1\"}\n");
+        members.extend(gzip("{\"event_id\":\"e2\"}\n"));
+        assert_eq!(
+            round_trip("gzip-members", &members, Compression::Gzip),
+            LINES
+        );
+    }
+
+    #[test]
+    fn zstd_is_detected_and_decoded() {
+        let bytes = zstd::encode_all(LINES.as_bytes(), 3).unwrap();
+        assert_eq!(round_trip("zstd", &bytes, Compression::Zstd), LINES);
+    }
+
+    #[test]
+    fn bzip2_is_detected_and_decoded() {
+        let mut encoder = bzip2::write::BzEncoder::new(Vec::new(), bzip2::Compression::default());
+        encoder.write_all(LINES.as_bytes()).unwrap();
+        let bytes = encoder.finish().unwrap();
+        assert_eq!(round_trip("bzip2", &bytes, Compression::Bzip2), LINES);
+    }
+
+    #[test]
+    fn anything_else_is_read_as_plain_text() {
+        assert_eq!(
+            round_trip("plain", LINES.as_bytes(), Compression::None),
+            LINES
+        );
+        assert_eq!(round_trip("empty", b"", Compression::None), "");
+        // One byte short of the gzip magic.
+        assert_eq!(round_trip("short", b"\x1f", Compression::None), "\x1f");
+    }
+
+    #[test]
+    fn compression_extensions_are_dropped_from_the_logical_path() {
+        assert_eq!(
+            logical_path(Path::new("a/audit.csv.gz")),
+            Path::new("a/audit.csv")
+        );
+        assert_eq!(
+            logical_path(Path::new("audit.jsonl.zst")),
+            Path::new("audit.jsonl")
+        );
+        assert_eq!(
+            logical_path(Path::new("audit.log.bz2")),
+            Path::new("audit.log")
+        );
+        assert_eq!(
+            logical_path(Path::new("audit.jsonl")),
+            Path::new("audit.jsonl")
+        );
+    }
+}
@@ -0,0 +1,229 @@
+//! Parser for the CSV written by `FileStorage._write_csv`: the flat columns of
+//! `_flatten_event` are put back into the nested event, and any other column is kept
+//! under `custom`.
+
+use super::RejectedLine;
+use crate::event::{AuditEvent, EventRow};
+use csv::{ErrorKind, ReaderBuilder, StringRecord, Trim};
+use serde_json::{Map, Value};
+use std::io::Read;
+
+#[derive(Clone, Copy)]
+enum Cell {
+    Text,
+    Number,
+    Flag,
+}
+
+/// Where each column written by `FileStorage._flatten_event` (or the explorer's own CSV
+/// export) lives in the nested event.
+fn column_target(name: &str) -> Option<(&'static [&'static str], Cell)> {
+    let target: (&'static [&'static str], Cell) = match name {
+        "event_id" => (&["event_id"], Cell::Text),
+        "timestamp" => (&["timestamp"], Cell::Text),
+        "category" => (&["action", "category"], Cell::Text),
+        "action_type" => (&["action", "type"], Cell::Text),
+        "operation" | "action" => (&["action", "operation"], Cell::Text),
+        "username" | "user" => (&["actor", "username"], Cell::Text),
+        "ip_address" => (&["actor", "ip_address"], Cell::Text),
+        "duration_ms" => (&["performance", "duration_ms"], Cell::Number),
+        "status" => (&["action", "result", "status"], Cell::Text),
+        "error_occurred" => (&["error", "occurred"], Cell::Flag),
+        "error_type" => (&["error", "type"], Cell::Text),
+        "error_message" => (&["error", "message"], Cell::Text),
+        _ => return None,
+    };
+    Some(target)
+}
+
+fn convert(column: &str, value: &str, cell: Cell) -> Result<Value, String> {
+    match cell {
+        Cell::Text => Ok(Value::String(value.to_string())),
+        Cell::Number => value
+            .parse::<f64>()
+            .ok()
+            .and_then(serde_json::Number::from_f64)
+            .map(Value::Number)
+            .ok_or_else(|| format!("{}: expected a number, got {:?}", column, value)),
+        Cell::Flag => match value.to_ascii_lowercase().as_str() {
+            "true" | "1" | "yes" => Ok(Value::Bool(true)),
+            "false" | "0" | "no" => Ok(Value::Bool(false)),
+            _ => Err(format!("{}: expected a boolean, got {:?}", column, value)),
+        },
+    }
+}
+
+/// Extra columns may hold JSON written straight into the cell; keep it structured.
+fn free_cell(value: &str) -> Value {
+    let trimmed = value.trim_start();
+    if trimmed.starts_with('{') || trimmed.starts_with('[') {
+        if let Ok(parsed) = serde_json::from_str(value) {
+            return parsed;
+        }
+    }
+    Value::String(value.to_string())
+}
+
+fn insert_path(root: &mut Map<String, Value>, path: &[&str], value: Value) {
+    let (last, parents) = path.split_last().expect("column paths are never empty");
+    let mut node = root;
+    for key in parents {
+        let entry = node
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+        if !entry.is_object() {
+            *entry = Value::Object(Map::new());
+        }
+        node = entry.as_object_mut().expect("just ensured an object");
+    }
+    node.insert(last.to_string(), value);
+}
+
+fn record_to_raw(headers: &StringRecord, record: &StringRecord) -> Result<Value, String> {
+    let mut root = Map::new();
+    for (column, value) in headers.iter().zip(record.iter()) {
+        if value.is_empty() {
+            continue;
+        }
+        match column_target(column) {
+            Some((path, cell)) => insert_path(&mut root, path, convert(column, value, cell)?),
+            None => insert_path(&mut root, &["custom", column], free_cell(value)),
+        }
+    }
+    Ok(Value::Object(root))
+}
+
+/// `offset` is where the reader starts in the file.
+pub fn parse_csv<R: Read>(
+    reader: R,
+    source: &str,
+    offset: u64,
+) -> Result<(Vec<EventRow>, Vec<RejectedLine>), String> {
+    let mut csv_reader = ReaderBuilder::new().trim(Trim::Headers).from_reader(reader);
+    let headers = csv_reader.headers().map_err(|e| e.to_string())?.clone();
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    let mut record = StringRecord::new();
+
+    loop {
+        match csv_reader.read_record(&mut record) {
+            Ok(false) => break,
+            Ok(true) => {
+                let (line, byte) = record
+                    .position()
+                    .map(|p| (p.line() as usize, offset + p.byte()))
+                    .unwrap_or((0, 0));
+                let parsed = record_to_raw(&headers, &record).and_then(|raw| {
+                    let event = AuditEvent::from_value(&raw).map_err(|e| e.to_string())?;
+                    Ok(EventRow::new(&event, raw, source))
+                });
+                match parsed {
+                    Ok(row) => rows.push(row),
+                    Err(e) => {
+                        let snippet = record.iter().collect::<Vec<_>>().join(",");
+                        rejects.push(RejectedLine::new(line, byte, e, &snippet));
+                    }
+                }
+            }
+            Err(e) => {
+                if let ErrorKind::Io(_) = e.kind() {
+                    return Err(e.to_string());
+                }
+                let (line, byte) = e
+                    .position()
+                    .map(|p| (p.line() as usize, offset + p.byte()))
+                    .unwrap_or((0, 0));
+                rejects.push(RejectedLine::new(line, byte, e.to_string(), ""));
+            }
+        }
+    }
+
+    Ok((rows, rejects))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const HEADER: &str = "event_id,timestamp,category,action_type,operation,username,\
+                          ip_address,duration_ms,status,error_occurred,error_type,error_message\n";
+
+    #[test]
+    fn flattened_columns_become_the_nested_event() {
+        let csv = format!(
+            "{}{}{}",
+            HEADER,
+            "e1,2026-01-01T10:00:00+00:00,AUTH,EXECUTE,login,alice,10.0.0.1,12.5,SUCCESS,False,,\n",
+            "e2,2026-01-01T10:00:01+00:00,DATABASE,READ,query,,,,FAILURE,True,DatabaseError,\
+             \"timeout, retrying \"\"later\"\"\"\n",
+        );
+        let (rows, rejects) = parse_csv(csv.as_bytes(), "audit.csv", 0).unwrap();
+        assert!(rejects.is_empty(), "{:?}", rejects);
+        assert_eq!(
+            rows[0].raw,
+            json!({
+                "event_id": "e1",
+                "timestamp": "2026-01-01T10:00:00+00:00",
+                "action": {
+                    "category": "AUTH",
+                    "type": "EXECUTE",
+                    "operation": "login",
+                    "result": {"status": "SUCCESS"},
+                },
+                "actor": {"username": "alice", "ip_address": "10.0.0.1"},
+                "performance": {"duration_ms": 12.5},
+                "error": {"occurred": false},
+            })
+        );
+        assert_eq!(
+            (
+                rows[0].user.as_str(),
+                rows[0].action.as_str(),
+                rows[0].source.as_str()
+            ),
+            ("alice", "login", "audit.csv")
+        );
+
+        // Empty cells leave their field out instead of writing empty strings.
+        assert_eq!(
+            rows[1].raw,
+            json!({
+                "event_id": "e2",
+                "timestamp": "2026-01-01T10:00:01+00:00",
+                "action": {
+                    "category": "DATABASE",
+                    "type": "READ",
+                    "operation": "query",
+                    "result": {"status": "FAILURE"},
+                },
+                "error": {
+                    "occurred": true,
+                    "type": "DatabaseError",
+                    "message": "timeout, retrying \"later\"",
+                },
+            })
+        );
+        assert_eq!(rows[1].user, "");
+    }
+
+    #[test]
+    fn other_columns_go_under_custom_and_bad_cells_are_rejected() {
+        let csv = "event_id,timestamp,duration_ms,tenant,tags\n\
+                   e1,2026-01-01T10:00:00Z,,acme,\"[\"\"a\"\", \"\"b\"\"]\"\n\
+                   e2,2026-01-01T10:00:01Z,slow,acme,\n";
+        let (rows, rejects) = parse_csv(csv.as_bytes(), "audit.csv", 100).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rows[0].raw["custom"],
+            json!({"tenant": "acme", "tags": ["a", "b"]})
+        );
+        assert_eq!(rejects.len(), 1);
+        assert_eq!(rejects[0].line, 3);
+        assert!(
+            rejects[0].error.contains("duration_ms"),
+            "{}",
+            rejects[0].error
+        );
+    }
+}
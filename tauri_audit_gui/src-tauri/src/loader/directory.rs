@@ -0,0 +1,197 @@
+//! Discovery of the per-day, per-category files written by `FileStorage._get_file_path`.
+
+use super::{load_path, LoadResult};
+use crate::jobs::{run_blocking, Job};
+use crate::store::{EventStore, StoreLoadSummary};
+use chrono::NaiveDate;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+pub const DEFAULT_FILENAME_PATTERN: &str = "audit_{date}.log";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DiscoveredFile {
+    pub path: String,
+    pub app_name: Option<String>,
+    pub date: Option<String>,
+    pub category: Option<String>,
+    pub size: u64,
+    /// Carries a rotation counter or compression suffix, so nothing appends to it any more.
+    pub archived: bool,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+pub struct DirectorySelection {
+    pub filename_pattern: Option<String>,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+    pub categories: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FileFailure {
+    pub path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DirectoryLoadResult {
+    #[serde(flatten)]
+    pub loaded: StoreLoadSummary,
+    pub failures: Vec<FileFailure>,
+}
+
+/// Turns a `filename_pattern` such as `{app_name}_{category}_{date}.jsonl` into a regex.
+/// Rotation counters (`.1`) and compression suffixes (`.gz`) are accepted after the name.
+fn pattern_regex(pattern: &str) -> Result<Regex, String> {
+    let mut expr = String::from("^");
+    let mut rest = pattern;
+    while let Some(start) = rest.find('{') {
+        expr.push_str(&regex::escape(&rest[..start]));
+        let end = rest[start..]
+            .find('}')
+            .map(|i| start + i)
+            .ok_or_else(|| format!("Unclosed placeholder in pattern: {}", pattern))?;
+        match &rest[start + 1..end] {
+            "app_name" => expr.push_str(r"(?P<app_name>.+?)"),
+            "date" => expr.push_str(r"(?P<date>\d{4}-\d{2}-\d{2})"),
+            "category" => expr.push_str(r"(?P<category>[A-Za-z_]+)"),
+            other => return Err(format!("Unknown placeholder {{{}}} in pattern", other)),
+        }
+        rest = &rest[end + 1..];
+    }
+    expr.push_str(&regex::escape(rest));
+    expr.push_str(r"(?P<archive>(?:\.\d+)?(?:\.(?:gz|zst|zstd|bz2))?)$");
+    Regex::new(&expr).map_err(|e| e.to_string())
+}
+
+fn parse_date(value: &Option<String>) -> Result<Option<NaiveDate>, String> {
+    value
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .map(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|e| format!("{}: {}", v, e)))
+        .transpose()
+}
+
+pub fn scan(directory: &Path, pattern: &str) -> Result<Vec<DiscoveredFile>, String> {
+    let re = pattern_regex(pattern)?;
+    let mut found = Vec::new();
+
+    for entry in fs::read_dir(directory).map_err(|e| e.to_string())? {
+        let entry = entry.map_err(|e| e.to_string())?;
+        let metadata = entry.metadata().map_err(|e| e.to_string())?;
+        if !metadata.is_file() {
+            continue;
+        }
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let Some(caps) = re.captures(&name) else {
+            continue;
+        };
+        let group = |key: &str| caps.name(key).map(|m| m.as_str().to_string());
+        found.push(DiscoveredFile {
+            path: entry.path().to_string_lossy().into_owned(),
+            app_name: group("app_name"),
+            date: group("date"),
+            category: group("category"),
+            size: metadata.len(),
+            archived: caps.name("archive").is_some_and(|m| !m.is_empty()),
+        });
+    }
+
+    found.sort_by(|a, b| a.date.cmp(&b.date).then_with(|| a.path.cmp(&b.path)));
+    Ok(found)
+}
+
+pub fn select(
+    files: Vec<DiscoveredFile>,
+    selection: &DirectorySelection,
+) -> Result<Vec<DiscoveredFile>, String> {
+    let from = parse_date(&selection.date_from)?;
+    let to = parse_date(&selection.date_to)?;
+
+    Ok(files
+        .into_iter()
+        .filter(|file| {
+            let date = file
+                .date
+                .as_deref()
+                .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+            let after_from = match (from, date) {
+                (Some(from), Some(date)) => date >= from,
+                _ => true,
+            };
+            let before_to = match (to, date) {
+                (Some(to), Some(date)) => date <= to,
+                _ => true,
+            };
+            let in_categories = selection.categories.is_empty()
+                || file.category.as_deref().is_some_and(|category| {
+                    selection
+                        .categories
+                        .iter()
+                        .any(|c| c.eq_ignore_ascii_case(category))
+                });
+            after_from && before_to && in_categories
+        })
+        .collect())
+}
+
+#[tauri::command]
+pub fn scan_audit_directory(
+    directory: String,
+    filename_pattern: Option<String>,
+) -> Result<Vec<DiscoveredFile>, String> {
+    let pattern = filename_pattern
+        .as_deref()
+        .unwrap_or(DEFAULT_FILENAME_PATTERN);
+    scan(Path::new(&directory), pattern)
+}
+
+fn load_directory(
+    directory: &Path,
+    selection: &DirectorySelection,
+    job: &Job,
+) -> Result<(Vec<LoadResult>, Vec<FileFailure>), String> {
+    let pattern = selection
+        .filename_pattern
+        .as_deref()
+        .unwrap_or(DEFAULT_FILENAME_PATTERN);
+    let files = select(scan(directory, pattern)?, selection)?;
+
+    let mut loaded = Vec::new();
+    let mut failures = Vec::new();
+    for file in files {
+        match load_path(Path::new(&file.path), job) {
+            Ok(result) => loaded.push(result),
+            Err(error) if job.is_cancelled() => return Err(error),
+            Err(error) => failures.push(FileFailure {
+                path: file.path,
+                error,
+            }),
+        }
+    }
+    Ok((loaded, failures))
+}
+
+/// Loads the selected files into the event store in place of what it held.
+#[tauri::command]
+pub async fn load_audit_directory(
+    app: AppHandle,
+    directory: String,
+    selection: DirectorySelection,
+    job_id: Option<String>,
+) -> Result<DirectoryLoadResult, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        let (loaded, failures) = load_directory(Path::new(&directory), &selection, job)?;
+        Ok(DirectoryLoadResult {
+            loaded: handle.state::<EventStore>().load(loaded, false),
+            failures,
+        })
+    })
+    .await
+}
@@ -0,0 +1,102 @@
+use super::RejectedLine;
+use crate::event::{AuditEvent, EventRow};
+use serde_json::Value;
+use std::io::BufRead;
+
+fn to_row(raw: Value, source: &str) -> Result<EventRow, String> {
+    let event = AuditEvent::from_value(&raw).map_err(|e| e.to_string())?;
+    Ok(EventRow::new(&event, raw, source))
+}
+
+/// A JSON array (or a single object). Elements that are not events become rejects, with
+/// `line` holding the element's 1-based position in the array.
+pub fn parse_json_array<R: BufRead>(
+    reader: R,
+    source: &str,
+) -> Result<(Vec<EventRow>, Vec<RejectedLine>), String> {
+    let data: Value = serde_json::from_reader(reader).map_err(|e| e.to_string())?;
+    let items = match data {
+        Value::Array(items) => items,
+        other => vec![other],
+    };
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    for (i, raw) in items.into_iter().enumerate() {
+        let snippet = raw.to_string();
+        match to_row(raw, source) {
+            Ok(row) => rows.push(row),
+            Err(e) => rejects.push(RejectedLine::new(i + 1, 0, e, &snippet)),
+        }
+    }
+    Ok((rows, rejects))
+}
+
+pub fn parse_line(line: &str, source: &str) -> Result<EventRow, String> {
+    let raw: Value = serde_json::from_str(line).map_err(|e| e.to_string())?;
+    to_row(raw, source)
+}
+
+/// Decodes one raw JSONL line. Blank lines give `None`; invalid UTF-8 and bad JSON
+/// come back as rejects carrying the line's position in the file. A BOM is dropped, since
+/// the tail and the line index read the first line straight from the file.
+pub fn parse_line_bytes(
+    buf: &[u8],
+    line_no: usize,
+    byte_offset: u64,
+    source: &str,
+) -> Option<Result<EventRow, RejectedLine>> {
+    let text = match std::str::from_utf8(buf) {
+        Ok(text) => text,
+        Err(e) => {
+            let lossy = String::from_utf8_lossy(buf);
+            return Some(Err(RejectedLine::new(
+                line_no,
+                byte_offset,
+                e.to_string(),
+                &lossy,
+            )));
+        }
+    };
+    let trimmed = text.trim_start_matches('\u{feff}').trim();
+    if trimmed.is_empty() {
+        return None;
+    }
+    Some(
+        parse_line(trimmed, source)
+            .map_err(|e| RejectedLine::new(line_no, byte_offset, e, trimmed)),
+    )
+}
+
+/// Parses JSONL line by line, collecting bad lines as rejects instead of failing the file.
+/// `offset` is where the reader starts in the file.
+pub fn parse_jsonl<R: BufRead>(
+    mut reader: R,
+    source: &str,
+    mut offset: u64,
+) -> Result<(Vec<EventRow>, Vec<RejectedLine>), String> {
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    let mut buf = Vec::new();
+    let mut line_no = 0usize;
+
+    loop {
+        buf.clear();
+        let read = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        line_no += 1;
+        let start = offset;
+        offset += read as u64;
+
+        match parse_line_bytes(&buf, line_no, start, source) {
+            Some(Ok(row)) => rows.push(row),
+            Some(Err(reject)) => rejects.push(reject),
+            None => {}
+        }
+    }
+
+    Ok((rows, rejects))
+}
@@ -0,0 +1,211 @@
+//! Native audit-log loading: format detection and the `load_audit_file` command.
+
+mod compression;
+mod csv;
+pub mod directory;
+mod json;
+pub mod sqlite;
+mod text;
+
+use crate::event::{source_name, EventRow};
+use crate::jobs::{run_blocking, Job, ProgressKind};
+use serde::Serialize;
+use std::io::BufRead;
+use std::path::Path;
+use tauri::AppHandle;
+
+pub use compression::{detect as compression_of, Compression};
+pub use json::parse_line_bytes;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogFormat {
+    Json,
+    Jsonl,
+    Csv,
+    Text,
+}
+
+const SNIPPET_CHARS: usize = 200;
+
+const BOM: &[u8] = b"\xef\xbb\xbf";
+
+/// A line that could not be turned into an event, kept so the UI can show what was dropped.
+#[derive(Debug, Clone, Serialize)]
+pub struct RejectedLine {
+    pub line: usize,
+    pub byte_offset: u64,
+    pub error: String,
+    pub snippet: String,
+}
+
+impl RejectedLine {
+    pub fn new(line: usize, byte_offset: u64, error: String, raw: &str) -> Self {
+        RejectedLine {
+            line,
+            byte_offset,
+            error,
+            snippet: raw.trim_end().chars().take(SNIPPET_CHARS).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoadResult {
+    pub path: String,
+    pub format: LogFormat,
+    pub compression: Compression,
+    pub rows: Vec<EventRow>,
+    pub rejects: Vec<RejectedLine>,
+}
+
+/// `FileStorage` names every format `*.log` by default, so sniff the content as well.
+fn detect_format<R: BufRead>(path: &Path, reader: &mut R) -> Result<LogFormat, String> {
+    let is_csv_ext = path
+        .extension()
+        .map(|ext| ext.eq_ignore_ascii_case("csv"))
+        .unwrap_or(false);
+    let head = reader.fill_buf().map_err(|e| e.to_string())?;
+    if head.starts_with(b"SQLite format 3\0") {
+        return Err(
+            "This is a SQLite database; open it as a Vigil SQL storage database".to_string(),
+        );
+    }
+    if is_csv_ext || head.starts_with(b"event_id,") {
+        return Ok(LogFormat::Csv);
+    }
+    match head.iter().find(|b| !b.is_ascii_whitespace()) {
+        Some(b'[') => Ok(LogFormat::Json),
+        Some(b'{') | None => Ok(LogFormat::Jsonl),
+        Some(b'=') => Ok(LogFormat::Text),
+        Some(_) => Err("Unsupported file content: expected JSON, JSONL, CSV or text".to_string()),
+    }
+}
+
+/// Consumes a leading UTF-8 BOM (Excel and Windows editors write one) so neither the
+/// sniffing nor the parsers see it. Returns how many bytes were skipped.
+fn skip_bom<R: BufRead>(reader: &mut R) -> Result<u64, String> {
+    let head = reader.fill_buf().map_err(|e| e.to_string())?;
+    if !head.starts_with(BOM) {
+        return Ok(0);
+    }
+    reader.consume(BOM.len());
+    Ok(BOM.len() as u64)
+}
+
+/// Sniffs how `path` is stored without parsing any events.
+pub fn format_of(path: &Path, job: &Job) -> Result<(LogFormat, Compression), String> {
+    let (mut reader, compression) = compression::open(path, job)?;
+    skip_bom(&mut reader)?;
+    let format = detect_format(&compression::logical_path(path), &mut reader)?;
+    Ok((format, compression))
+}
+
+pub fn load_path(path: &Path, job: &Job) -> Result<LoadResult, String> {
+    let (mut reader, compression) = compression::open(path, job)?;
+    let source = source_name(path);
+
+    let start = skip_bom(&mut reader)?;
+    let format = detect_format(&compression::logical_path(path), &mut reader)?;
+    let (rows, rejects) = match format {
+        LogFormat::Json => json::parse_json_array(reader, &source)?,
+        LogFormat::Jsonl => json::parse_jsonl(reader, &source, start)?,
+        LogFormat::Csv => csv::parse_csv(reader, &source, start)?,
+        LogFormat::Text => text::parse_text(reader, &source, start)?,
+    };
+    job.check()?;
+    job.report(ProgressKind::Events { parsed: rows.len() });
+
+    Ok(LoadResult {
+        path: path.to_string_lossy().into_owned(),
+        format,
+        compression,
+        rows,
+        rejects,
+    })
+}
+
+#[tauri::command]
+pub async fn load_audit_file(
+    app: AppHandle,
+    path: String,
+    job_id: Option<String>,
+) -> Result<LoadResult, String> {
+    run_blocking(app, job_id, move |job| load_path(Path::new(&path), job)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    const EVENT: &str = r#"{"event_id":"e1","timestamp":"2026-02-09T14:12:00Z","action":{"category":"AUTH","type":"LOGIN"}}"#;
+
+    fn with_bom(body: &str) -> Cursor<Vec<u8>> {
+        Cursor::new([BOM, body.as_bytes()].concat())
+    }
+
+    #[test]
+    fn bom_is_consumed_before_sniffing() {
+        let mut reader = with_bom(&format!("{}\n", EVENT));
+        assert_eq!(skip_bom(&mut reader).unwrap(), 3);
+        let format = detect_format(Path::new("audit.log"), &mut reader).unwrap();
+        assert_eq!(format, LogFormat::Jsonl);
+        assert_eq!(skip_bom(&mut reader).unwrap(), 0);
+    }
+
+    #[test]
+    fn jsonl_after_bom_parses_with_file_offsets() {
+        let mut reader = with_bom(&format!("{}\nnot json\n", EVENT));
+        let start = skip_bom(&mut reader).unwrap();
+        let (rows, rejects) = json::parse_jsonl(reader, "audit.log", start).unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(rows[0].event_id, "e1");
+        assert_eq!(rejects.len(), 1);
+        assert_eq!(rejects[0].line, 2);
+        assert_eq!(rejects[0].byte_offset, 3 + EVENT.len() as u64 + 1);
+    }
+
+    #[test]
+    fn json_array_and_csv_after_bom() {
+        let mut reader = with_bom(&format!("[{}]", EVENT));
+        skip_bom(&mut reader).unwrap();
+        assert_eq!(
+            detect_format(Path::new("audit.json"), &mut reader).unwrap(),
+            LogFormat::Json
+        );
+        let (rows, _) = json::parse_json_array(reader, "audit.json").unwrap();
+        assert_eq!(rows.len(), 1);
+
+        let mut reader = with_bom(
+            "event_id,timestamp,category,action_type\ne1,2026-02-09T14:12:00Z,AUTH,LOGIN\n",
+        );
+        let start = skip_bom(&mut reader).unwrap();
+        assert_eq!(
+            detect_format(Path::new("audit.log"), &mut reader).unwrap(),
+            LogFormat::Csv
+        );
+        let (rows, rejects) = csv::parse_csv(reader, "audit.log", start).unwrap();
+        assert!(rejects.is_empty(), "{:?}", rejects);
+        assert_eq!(rows[0].event_id, "e1");
+    }
+
+    #[test]
+    fn bad_array_elements_are_rejected_alone() {
+        let body = format!(r#"[{}, {{"event_id": 7}}, "text"]"#, EVENT);
+        let (rows, rejects) = json::parse_json_array(Cursor::new(body), "audit.json").unwrap();
+        assert_eq!(rows.len(), 1);
+        assert_eq!(
+            rejects.iter().map(|r| r.line).collect::<Vec<_>>(),
+            vec![2, 3]
+        );
+        assert!(json::parse_json_array(Cursor::new("[{"), "audit.json").is_err());
+    }
+
+    #[test]
+    fn line_decoder_drops_a_bom() {
+        let line = [BOM, EVENT.as_bytes()].concat();
+        let row = parse_line_bytes(&line, 1, 0, "audit.log").unwrap().unwrap();
+        assert_eq!(row.event_id, "e1");
+    }
+}
@@ -0,0 +1,446 @@
+//! Read-only access to the SQLite databases `SQLStorageBackend` writes: the explorer's
+//! filters run against the indexed `audit_events` columns and `event_data` is parsed into
+//! the normal event model.
+
+use super::{parse_line_bytes, RejectedLine};
+use crate::collector::browse::server_filters;
+use crate::collector::EventFilters;
+use crate::event::{source_name, EventRow};
+use crate::jobs::{run_blocking, ProgressKind};
+use crate::store::{EventFilter, ResultView};
+use chrono::{Duration, NaiveDate};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, OpenFlags};
+use serde::Serialize;
+use std::path::Path;
+use tauri::{AppHandle, Manager};
+
+const MAX_PAGE_SIZE: usize = 10_000;
+
+#[derive(Debug, Serialize)]
+pub struct DatabasePage {
+    pub path: String,
+    pub total: u64,
+    pub page: usize,
+    pub page_size: usize,
+    /// Events of this page, now held by the result view.
+    pub events: usize,
+    pub rejects: Vec<RejectedLine>,
+    /// Parts of the filter with no column to run on, so they were not applied.
+    pub ignored: Vec<String>,
+}
+
+fn open(path: &Path) -> Result<Connection, String> {
+    let conn = Connection::open_with_flags(
+        path,
+        OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+    )
+    .map_err(|e| format!("{}: {}", path.display(), e))?;
+    let tables: u32 = conn
+        .query_row(
+            "SELECT COUNT(*) FROM sqlite_master WHERE type = 'table' AND name = 'audit_events'",
+            [],
+            |row| row.get(0),
+        )
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    if tables == 0 {
+        return Err(format!(
+            "{} has no audit_events table; is it a Vigil SQL storage database?",
+            path.display()
+        ));
+    }
+    Ok(conn)
+}
+
+fn day(value: &Option<String>) -> Result<Option<NaiveDate>, String> {
+    value
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .map(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|e| format!("{}: {}", v, e)))
+        .transpose()
+}
+
+/// `WHERE` clause and parameters for the column filters and the date range. SQLAlchemy
+/// stores `timestamp` as `YYYY-MM-DD HH:MM:SS.ffffff`, so whole days compare as text.
+fn where_clause(
+    filters: &EventFilters,
+    filter: &EventFilter,
+) -> Result<(String, Vec<Value>), String> {
+    let mut clauses = Vec::new();
+    let mut values = Vec::new();
+    for (column, value) in filters.query() {
+        clauses.push(format!("{} = ?", column));
+        values.push(Value::Text(value.to_string()));
+    }
+    if let Some(from) = day(&filter.date_from)? {
+        clauses.push("timestamp >= ?".to_string());
+        values.push(Value::Text(from.format("%Y-%m-%d").to_string()));
+    }
+    if let Some(to) = day(&filter.date_to)? {
+        clauses.push("timestamp < ?".to_string());
+        values.push(Value::Text(
+            (to + Duration::days(1)).format("%Y-%m-%d").to_string(),
+        ));
+    }
+    let clause = match clauses.is_empty() {
+        true => String::new(),
+        false => format!(" WHERE {}", clauses.join(" AND ")),
+    };
+    Ok((clause, values))
+}
+
+/// `progress` sees the number of events parsed so far every 10,000 rows and can stop
+/// the read by returning an error.
+fn query(
+    path: &Path,
+    filter: &EventFilter,
+    extra: EventFilters,
+    page: usize,
+    page_size: usize,
+    mut progress: impl FnMut(usize) -> Result<(), String>,
+) -> Result<(DatabasePage, Vec<EventRow>), String> {
+    let conn = open(path)?;
+    let (filters, ignored) = server_filters(filter, extra)?;
+    let (clause, mut values) = where_clause(&filters, filter)?;
+    let page = page.max(1);
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let start = (page - 1).saturating_mul(page_size);
+
+    let total: u64 = conn
+        .query_row(
+            &format!("SELECT COUNT(*) FROM audit_events{}", clause),
+            params_from_iter(&values),
+            |row| row.get(0),
+        )
+        .map_err(|e| e.to_string())?;
+    progress(0)?;
+
+    // Newest first, like `SQLStorageBackend.query`.
+    values.push(Value::Integer(page_size as i64));
+    values.push(Value::Integer(start as i64));
+    let mut statement = conn
+        .prepare(&format!(
+            "SELECT event_data FROM audit_events{} ORDER BY timestamp DESC LIMIT ? OFFSET ?",
+            clause
+        ))
+        .map_err(|e| e.to_string())?;
+    let mut result = statement
+        .query(params_from_iter(&values))
+        .map_err(|e| e.to_string())?;
+
+    let source = source_name(path);
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    let mut read = 0;
+    while let Some(row) = result.next().map_err(|e| e.to_string())? {
+        if read % 10_000 == 0 {
+            progress(rows.len())?;
+        }
+        read += 1;
+        let line = start + read;
+        let data = row.get_ref(0).map_err(|e| e.to_string())?;
+        let bytes = data.as_bytes().unwrap_or_default();
+        match parse_line_bytes(bytes, line, 0, &source) {
+            Some(Ok(event)) => rows.push(event),
+            Some(Err(reject)) => rejects.push(reject),
+            None => rejects.push(RejectedLine::new(
+                line,
+                0,
+                "Empty event_data".to_string(),
+                "",
+            )),
+        }
+    }
+    progress(rows.len())?;
+
+    let page = DatabasePage {
+        path: path.to_string_lossy().into_owned(),
+        total,
+        page,
+        page_size,
+        events: rows.len(),
+        rejects,
+        ignored,
+    };
+    Ok((page, rows))
+}
+
+/// One page (1-based) of a `SQLStorageBackend` database, newest first, loaded into the
+/// result view. `extra` carries the application/environment/actor filters the panel has
+/// no control for. A rejected row's `line` is its position in the filtered result.
+#[tauri::command]
+pub async fn sqlite_query(
+    app: AppHandle,
+    path: String,
+    filter: EventFilter,
+    extra: Option<EventFilters>,
+    page: usize,
+    page_size: usize,
+    job_id: Option<String>,
+) -> Result<DatabasePage, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        let (page, rows) = query(
+            Path::new(&path),
+            &filter,
+            extra.unwrap_or_default(),
+            page,
+            page_size,
+            |parsed| {
+                job.check()?;
+                job.report(ProgressKind::Events { parsed });
+                Ok(())
+            },
+        )?;
+        handle.state::<ResultView>().0.replace(rows);
+        Ok(page)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use std::path::PathBuf;
+
+    /// The DDL SQLAlchemy emits on SQLite for `build_audit_events_table`.
+    const SCHEMA: &str = "
+        CREATE TABLE audit_events (
+            event_id VARCHAR(36) NOT NULL,
+            timestamp DATETIME NOT NULL,
+            version VARCHAR(20) DEFAULT '1.0.0' NOT NULL,
+            actor_type VARCHAR(50),
+            actor_username VARCHAR(255),
+            action_type VARCHAR(50),
+            action_category VARCHAR(50),
+            action_operation VARCHAR(255),
+            result_status VARCHAR(20),
+            application VARCHAR(255),
+            environment VARCHAR(100),
+            event_data TEXT NOT NULL,
+            created_at DATETIME DEFAULT (CURRENT_TIMESTAMP) NOT NULL,
+            PRIMARY KEY (event_id)
+        );
+        CREATE INDEX ix_audit_events_timestamp ON audit_events (timestamp);
+        CREATE INDEX ix_audit_events_actor_username ON audit_events (actor_username);
+        CREATE INDEX ix_audit_events_action_category ON audit_events (action_category);
+        CREATE INDEX ix_audit_events_action_type ON audit_events (action_type);
+        CREATE INDEX ix_audit_events_result_status ON audit_events (result_status);
+        CREATE INDEX ix_audit_events_application ON audit_events (application);
+        CREATE INDEX ix_audit_events_environment ON audit_events (environment);
+    ";
+
+    /// A database laid out the way `SQLStorageBackend.store` writes it: the columns come
+    /// from the event, `timestamp` loses its offset and `event_data` is the event as JSON.
+    /// `bad` rows carry unparseable `event_data`.
+    fn database(name: &str, events: &[Value], bad: &[(&str, &str, &str)]) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.db", name, std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let conn = Connection::open(&path).unwrap();
+        conn.execute_batch(SCHEMA).unwrap();
+        let text = |value: &Value| value.as_str().map(str::to_string);
+        for event in events {
+            let timestamp =
+                chrono::DateTime::parse_from_rfc3339(event["timestamp"].as_str().unwrap())
+                    .unwrap()
+                    .naive_utc()
+                    .format("%Y-%m-%d %H:%M:%S%.6f")
+                    .to_string();
+            conn.execute(
+                "INSERT INTO audit_events (event_id, timestamp, actor_type, actor_username,
+                    action_type, action_category, action_operation, result_status,
+                    application, environment, event_data)
+                 VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)",
+                rusqlite::params![
+                    text(&event["event_id"]),
+                    timestamp,
+                    text(&event["actor"]["type"]),
+                    text(&event["actor"]["username"]),
+                    text(&event["action"]["type"]),
+                    text(&event["action"]["category"]),
+                    text(&event["action"]["operation"]),
+                    text(&event["action"]["result"]["status"]),
+                    text(&event["metadata"]["application"]),
+                    text(&event["metadata"]["environment"]),
+                    event.to_string(),
+                ],
+            )
+            .unwrap();
+        }
+        for (id, timestamp, data) in bad {
+            conn.execute(
+                "INSERT INTO audit_events (event_id, timestamp, event_data) VALUES (?, ?, ?)",
+                rusqlite::params![id, timestamp, data],
+            )
+            .unwrap();
+        }
+        path
+    }
+
+    fn event(id: &str, timestamp: &str, category: &str, user: &str, environment: &str) -> Value {
+        json!({
+            "event_id": id,
+            "timestamp": timestamp,
+            "version": "1.0.0",
+            "actor": {"type": "user", "username": user},
+            "action": {
+                "type": "READ",
+                "category": category,
+                "operation": "fetch",
+                "result": {"status": "SUCCESS"},
+            },
+            "metadata": {"application": "billing", "environment": environment},
+        })
+    }
+
+    fn run(
+        path: &Path,
+        filter: Value,
+        extra: Value,
+        page: usize,
+        page_size: usize,
+    ) -> (DatabasePage, Vec<String>) {
+        let filter: EventFilter = serde_json::from_value(filter).unwrap();
+        let extra: EventFilters = serde_json::from_value(extra).unwrap();
+        let (page, rows) = query(path, &filter, extra, page, page_size, |_| Ok(())).unwrap();
+        (page, rows.into_iter().map(|row| row.event_id).collect())
+    }
+
+    #[test]
+    fn filters_run_on_the_columns() {
+        let path = database(
+            "sqlite-columns",
+            &[
+                event("a", "2026-01-01T10:00:00+00:00", "AUTH", "alice", "prod"),
+                event(
+                    "b",
+                    "2026-01-02T10:00:00+00:00",
+                    "DATABASE",
+                    "alice",
+                    "prod",
+                ),
+                event("c", "2026-01-03T10:00:00+00:00", "AUTH", "bob", "prod"),
+                event("d", "2026-01-04T10:00:00+00:00", "AUTH", "alice", "staging"),
+            ],
+            &[],
+        );
+        let (page, ids) = run(&path, json!({"category": "AUTH"}), json!({}), 1, 10);
+        assert_eq!(
+            (page.total, ids),
+            (3, vec!["d".into(), "c".into(), "a".into()])
+        );
+
+        let (page, ids) = run(
+            &path,
+            json!({"query": "actor.username:alice"}),
+            json!({"environment": "prod"}),
+            1,
+            10,
+        );
+        assert_eq!((page.total, ids), (2, vec!["b".into(), "a".into()]));
+        assert!(page.ignored.is_empty());
+
+        // Newest first, one page at a time.
+        let (page, ids) = run(&path, json!({}), json!({}), 2, 3);
+        assert_eq!((page.total, page.page, ids), (4, 2, vec!["a".into()]));
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn date_bounds_cover_whole_days() {
+        let path = database(
+            "sqlite-dates",
+            &[
+                event(
+                    "before",
+                    "2026-01-01T23:59:59.999999+00:00",
+                    "AUTH",
+                    "a",
+                    "prod",
+                ),
+                event("first", "2026-01-02T00:00:00+00:00", "AUTH", "a", "prod"),
+                event(
+                    "last",
+                    "2026-01-03T23:59:59.500000+00:00",
+                    "AUTH",
+                    "a",
+                    "prod",
+                ),
+                event("after", "2026-01-04T00:00:00+00:00", "AUTH", "a", "prod"),
+            ],
+            &[],
+        );
+        let (_, ids) = run(
+            &path,
+            json!({"date_from": "2026-01-02", "date_to": "2026-01-03"}),
+            json!({}),
+            1,
+            10,
+        );
+        assert_eq!(ids, vec!["last", "first"]);
+        let (_, ids) = run(&path, json!({"date_to": "2026-01-01"}), json!({}), 1, 10);
+        assert_eq!(ids, vec!["before"]);
+
+        let filter: EventFilter =
+            serde_json::from_value(json!({"date_from": "01/02/2026"})).unwrap();
+        assert!(query(&path, &filter, EventFilters::default(), 1, 10, |_| Ok(())).is_err());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn terms_without_a_column_are_listed_as_ignored() {
+        let path = database(
+            "sqlite-ignored",
+            &[
+                event("a", "2026-01-01T10:00:00+00:00", "AUTH", "alice", "prod"),
+                event("b", "2026-01-02T10:00:00+00:00", "DATABASE", "bob", "prod"),
+            ],
+            &[],
+        );
+        let (page, ids) = run(
+            &path,
+            json!({"search": "fetch", "query": "category:database action.operation:fetch"}),
+            json!({}),
+            1,
+            10,
+        );
+        assert_eq!(ids, vec!["b"]);
+        assert_eq!(page.ignored, vec!["action.operation:fetch", "search"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn bad_event_data_becomes_a_reject() {
+        let path = database(
+            "sqlite-rejects",
+            &[
+                event("a", "2026-01-01T10:00:00+00:00", "AUTH", "alice", "prod"),
+                event("d", "2026-01-04T10:00:00+00:00", "AUTH", "alice", "prod"),
+            ],
+            &[
+                ("b", "2026-01-02 10:00:00.000000", "{not json"),
+                ("c", "2026-01-03 10:00:00.000000", ""),
+            ],
+        );
+        let (page, ids) = run(&path, json!({}), json!({}), 1, 10);
+        assert_eq!((page.total, page.events), (4, 2));
+        assert_eq!(ids, vec!["d", "a"]);
+        // Lines count positions in the result, newest first.
+        let lines: Vec<_> = page.rejects.iter().map(|r| r.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert_eq!(page.rejects[0].error, "Empty event_data");
+        assert!(page.rejects[1].snippet.starts_with("{not json"));
+
+        let missing = std::env::temp_dir().join(format!("sqlite-empty-{}.db", std::process::id()));
+        Connection::open(&missing)
+            .unwrap()
+            .execute_batch("CREATE TABLE t (x)")
+            .unwrap();
+        assert!(open(&missing)
+            .unwrap_err()
+            .contains("no audit_events table"));
+        std::fs::remove_file(&missing).unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+}
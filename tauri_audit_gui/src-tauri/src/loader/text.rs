@@ -0,0 +1,268 @@
+//! Parser for the block format written by `FileStorage._write_text`.
+
+use super::RejectedLine;
+use crate::event::{AuditEvent, EventRow};
+use std::io::BufRead;
+
+struct Block {
+    line: usize,
+    byte_offset: u64,
+    lines: Vec<String>,
+}
+
+impl Block {
+    fn reject(&self, error: String) -> RejectedLine {
+        RejectedLine::new(self.line, self.byte_offset, error, &self.lines.join("\n"))
+    }
+}
+
+fn is_separator(line: &str) -> bool {
+    line.len() >= 10 && line.bytes().all(|b| b == b'=')
+}
+
+/// Python writes `None` for missing optional values.
+fn optional(value: &str) -> Option<String> {
+    let value = value.trim();
+    if value.is_empty() || value == "None" {
+        None
+    } else {
+        Some(value.to_string())
+    }
+}
+
+fn parse_error_section(event: &mut AuditEvent, lines: &[String]) {
+    let mut lines = lines.iter();
+    let Some(first) = lines.next() else {
+        return;
+    };
+    let header = first.strip_prefix("ERROR:").unwrap_or(first).trim_start();
+    let (error_type, first_message) = header.split_once(": ").unwrap_or((header, ""));
+
+    let mut message = first_message.to_string();
+    let mut trace = None;
+    for line in lines.by_ref() {
+        if line == "Stack Trace:" {
+            trace = Some(lines.by_ref().cloned().collect::<Vec<_>>().join("\n"));
+            break;
+        }
+        message.push('\n');
+        message.push_str(line);
+    }
+
+    event.error.occurred = true;
+    event.error.error_type = optional(error_type);
+    event.error.message = optional(message.trim_end());
+    event.error.stack_trace = trace.and_then(|t| optional(t.trim_end()));
+}
+
+fn parse_block(lines: &[String]) -> Result<AuditEvent, String> {
+    let mut event = AuditEvent::default();
+
+    for (i, line) in lines.iter().enumerate() {
+        if line.starts_with("ERROR:") {
+            parse_error_section(&mut event, &lines[i..]);
+            break;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (key, value) = line
+            .split_once(':')
+            .ok_or_else(|| format!("unexpected line {:?}", line))?;
+        let value = value.strip_prefix(' ').unwrap_or(value);
+        match key {
+            "Event ID" => event.event_id = value.trim().to_string(),
+            "Timestamp" => event.timestamp = value.trim().to_string(),
+            "Category" => event.action.category = value.trim().to_string(),
+            "Action" => event.action.operation = optional(value),
+            "Type" => event.action.action_type = value.trim().to_string(),
+            "User" => event.actor.username = optional(value),
+            "Parameters" => {
+                event.action.parameters =
+                    serde_json::from_str(value).map_err(|e| format!("Parameters: {}", e))?
+            }
+            "Duration" => {
+                let ms = value.trim().trim_end_matches("ms");
+                let ms = ms.parse::<f64>().map_err(|e| format!("Duration: {}", e))?;
+                event.performance.duration_ms = Some(ms);
+            }
+            "Status" => event.action.result.status = value.trim().to_string(),
+            _ => return Err(format!("unknown field {:?}", key)),
+        }
+    }
+
+    if event.event_id.is_empty() {
+        return Err("block has no Event ID".to_string());
+    }
+    if event.timestamp.is_empty() {
+        return Err("block has no Timestamp".to_string());
+    }
+    Ok(event)
+}
+
+fn close(block: Block, source: &str, rows: &mut Vec<EventRow>, rejects: &mut Vec<RejectedLine>) {
+    match parse_block(&block.lines) {
+        Ok(event) => rows.push(EventRow::from_event(event, source)),
+        Err(e) => rejects.push(block.reject(e)),
+    }
+}
+
+/// `offset` is where the reader starts in the file.
+///
+/// A separator inside a block normally closes it, but when the next line is `Event ID:`
+/// it was the opening separator of the next block: the open block was cut short (a crash
+/// mid-write), so it is rejected and parsing carries on in step with the file.
+pub fn parse_text<R: BufRead>(
+    mut reader: R,
+    source: &str,
+    mut offset: u64,
+) -> Result<(Vec<EventRow>, Vec<RejectedLine>), String> {
+    let mut rows = Vec::new();
+    let mut rejects = Vec::new();
+    let mut current: Option<Block> = None;
+    // Position of a separator seen inside `current` that may close it or open the next block.
+    let mut separator: Option<(usize, u64)> = None;
+    let mut buf = Vec::new();
+    let mut line_no = 0usize;
+
+    loop {
+        buf.clear();
+        let read = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        line_no += 1;
+        let start = offset;
+        offset += read as u64;
+        let text = String::from_utf8_lossy(&buf);
+        let line = text.trim_end_matches(['\n', '\r']);
+
+        if let Some((sep_line, sep_offset)) = separator.take() {
+            if let Some(block) = current.take() {
+                if line.starts_with("Event ID:") {
+                    rejects.push(block.reject("truncated event block".to_string()));
+                    current = Some(Block {
+                        line: sep_line,
+                        byte_offset: sep_offset,
+                        lines: vec![line.to_string()],
+                    });
+                    continue;
+                }
+                close(block, source, &mut rows, &mut rejects);
+            }
+        }
+
+        if is_separator(line) {
+            match current {
+                Some(_) => separator = Some((line_no, start)),
+                None => {
+                    current = Some(Block {
+                        line: line_no,
+                        byte_offset: start,
+                        lines: Vec::new(),
+                    })
+                }
+            }
+            continue;
+        }
+
+        match current.as_mut() {
+            Some(block) => block.lines.push(line.to_string()),
+            None if line.trim().is_empty() => {}
+            None => rejects.push(RejectedLine::new(
+                line_no,
+                start,
+                "text outside of an event block".to_string(),
+                line,
+            )),
+        }
+    }
+
+    if let Some(block) = current {
+        match separator {
+            Some(_) => close(block, source, &mut rows, &mut rejects),
+            None => rejects.push(block.reject("unterminated event block".to_string())),
+        }
+    }
+
+    Ok((rows, rejects))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SEP: &str =
+        "================================================================================";
+
+    fn block(id: &str, status: &str) -> String {
+        format!(
+            "\n{SEP}\nEvent ID: {id}\nTimestamp: 2026-02-09T14:12:00+00:00\nCategory: AUTH\n\
+             Action: login\nType: LOGIN\nUser: alice\nDuration: 12.50ms\nStatus: {status}\n{SEP}\n"
+        )
+    }
+
+    fn parse(body: &str) -> (Vec<EventRow>, Vec<RejectedLine>) {
+        parse_text(body.as_bytes(), "audit.log", 0).unwrap()
+    }
+
+    #[test]
+    fn parses_consecutive_blocks() {
+        let (rows, rejects) = parse(&(block("e1", "SUCCESS") + &block("e2", "FAILURE")));
+        assert!(rejects.is_empty(), "{:?}", rejects);
+        let ids: Vec<_> = rows.iter().map(|r| r.event_id.as_str()).collect();
+        assert_eq!(ids, ["e1", "e2"]);
+        assert_eq!(rows[0].user, "alice");
+        assert_eq!(rows[1].status, "FAILURE");
+        assert_eq!(rows[0].raw["performance"]["duration_ms"], 12.5);
+    }
+
+    #[test]
+    fn error_section_with_stack_trace() {
+        let body = block("e1", "FAILURE").replace(
+            &format!("Status: FAILURE\n{SEP}"),
+            &format!(
+                "Status: FAILURE\n\nERROR: ValueError: bad input\nStack Trace:\n  line 1\n{SEP}"
+            ),
+        );
+        let (rows, rejects) = parse(&body);
+        assert!(rejects.is_empty(), "{:?}", rejects);
+        let error = &rows[0].raw["error"];
+        assert_eq!(error["type"], "ValueError");
+        assert_eq!(error["message"], "bad input");
+        assert_eq!(error["stack_trace"], "line 1");
+    }
+
+    #[test]
+    fn truncated_block_does_not_shift_the_rest() {
+        let first = block("e1", "SUCCESS");
+        let cut = &first[..first.find("Type:").unwrap()];
+        let body = format!(
+            "{}{}{}",
+            cut,
+            block("e2", "SUCCESS"),
+            block("e3", "SUCCESS")
+        );
+        let (rows, rejects) = parse(&body);
+        let ids: Vec<_> = rows.iter().map(|r| r.event_id.as_str()).collect();
+        assert_eq!(ids, ["e2", "e3"]);
+        assert_eq!(rejects.len(), 1);
+        assert_eq!(rejects[0].error, "truncated event block");
+        assert_eq!(rejects[0].line, 2);
+        assert_eq!(rejects[0].byte_offset, 1);
+    }
+
+    #[test]
+    fn unterminated_and_stray_text_are_rejected() {
+        let body = format!("stray\n{}\n{SEP}\nEvent ID: e2\n", block("e1", "SUCCESS"));
+        let (rows, rejects) = parse(&body);
+        assert_eq!(rows.len(), 1);
+        let errors: Vec<_> = rejects.iter().map(|r| r.error.as_str()).collect();
+        assert_eq!(
+            errors,
+            ["text outside of an event block", "unterminated event block"]
+        );
+    }
+}
@@ -1,11 +1,22 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod collector;
+mod event;
+mod jobs;
+mod line_index;
+mod loader;
+mod search;
+mod store;
+mod tail;
+mod validation;
+
 use chrono::Utc;
+use jobs::{run_blocking, Job, ProgressKind};
 use printpdf::*;
 use serde::{Deserialize, Serialize};
 use std::fs::File;
 use std::io::BufWriter;
-use std::io::{Read, Seek, SeekFrom};
+use tauri::Manager;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct ReportRow {
@@ -27,17 +38,28 @@ struct ReportPayload {
     rows: Vec<ReportRow>,
 }
 
-#[tauri::command]
-fn generate_pdf_report(path: String, payload: ReportPayload) -> Result<(), String> {
+const PAGE_TOP: f64 = 285.0;
+const PAGE_BOTTOM: f64 = 20.0;
+const ROW_HEIGHT: f64 = 4.5;
+
+/// Event rows that fit on a page when the first one is written at `top`.
+fn rows_fitting(top: f64) -> usize {
+    if top < PAGE_BOTTOM {
+        return 0;
+    }
+    ((top - PAGE_BOTTOM) / ROW_HEIGHT) as usize + 1
+}
+
+fn write_pdf_report(path: String, payload: ReportPayload, job: &Job) -> Result<(), String> {
     let (doc, page1, layer1) =
         PdfDocument::new("Audit Report", Mm(210.0), Mm(297.0), "Layer 1");
-    let current_layer = doc.get_page(page1).get_layer(layer1);
+    let mut current_layer = doc.get_page(page1).get_layer(layer1);
 
     let font = doc
         .add_builtin_font(BuiltinFont::Helvetica)
         .map_err(|e| e.to_string())?;
 
-    let mut y = 285.0;
+    let mut y = PAGE_TOP;
     let line = 6.0;
 
     current_layer.use_text("Audit Report", 18.0, Mm(20.0), Mm(y), &font);
@@ -86,45 +108,122 @@ fn generate_pdf_report(path: String, payload: ReportPayload) -> Result<(), Strin
     }
     y -= 6.0;
 
-    section_title(&current_layer, &font, "Events (first 100)", &mut y);
-    for row in payload.rows.iter().take(100) {
-        let line_text = format!(
-            "{} | {} | {} | {} | {}",
-            row.timestamp, row.action, row.category, row.user, row.status
-        );
-        current_layer.use_text(line_text, 8.0, Mm(20.0), Mm(y), &font);
-        y -= 4.5;
-        if y < 20.0 {
-            break;
+    section_title(
+        &current_layer,
+        &font,
+        &format!("Events (first {})", payload.rows.len()),
+        &mut y,
+    );
+
+    // The summary takes the top of the first page; the rows continue on as many pages as
+    // they need, so the page count is known before any of them is drawn.
+    let first = rows_fitting(y);
+    let per_page = rows_fitting(PAGE_TOP);
+    let total = 1 + payload.rows.len().saturating_sub(first).div_ceil(per_page);
+    let mut rows = payload.rows.iter();
+    for page in 1..=total {
+        job.check()?;
+        let fits = if page == 1 {
+            first
+        } else {
+            let (next_page, next_layer) = doc.add_page(Mm(210.0), Mm(297.0), "Layer 1");
+            current_layer = doc.get_page(next_page).get_layer(next_layer);
+            y = PAGE_TOP;
+            per_page
+        };
+        for row in rows.by_ref().take(fits) {
+            let line_text = format!(
+                "{} | {} | {} | {} | {}",
+                row.timestamp, row.action, row.category, row.user, row.status
+            );
+            current_layer.use_text(line_text, 8.0, Mm(20.0), Mm(y), &font);
+            y -= ROW_HEIGHT;
         }
+        job.report(ProgressKind::Pages {
+            rendered: page,
+            total,
+        });
     }
+    job.check()?;
 
     let mut buffer = BufWriter::new(File::create(path).map_err(|e| e.to_string())?);
     doc.save(&mut buffer).map_err(|e| e.to_string())
 }
 
 #[tauri::command]
-fn read_tail_chunk(path: String, offset: u64) -> Result<(String, u64), String> {
-    let mut file = File::open(path).map_err(|e| e.to_string())?;
-    let metadata = file.metadata().map_err(|e| e.to_string())?;
-    let file_len = metadata.len();
-
-    if offset >= file_len {
-        return Ok(("".to_string(), file_len));
-    }
-
-    file.seek(SeekFrom::Start(offset))
-        .map_err(|e| e.to_string())?;
-    let mut buf = String::new();
-    file.read_to_string(&mut buf).map_err(|e| e.to_string())?;
-    Ok((buf, file_len))
+async fn generate_pdf_report(
+    app: tauri::AppHandle,
+    path: String,
+    payload: ReportPayload,
+    job_id: Option<String>,
+) -> Result<(), String> {
+    run_blocking(app, job_id, move |job| write_pdf_report(path, payload, job)).await
 }
 
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_fs::init())
-        .invoke_handler(tauri::generate_handler![generate_pdf_report, read_tail_chunk])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .manage(store::EventStore::default())
+        .manage(store::ResultView::default())
+        .manage(line_index::LineIndexCache::default())
+        .manage(search::SearchIndexes::default())
+        .manage(store::sql::SqlConsole::default())
+        .manage(jobs::JobRegistry::default())
+        .manage(tail::watcher::TailWatcher::default())
+        .manage(tail::session::TailSessions::default())
+        .manage(tail::checkpoint::TailCheckpoints::default())
+        .manage(collector::tail::RemoteTails::default())
+        .manage(collector::replay::ReplayJournal::default())
+        .manage(collector::health::HealthMonitors::default())
+        .invoke_handler(tauri::generate_handler![
+            generate_pdf_report,
+            tail::read_tail_chunk,
+            tail::watcher::tail_follow,
+            tail::watcher::tail_unfollow,
+            tail::session::tail_session_start,
+            tail::session::tail_session_status,
+            tail::session::tail_session_stop,
+            collector::tail::remote_tail_start,
+            collector::tail::remote_tail_stop,
+            collector::browse::collector_query,
+            collector::browse::collector_get_event,
+            collector::fleet::fleet_status,
+            collector::fleet::fleet_agent_metrics,
+            collector::replay::collector_replay,
+            collector::health::collector_health_start,
+            collector::health::collector_health_history,
+            collector::health::collector_health_stop,
+            loader::load_audit_file,
+            loader::directory::scan_audit_directory,
+            loader::directory::load_audit_directory,
+            loader::sqlite::sqlite_query,
+            validation::validate_audit_file,
+            store::store_open_files,
+            store::store_add_rows,
+            store::store_clear,
+            store::results_clear,
+            store::query_page,
+            store::count_events,
+            store::aggregate::aggregate,
+            store::integrity::integrity_chain,
+            store::export::export_events,
+            store::sql::run_sql,
+            store::sql::export_sql,
+            line_index::index_audit_file,
+            line_index::read_indexed_page,
+            search::search_index_files,
+            search::search_index_status,
+            search::search_index_clear,
+            search::search_events,
+            jobs::cancel_job
+        ])
+        .build(tauri::generate_context!())
+        .expect("error while running tauri application")
+        .run(|app, event| {
+            // Tails still running have checkpoints held back by the write interval.
+            if let tauri::RunEvent::Exit = event {
+                let _ = app.state::<tail::checkpoint::TailCheckpoints>().flush(app);
+            }
+        });
 }
@@ -0,0 +1,924 @@
+//! Persistent full-text index over audit events, one tantivy index per workspace.
+//!
+//! Every string anywhere in an event goes into `text`, and the whole event is indexed as
+//! a JSON field, so `timeout` and `error.message:timeout` both work. JSONL files are
+//! indexed from a tail cursor and only the appended bytes are read again; other formats
+//! are reindexed whenever they change. Which sources were indexed up to where is kept
+//! in the commit payload, so the manifest can never disagree with the documents. Chunks
+//! the live tail reads are indexed as they are, without reading the file again, and
+//! committed in batches; a search commits what is left.
+
+use crate::event::{parse_timestamp, source_name, EventRow};
+use crate::jobs::{run_blocking, Job, ProgressKind};
+use crate::loader::{self, Compression, LogFormat, RejectedLine};
+use crate::store::ResultView;
+use crate::tail::{read_chunk, TailChunk, TailCursor, TailTransition, DEFAULT_CHUNK_BYTES};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, UNIX_EPOCH};
+use tantivy::collector::{Count, TopDocs};
+use tantivy::directory::MmapDirectory;
+use tantivy::query::{AllQuery, Query, QueryParser};
+use tantivy::schema::{Field, OwnedValue, Schema, FAST, INDEXED, STORED, STRING, TEXT};
+use tantivy::{
+    DateTime, Index, IndexReader, IndexWriter, Order, ReloadPolicy, TantivyDocument, TantivyError,
+    Term,
+};
+use tauri::{AppHandle, Manager};
+
+pub const DEFAULT_WORKSPACE: &str = "default";
+const WRITER_HEAP_BYTES: usize = 50_000_000;
+const DEFAULT_LIMIT: usize = 200;
+const MAX_LIMIT: usize = 5000;
+/// Appends picked up from the tail are committed at most this often, unless this many
+/// documents are waiting.
+const FOLLOW_COMMIT_INTERVAL: Duration = Duration::from_secs(2);
+const FOLLOW_COMMIT_DOCS: usize = 10_000;
+
+#[derive(Clone, Copy)]
+struct Fields {
+    path: Field,
+    event_id: Field,
+    timestamp: Field,
+    text: Field,
+    event: Field,
+    raw: Field,
+    line: Field,
+    byte_offset: Field,
+}
+
+impl Fields {
+    fn schema() -> Schema {
+        let mut builder = Schema::builder();
+        builder.add_text_field("path", STRING | STORED);
+        builder.add_text_field("event_id", STRING);
+        builder.add_date_field("timestamp", INDEXED | FAST);
+        builder.add_text_field("text", TEXT);
+        builder.add_json_field("event", TEXT);
+        builder.add_text_field("raw", STORED);
+        builder.add_u64_field("line", STORED);
+        builder.add_u64_field("byte_offset", STORED);
+        builder.build()
+    }
+
+    fn of(schema: &Schema) -> Result<Fields, String> {
+        let field = |name: &str| schema.get_field(name).map_err(|e| e.to_string());
+        Ok(Fields {
+            path: field("path")?,
+            event_id: field("event_id")?,
+            timestamp: field("timestamp")?,
+            text: field("text")?,
+            event: field("event")?,
+            raw: field("raw")?,
+            line: field("line")?,
+            byte_offset: field("byte_offset")?,
+        })
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+struct FileStamp {
+    size: u64,
+    mtime_ns: u64,
+}
+
+impl FileStamp {
+    fn of(path: &Path) -> Result<FileStamp, String> {
+        let metadata = fs::metadata(path).map_err(|e| e.to_string())?;
+        let mtime_ns = metadata
+            .modified()
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+        Ok(FileStamp {
+            size: metadata.len(),
+            mtime_ns,
+        })
+    }
+}
+
+/// How far one source has been indexed. JSONL keeps a cursor and grows; anything else is
+/// read whole and only remembered by size and mtime.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct IndexedSource {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cursor: Option<TailCursor>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    stamp: Option<FileStamp>,
+    events: usize,
+    rejects: usize,
+    updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceStatus {
+    pub path: String,
+    pub events: usize,
+    pub rejects: usize,
+    pub incremental: bool,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchIndexStatus {
+    pub workspace: String,
+    pub directory: String,
+    pub documents: u64,
+    pub sources: Vec<SourceStatus>,
+    /// Why keeping up with the tail last failed, if the latest attempt did.
+    pub error: Option<String>,
+}
+
+/// Where a hit was read from. Only JSONL sources have lines and offsets.
+#[derive(Debug, Serialize)]
+pub struct SearchHit {
+    pub event_id: String,
+    pub path: String,
+    pub line: Option<u64>,
+    pub byte_offset: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SearchPage {
+    pub query: String,
+    pub total: usize,
+    pub offset: usize,
+    /// Hits on this page, now held by the result view.
+    pub events: usize,
+    /// In step with the events in the store.
+    pub hits: Vec<SearchHit>,
+    /// Hits on this page whose stored event no longer parses; counted in `total` only.
+    pub rejects: Vec<RejectedLine>,
+}
+
+struct Writer {
+    writer: IndexWriter,
+    sources: BTreeMap<String, IndexedSource>,
+    /// `sources` as of the last commit, to roll back to.
+    committed: BTreeMap<String, IndexedSource>,
+    committed_at: Instant,
+    /// Documents added since the last commit.
+    pending: usize,
+    error: Option<String>,
+}
+
+struct Workspace {
+    name: String,
+    dir: PathBuf,
+    index: Index,
+    fields: Fields,
+    reader: IndexReader,
+    writer: Mutex<Writer>,
+}
+
+/// Collects every string in an event, keys excluded, for the catch-all `text` field.
+fn collect_strings(value: &Value, out: &mut String) {
+    match value {
+        Value::String(s) => {
+            if !out.is_empty() {
+                out.push('\n');
+            }
+            out.push_str(s);
+        }
+        Value::Array(items) => items.iter().for_each(|item| collect_strings(item, out)),
+        Value::Object(map) => map.values().for_each(|item| collect_strings(item, out)),
+        _ => {}
+    }
+}
+
+fn valid_name(workspace: &str) -> Result<(), String> {
+    let ok = !workspace.is_empty()
+        && workspace.len() <= 64
+        && workspace
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if ok {
+        Ok(())
+    } else {
+        Err(format!(
+            "Invalid workspace name '{}': use letters, digits, '-' or '_'",
+            workspace
+        ))
+    }
+}
+
+impl Workspace {
+    fn open(app: &AppHandle, name: &str) -> Result<Workspace, String> {
+        valid_name(name)?;
+        let dir = app
+            .path()
+            .app_data_dir()
+            .map_err(|e| e.to_string())?
+            .join("search-index")
+            .join(name);
+        Workspace::open_in(name, dir)
+    }
+
+    fn open_in(name: &str, dir: PathBuf) -> Result<Workspace, String> {
+        fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+        let directory = MmapDirectory::open(&dir).map_err(|e| e.to_string())?;
+        let index = match Index::open_or_create(directory, Fields::schema()) {
+            Ok(index) => index,
+            // Built before the schema last changed; the sources can be indexed again.
+            Err(TantivyError::SchemaError(_)) => {
+                fs::remove_dir_all(&dir).map_err(|e| e.to_string())?;
+                fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+                Index::create_in_dir(&dir, Fields::schema()).map_err(|e| e.to_string())?
+            }
+            Err(e) => return Err(e.to_string()),
+        };
+        let fields = Fields::of(&index.schema())?;
+        let reader = index
+            .reader_builder()
+            .reload_policy(ReloadPolicy::Manual)
+            .try_into()
+            .map_err(|e| e.to_string())?;
+        let writer = index.writer(WRITER_HEAP_BYTES).map_err(|e| e.to_string())?;
+        let sources = index
+            .load_metas()
+            .ok()
+            .and_then(|metas| metas.payload)
+            .and_then(|payload| serde_json::from_str(&payload).ok());
+        // Without a readable manifest nothing says what is in there; start from scratch.
+        let sources = match sources {
+            Some(sources) => sources,
+            None => {
+                writer.delete_all_documents().map_err(|e| e.to_string())?;
+                BTreeMap::new()
+            }
+        };
+        Ok(Workspace {
+            name: name.to_string(),
+            dir,
+            index,
+            fields,
+            reader,
+            writer: Mutex::new(Writer {
+                writer,
+                committed: sources.clone(),
+                sources,
+                committed_at: Instant::now(),
+                pending: 0,
+                error: None,
+            }),
+        })
+    }
+
+    fn document(
+        &self,
+        path: &str,
+        row: &EventRow,
+        position: Option<&(usize, u64)>,
+    ) -> TantivyDocument {
+        let fields = self.fields;
+        let mut doc = TantivyDocument::default();
+        doc.add_text(fields.path, path);
+        if let Some(&(line, byte_offset)) = position {
+            doc.add_u64(fields.line, line as u64);
+            doc.add_u64(fields.byte_offset, byte_offset);
+        }
+        doc.add_text(fields.event_id, &row.event_id);
+        // Events without a usable timestamp sort as the oldest.
+        let micros = parse_timestamp(&row.timestamp)
+            .map(|ts| ts.timestamp_micros())
+            .unwrap_or(0);
+        doc.add_date(fields.timestamp, DateTime::from_timestamp_micros(micros));
+        let mut text = String::new();
+        collect_strings(&row.raw, &mut text);
+        doc.add_text(fields.text, text);
+        if row.raw.is_object() {
+            doc.add_field_value(fields.event, OwnedValue::from(row.raw.clone()));
+        }
+        doc.add_text(fields.raw, row.raw.to_string());
+        doc
+    }
+
+    /// `positions` holds the line number and byte offset of each row, when known.
+    fn add_rows(
+        &self,
+        writer: &mut Writer,
+        path: &str,
+        rows: &[EventRow],
+        positions: &[(usize, u64)],
+    ) -> Result<(), String> {
+        for (i, row) in rows.iter().enumerate() {
+            writer
+                .writer
+                .add_document(self.document(path, row, positions.get(i)))
+                .map_err(|e| e.to_string())?;
+        }
+        writer.pending += rows.len();
+        Ok(())
+    }
+
+    fn forget(&self, writer: &Writer, path: &str) {
+        writer
+            .writer
+            .delete_term(Term::from_field_text(self.fields.path, path));
+    }
+
+    fn commit(&self, writer: &mut Writer) -> Result<(), String> {
+        let payload = serde_json::to_string(&writer.sources).map_err(|e| e.to_string())?;
+        let mut prepared = writer.writer.prepare_commit().map_err(|e| e.to_string())?;
+        prepared.set_payload(&payload);
+        prepared.commit().map_err(|e| e.to_string())?;
+        writer.committed = writer.sources.clone();
+        writer.committed_at = Instant::now();
+        writer.pending = 0;
+        self.reader.reload().map_err(|e| e.to_string())
+    }
+
+    /// Commits whatever the tail left pending.
+    fn flush(&self, writer: &mut Writer) -> Result<(), String> {
+        if writer.pending > 0 || writer.sources != writer.committed {
+            self.commit(writer)?;
+        }
+        Ok(())
+    }
+
+    /// Drops everything since the last commit so the payload keeps matching the documents.
+    fn rollback(&self, writer: &mut Writer) -> Result<(), String> {
+        writer.sources = writer.committed.clone();
+        writer.pending = 0;
+        writer.writer.rollback().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Adds a chunk read from `source`'s cursor, starting over when the file was replaced
+    /// or truncated. A missing file keeps its documents. Returns whether anything changed.
+    fn apply(
+        &self,
+        writer: &mut Writer,
+        path: &str,
+        source: &mut IndexedSource,
+        chunk: &TailChunk,
+    ) -> Result<bool, String> {
+        if chunk.transition == Some(TailTransition::Missing) {
+            return Ok(false);
+        }
+        let mut changed = source.cursor.as_ref() != Some(&chunk.cursor);
+        if matches!(
+            chunk.transition,
+            Some(TailTransition::Replaced | TailTransition::Truncated { .. })
+        ) {
+            self.forget(writer, path);
+            source.events = 0;
+            source.rejects = 0;
+            changed = true;
+        }
+        self.add_rows(writer, path, &chunk.rows, &chunk.positions)?;
+        source.events += chunk.rows.len();
+        source.rejects += chunk.rejects.len();
+        source.cursor = Some(chunk.cursor.clone());
+        if changed {
+            source.updated_at = Utc::now().to_rfc3339();
+        }
+        Ok(changed)
+    }
+
+    /// Reads whatever the cursor has not seen yet.
+    fn append(
+        &self,
+        writer: &mut Writer,
+        path: &str,
+        mut source: IndexedSource,
+        job: &Job,
+    ) -> Result<bool, String> {
+        let mut changed = false;
+        loop {
+            let cursor = source.cursor.get_or_insert_with(TailCursor::default);
+            let chunk = read_chunk(Path::new(path), cursor, DEFAULT_CHUNK_BYTES, job)?;
+            changed |= self.apply(writer, path, &mut source, &chunk)?;
+            job.report(ProgressKind::Events {
+                parsed: source.events,
+            });
+            if !chunk.has_more || chunk.transition == Some(TailTransition::Missing) {
+                break;
+            }
+        }
+        writer.sources.insert(path.to_string(), source);
+        Ok(changed)
+    }
+
+    fn reload_whole(
+        &self,
+        writer: &mut Writer,
+        path: &str,
+        stamp: FileStamp,
+        job: &Job,
+    ) -> Result<bool, String> {
+        let previous = writer.sources.get(path);
+        if previous.and_then(|source| source.stamp.as_ref()) == Some(&stamp) {
+            return Ok(false);
+        }
+        let loaded = loader::load_path(Path::new(path), job)?;
+        self.forget(writer, path);
+        self.add_rows(writer, path, &loaded.rows, &[])?;
+        writer.sources.insert(
+            path.to_string(),
+            IndexedSource {
+                cursor: None,
+                stamp: Some(stamp),
+                events: loaded.rows.len(),
+                rejects: loaded.rejects.len(),
+                updated_at: Utc::now().to_rfc3339(),
+            },
+        );
+        Ok(true)
+    }
+
+    fn index_path(&self, writer: &mut Writer, path: &str, job: &Job) -> Result<bool, String> {
+        let (format, compression) = loader::format_of(Path::new(path), job)?;
+        if format == LogFormat::Jsonl && compression == Compression::None {
+            let source = match writer.sources.get(path) {
+                Some(source) if source.cursor.is_some() => source.clone(),
+                // Previously indexed whole, e.g. while the file was still a JSON array.
+                Some(_) => {
+                    self.forget(writer, path);
+                    IndexedSource::empty()
+                }
+                None => IndexedSource::empty(),
+            };
+            return self.append(writer, path, source, job);
+        }
+        self.reload_whole(writer, path, FileStamp::of(Path::new(path))?, job)
+    }
+
+    fn index_paths(&self, paths: &[String], job: &Job) -> Result<(), String> {
+        let mut writer = self.writer.lock().unwrap();
+        // Settle what the tail added first, so a failure below only undoes this batch.
+        self.flush(&mut writer)?;
+        let result = paths.iter().try_for_each(|path| {
+            job.check()?;
+            self.index_path(&mut writer, path, job)
+                .map(|_| ())
+                .map_err(|e| format!("{}: {}", path, e))
+        });
+        if let Err(e) = result {
+            self.rollback(&mut writer)?;
+            return Err(e);
+        }
+        self.commit(&mut writer)
+    }
+
+    /// Takes the chunk the tail read from `path` at `from`, if this workspace covers it,
+    /// and commits once enough has piled up. A failure drops every uncommitted append; the
+    /// cursors go back with them, so the next call reads those lines again.
+    fn follow(&self, path: &str, from: &TailCursor, chunk: &TailChunk, job: &Job) {
+        let mut writer = self.writer.lock().unwrap();
+        let result = self
+            .follow_locked(&mut writer, path, from, chunk, job)
+            .or_else(|e| {
+                self.rollback(&mut writer)
+                    .map_err(|rollback| format!("{} (rollback failed: {})", e, rollback))?;
+                Err(e)
+            });
+        writer.error = result.err().map(|e| format!("{}: {}", path, e));
+    }
+
+    fn follow_locked(
+        &self,
+        writer: &mut Writer,
+        path: &str,
+        from: &TailCursor,
+        chunk: &TailChunk,
+        job: &Job,
+    ) -> Result<(), String> {
+        let source = writer
+            .sources
+            .get(path)
+            .filter(|source| source.cursor.is_some());
+        match source.cloned() {
+            // Indexed up to where the tail read from, so the chunk is exactly what is new.
+            Some(mut source) if source.cursor.as_ref() == Some(from) => {
+                self.apply(writer, path, &mut source, chunk)?;
+                writer.sources.insert(path.to_string(), source);
+            }
+            // Behind or ahead of the tail, e.g. indexed before the tail resumed from a
+            // checkpoint; catch up from the file instead.
+            Some(source) => {
+                self.append(writer, path, source, job)?;
+            }
+            None => {}
+        }
+        if writer.pending >= FOLLOW_COMMIT_DOCS
+            || writer.committed_at.elapsed() >= FOLLOW_COMMIT_INTERVAL
+        {
+            self.flush(writer)?;
+        }
+        Ok(())
+    }
+
+    fn clear(&self, paths: Option<Vec<String>>) -> Result<(), String> {
+        let mut writer = self.writer.lock().unwrap();
+        match paths {
+            Some(paths) => {
+                for path in paths {
+                    self.forget(&writer, &path);
+                    writer.sources.remove(&path);
+                }
+            }
+            None => {
+                writer
+                    .writer
+                    .delete_all_documents()
+                    .map_err(|e| e.to_string())?;
+                writer.sources.clear();
+            }
+        }
+        self.commit(&mut writer)
+    }
+
+    fn status(&self) -> Result<SearchIndexStatus, String> {
+        let mut writer = self.writer.lock().unwrap();
+        self.flush(&mut writer)?;
+        Ok(SearchIndexStatus {
+            workspace: self.name.clone(),
+            directory: self.dir.to_string_lossy().into_owned(),
+            documents: self.reader.searcher().num_docs(),
+            sources: writer
+                .sources
+                .iter()
+                .map(|(path, source)| SourceStatus {
+                    path: path.clone(),
+                    events: source.events,
+                    rejects: source.rejects,
+                    incremental: source.cursor.is_some(),
+                    updated_at: source.updated_at.clone(),
+                })
+                .collect(),
+            error: writer.error.clone(),
+        })
+    }
+
+    /// Newest first. An empty query lists everything.
+    fn search(
+        &self,
+        query: &str,
+        limit: usize,
+        offset: usize,
+    ) -> Result<(SearchPage, Vec<EventRow>), String> {
+        let parsed: Box<dyn Query> = if query.trim().is_empty() {
+            Box::new(AllQuery)
+        } else {
+            let mut parser =
+                QueryParser::for_index(&self.index, vec![self.fields.text, self.fields.event]);
+            parser.set_conjunction_by_default();
+            parser.parse_query(query).map_err(|e| e.to_string())?
+        };
+        self.flush(&mut self.writer.lock().unwrap())?;
+        let searcher = self.reader.searcher();
+        let top = TopDocs::with_limit(limit.clamp(1, MAX_LIMIT))
+            .and_offset(offset)
+            .order_by_fast_field::<DateTime>("timestamp", Order::Desc);
+        let (hits, total) = searcher
+            .search(&parsed, &(top, Count))
+            .map_err(|e| e.to_string())?;
+
+        let mut rows = Vec::with_capacity(hits.len());
+        let mut found = Vec::with_capacity(hits.len());
+        let mut rejects = Vec::new();
+        for (_, address) in hits {
+            let doc: TantivyDocument = searcher.doc(address).map_err(|e| e.to_string())?;
+            let text = |field: Field| match doc.get_first(field) {
+                Some(OwnedValue::Str(s)) => s.as_str(),
+                _ => "",
+            };
+            let number = |field: Field| match doc.get_first(field) {
+                Some(OwnedValue::U64(n)) => Some(*n),
+                _ => None,
+            };
+            let path = text(self.fields.path);
+            let (line, byte_offset) = (number(self.fields.line), number(self.fields.byte_offset));
+            let raw = text(self.fields.raw);
+            let parsed = loader::parse_line_bytes(
+                raw.as_bytes(),
+                line.unwrap_or(0) as usize,
+                byte_offset.unwrap_or(0),
+                &source_name(Path::new(path)),
+            );
+            match parsed {
+                Some(Ok(row)) => {
+                    found.push(SearchHit {
+                        event_id: row.event_id.clone(),
+                        path: path.to_string(),
+                        line,
+                        byte_offset,
+                    });
+                    rows.push(row);
+                }
+                Some(Err(reject)) => rejects.push(reject),
+                None => rejects.push(RejectedLine::new(
+                    line.unwrap_or(0) as usize,
+                    byte_offset.unwrap_or(0),
+                    "the stored event is empty".to_string(),
+                    raw,
+                )),
+            }
+        }
+        let page = SearchPage {
+            query: query.to_string(),
+            total,
+            offset,
+            events: rows.len(),
+            hits: found,
+            rejects,
+        };
+        Ok((page, rows))
+    }
+}
+
+impl IndexedSource {
+    fn empty() -> IndexedSource {
+        IndexedSource {
+            cursor: None,
+            stamp: None,
+            events: 0,
+            rejects: 0,
+            updated_at: String::new(),
+        }
+    }
+}
+
+/// Workspaces opened this session. Each holds its index writer, and with it the
+/// directory lock, until the app exits.
+#[derive(Default)]
+pub struct SearchIndexes {
+    open: Mutex<HashMap<String, Arc<Workspace>>>,
+}
+
+impl SearchIndexes {
+    fn workspace(&self, app: &AppHandle, name: Option<String>) -> Result<Arc<Workspace>, String> {
+        let name = name.unwrap_or_else(|| DEFAULT_WORKSPACE.to_string());
+        let mut open = self.open.lock().unwrap();
+        if let Some(workspace) = open.get(&name) {
+            return Ok(workspace.clone());
+        }
+        let workspace = Arc::new(Workspace::open(app, &name)?);
+        open.insert(name, workspace.clone());
+        Ok(workspace)
+    }
+
+    /// Called by the tail with each chunk it read from `path` at `from`, so open
+    /// workspaces stay current. Failures show up in the workspace status.
+    pub fn follow(&self, path: &str, from: &TailCursor, chunk: &TailChunk, job: &Job) {
+        let open: Vec<_> = self.open.lock().unwrap().values().cloned().collect();
+        for workspace in open {
+            workspace.follow(path, from, chunk, job);
+        }
+    }
+}
+
+/// Indexes `paths` into `workspace`, reading only what changed since the last run.
+#[tauri::command]
+pub async fn search_index_files(
+    app: AppHandle,
+    workspace: Option<String>,
+    paths: Vec<String>,
+    job_id: Option<String>,
+) -> Result<SearchIndexStatus, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        let workspace = handle
+            .state::<SearchIndexes>()
+            .workspace(&handle, workspace)?;
+        workspace.index_paths(&paths, job)?;
+        workspace.status()
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn search_index_status(
+    app: AppHandle,
+    workspace: Option<String>,
+) -> Result<SearchIndexStatus, String> {
+    let handle = app.clone();
+    run_blocking(app, None, move |_| {
+        let workspace = handle
+            .state::<SearchIndexes>()
+            .workspace(&handle, workspace)?;
+        workspace.status()
+    })
+    .await
+}
+
+/// Drops the given sources from the index, or everything when `paths` is omitted.
+#[tauri::command]
+pub async fn search_index_clear(
+    app: AppHandle,
+    workspace: Option<String>,
+    paths: Option<Vec<String>>,
+) -> Result<SearchIndexStatus, String> {
+    let handle = app.clone();
+    run_blocking(app, None, move |_| {
+        let workspace = handle
+            .state::<SearchIndexes>()
+            .workspace(&handle, workspace)?;
+        workspace.clear(paths)?;
+        workspace.status()
+    })
+    .await
+}
+
+/// Full-text search; plain words match any string in the event and `error.message:timeout`
+/// style terms target one field. The hits are loaded into the result view.
+#[tauri::command]
+pub async fn search_events(
+    app: AppHandle,
+    workspace: Option<String>,
+    query: String,
+    limit: Option<usize>,
+    offset: Option<usize>,
+) -> Result<SearchPage, String> {
+    let handle = app.clone();
+    run_blocking(app, None, move |_| {
+        let workspace = handle
+            .state::<SearchIndexes>()
+            .workspace(&handle, workspace)?;
+        let (page, rows) =
+            workspace.search(&query, limit.unwrap_or(DEFAULT_LIMIT), offset.unwrap_or(0))?;
+        handle.state::<ResultView>().0.replace(rows);
+        Ok(page)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn temp(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("search-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn line(id: &str, message: &str) -> String {
+        format!(
+            "{{\"event_id\":\"{}\",\"timestamp\":\"2024-01-01T00:00:00Z\",\"error\":{{\"message\":\"{}\"}}}}\n",
+            id, message
+        )
+    }
+
+    fn append(path: &Path, lines: &[String]) {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(lines.concat().as_bytes()).unwrap();
+    }
+
+    fn ids(workspace: &Workspace, query: &str) -> Vec<String> {
+        let (_, rows) = workspace.search(query, MAX_LIMIT, 0).unwrap();
+        let mut ids: Vec<_> = rows.into_iter().map(|row| row.event_id).collect();
+        ids.sort();
+        ids
+    }
+
+    fn source(workspace: &Workspace, path: &str) -> IndexedSource {
+        workspace.writer.lock().unwrap().sources[path].clone()
+    }
+
+    #[test]
+    fn appended_lines_are_indexed_once() {
+        let dir = temp("append");
+        let log = dir.join("audit.jsonl");
+        let path = log.to_string_lossy().into_owned();
+        let workspace = Workspace::open_in("append", dir.join("index")).unwrap();
+        let job = Job::detached();
+
+        append(&log, &[line("a1", "timeout"), line("a2", "denied")]);
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+        assert_eq!(ids(&workspace, ""), ["a1", "a2"]);
+
+        append(&log, &[line("a3", "timeout")]);
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+        assert_eq!(ids(&workspace, ""), ["a1", "a2", "a3"]);
+        assert_eq!(ids(&workspace, "error.message:timeout"), ["a1", "a3"]);
+        let indexed = source(&workspace, &path);
+        assert_eq!(indexed.events, 3);
+        assert_eq!(
+            indexed.cursor.unwrap().offset,
+            fs::metadata(&log).unwrap().len()
+        );
+
+        // The tail's chunk is taken as is when it starts where the index stopped.
+        append(&log, &[line("a4", "slow")]);
+        let from = source(&workspace, &path).cursor.unwrap();
+        let chunk = read_chunk(&log, &from, DEFAULT_CHUNK_BYTES, &job).unwrap();
+        workspace.follow(&path, &from, &chunk, &job);
+        assert_eq!(ids(&workspace, ""), ["a1", "a2", "a3", "a4"]);
+
+        // A chunk from further on means the index fell behind; it catches up from the file.
+        append(&log, &[line("a5", "slow"), line("a6", "slow")]);
+        let behind = source(&workspace, &path).cursor.unwrap();
+        let first = read_chunk(&log, &behind, DEFAULT_CHUNK_BYTES, &job).unwrap();
+        append(&log, &[line("a7", "slow")]);
+        let chunk = read_chunk(&log, &first.cursor, DEFAULT_CHUNK_BYTES, &job).unwrap();
+        workspace.follow(&path, &first.cursor, &chunk, &job);
+        assert_eq!(ids(&workspace, "slow"), ["a4", "a5", "a6", "a7"]);
+        assert_eq!(workspace.status().unwrap().documents, 7);
+    }
+
+    #[test]
+    fn truncated_and_rotated_files_are_indexed_again() {
+        let dir = temp("rotate");
+        let log = dir.join("audit.jsonl");
+        let path = log.to_string_lossy().into_owned();
+        let workspace = Workspace::open_in("rotate", dir.join("index")).unwrap();
+        let job = Job::detached();
+
+        append(
+            &log,
+            &[line("old1", "x"), line("old2", "x"), line("old3", "x")],
+        );
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+
+        // Truncated in place and written again.
+        fs::write(&log, line("new1", "x")).unwrap();
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+        assert_eq!(ids(&workspace, ""), ["new1"]);
+        assert_eq!(source(&workspace, &path).events, 1);
+
+        // Rotated away, with a new file at the path that is already longer.
+        fs::rename(&log, dir.join("audit.jsonl.1")).unwrap();
+        append(&log, &[line("r1", "x"), line("r2", "x"), line("r3", "x")]);
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+        assert_eq!(ids(&workspace, ""), ["r1", "r2", "r3"]);
+
+        // The tail reports the same through its chunks.
+        let from = source(&workspace, &path).cursor.unwrap();
+        fs::remove_file(&log).unwrap();
+        append(&log, &[line("t1", "x")]);
+        let chunk = read_chunk(&log, &from, DEFAULT_CHUNK_BYTES, &job).unwrap();
+        assert!(chunk.transition.is_some());
+        workspace.follow(&path, &from, &chunk, &job);
+        assert_eq!(ids(&workspace, ""), ["t1"]);
+        assert_eq!(source(&workspace, &path).events, 1);
+
+        // A missing file keeps what was indexed.
+        fs::remove_file(&log).unwrap();
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap_err();
+        assert_eq!(ids(&workspace, ""), ["t1"]);
+    }
+
+    #[test]
+    fn a_failed_run_restores_the_previous_manifest() {
+        let dir = temp("rollback");
+        let log = dir.join("audit.jsonl");
+        let path = log.to_string_lossy().into_owned();
+        let missing = dir.join("gone.jsonl").to_string_lossy().into_owned();
+        let workspace = Workspace::open_in("rollback", dir.join("index")).unwrap();
+        let job = Job::detached();
+
+        append(&log, &[line("b1", "x")]);
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+        let before = workspace.writer.lock().unwrap().committed.clone();
+
+        append(&log, &[line("b2", "x")]);
+        let err = workspace
+            .index_paths(&[path.clone(), missing.clone()], &job)
+            .unwrap_err();
+        assert!(err.starts_with(&missing), "{}", err);
+        {
+            let writer = workspace.writer.lock().unwrap();
+            assert_eq!(writer.sources, before);
+            assert_eq!(writer.committed, before);
+            assert_eq!(writer.pending, 0);
+        }
+        assert_eq!(ids(&workspace, ""), ["b1"]);
+
+        // The manifest on disk agrees, so a reopened index picks up b2 next time.
+        drop(workspace);
+        let workspace = Workspace::open_in("rollback", dir.join("index")).unwrap();
+        assert_eq!(workspace.writer.lock().unwrap().sources, before);
+        workspace
+            .index_paths(std::slice::from_ref(&path), &job)
+            .unwrap();
+        assert_eq!(ids(&workspace, ""), ["b1", "b2"]);
+    }
+}
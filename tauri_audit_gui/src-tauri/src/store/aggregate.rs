@@ -0,0 +1,482 @@
+//! Group-by counts and numeric statistics over filtered events, optionally split into
+//! minute/hour/day/week buckets on the wall clock of an IANA time zone.
+
+use super::query::Field;
+use super::{view, EventFilter, EventStore, StoredEvent};
+use crate::jobs::{run_blocking, Job};
+use chrono::{DateTime, Datelike, Duration, Offset, TimeZone, Timelike, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use tauri::AppHandle;
+
+const DEFAULT_PERCENTILES: [f64; 3] = [50.0, 90.0, 99.0];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Bucket {
+    Minute,
+    Hour,
+    Day,
+    Week,
+}
+
+impl Bucket {
+    /// Start of the bucket holding `ts` in `tz`. Weeks start on Monday. Minutes and hours
+    /// keep the event's own offset, so the repeated hour at a DST change stays two buckets.
+    fn floor(self, ts: DateTime<Utc>, tz: Tz) -> DateTime<Tz> {
+        let local = ts.with_timezone(&tz);
+        let wall = local.naive_local();
+        let start = match self {
+            Bucket::Minute => wall.date().and_hms_opt(wall.hour(), wall.minute(), 0),
+            Bucket::Hour => wall.date().and_hms_opt(wall.hour(), 0, 0),
+            Bucket::Day => wall.date().and_hms_opt(0, 0, 0),
+            Bucket::Week => {
+                let back = wall.weekday().num_days_from_monday() as i64;
+                (wall.date() - Duration::days(back)).and_hms_opt(0, 0, 0)
+            }
+        }
+        .unwrap_or(wall);
+        if matches!(self, Bucket::Minute | Bucket::Hour) {
+            let offset = local.offset().fix();
+            return (start - offset).and_utc().with_timezone(&tz);
+        }
+        // Midnight can fall into a DST gap; the day then starts when the clock resumes.
+        (0..=24 * 4)
+            .find_map(|quarter| {
+                tz.from_local_datetime(&(start + Duration::minutes(15 * quarter)))
+                    .earliest()
+            })
+            .unwrap_or(local)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AggregateSpec {
+    /// Short keys (`user`, `status`, ...) or dotted paths such as `actor.roles`.
+    pub group_by: Vec<String>,
+    pub bucket: Option<Bucket>,
+    /// IANA name, e.g. `Europe/Berlin`; UTC when omitted.
+    pub time_zone: Option<String>,
+    /// Numeric fields to summarise per group, e.g. `performance.duration_ms`.
+    pub metrics: Vec<String>,
+    pub percentiles: Option<Vec<f64>>,
+    /// Keep only the largest key combinations, counted across all buckets, so a stacked
+    /// chart keeps the same series in every bucket.
+    pub limit: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricStats {
+    pub count: usize,
+    pub sum: f64,
+    pub min: f64,
+    pub max: f64,
+    pub avg: f64,
+    /// Keyed `p50`, `p99.9`, ...; linear interpolation between the closest ranks.
+    pub percentiles: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Group {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bucket: Option<String>,
+    /// One value per `group_by` field, `null` where the event has none.
+    pub keys: Vec<Value>,
+    pub count: usize,
+    pub metrics: BTreeMap<String, MetricStats>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Aggregation {
+    pub matched: usize,
+    /// Matched events left out of a bucketed aggregation for lack of a timestamp.
+    pub unbucketed: usize,
+    /// Distinct key combinations before `limit`.
+    pub group_count: usize,
+    pub time_zone: String,
+    pub groups: Vec<Group>,
+}
+
+struct Acc {
+    bucket: Option<DateTime<Tz>>,
+    keys: Vec<Value>,
+    count: usize,
+    samples: Vec<Vec<f64>>,
+}
+
+struct Compiled {
+    group_by: Vec<Field>,
+    bucket: Option<Bucket>,
+    tz: Tz,
+    metrics: Vec<(String, Field)>,
+    percentiles: Vec<f64>,
+    limit: Option<usize>,
+    matched: usize,
+    unbucketed: usize,
+    groups: HashMap<(Option<i64>, Vec<String>), Acc>,
+}
+
+fn numbers(field: &Field, event: &StoredEvent) -> Vec<f64> {
+    field
+        .values(event)
+        .iter()
+        .filter_map(|value| match value {
+            Value::Number(n) => n.as_f64(),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every combination of the fields' values; a field without any counts as `null`.
+fn combinations(fields: &[Field], event: &StoredEvent) -> Vec<Vec<Value>> {
+    let mut combos = vec![Vec::new()];
+    for field in fields {
+        let mut seen = HashSet::new();
+        let mut values = field.values(event);
+        values.retain(|value| seen.insert(value.to_string()));
+        if values.is_empty() {
+            values.push(Value::Null);
+        }
+        combos = combos
+            .into_iter()
+            .flat_map(|combo| {
+                values.iter().map(move |value| {
+                    let mut next = combo.clone();
+                    next.push(value.clone());
+                    next
+                })
+            })
+            .collect();
+    }
+    combos
+}
+
+fn percentile(sorted: &[f64], p: f64) -> f64 {
+    let rank = p / 100.0 * (sorted.len() - 1) as f64;
+    let (lo, hi) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lo] + (sorted[hi] - sorted[lo]) * (rank - lo as f64)
+}
+
+fn stats(mut samples: Vec<f64>, percentiles: &[f64]) -> Option<MetricStats> {
+    if samples.is_empty() {
+        return None;
+    }
+    samples.sort_by(f64::total_cmp);
+    let sum: f64 = samples.iter().sum();
+    Some(MetricStats {
+        count: samples.len(),
+        sum,
+        min: samples[0],
+        max: samples[samples.len() - 1],
+        avg: sum / samples.len() as f64,
+        percentiles: percentiles
+            .iter()
+            .map(|&p| (format!("p{}", p), percentile(&samples, p)))
+            .collect(),
+    })
+}
+
+impl Compiled {
+    fn new(spec: &AggregateSpec) -> Result<Compiled, String> {
+        let zone = spec.time_zone.as_deref().unwrap_or("UTC");
+        let tz: Tz = zone
+            .parse()
+            .map_err(|_| format!("Unknown time zone '{}'", zone))?;
+        let percentiles = spec
+            .percentiles
+            .clone()
+            .unwrap_or_else(|| DEFAULT_PERCENTILES.to_vec());
+        if let Some(p) = percentiles.iter().find(|p| !(0.0..=100.0).contains(*p)) {
+            return Err(format!("Percentile {} is outside 0-100", p));
+        }
+        Ok(Compiled {
+            group_by: spec
+                .group_by
+                .iter()
+                .map(|name| Field::parse(name))
+                .collect::<Result<_, _>>()?,
+            bucket: spec.bucket,
+            tz,
+            metrics: spec
+                .metrics
+                .iter()
+                .map(|name| Field::parse(name).map(|field| (name.clone(), field)))
+                .collect::<Result<_, _>>()?,
+            percentiles,
+            limit: spec.limit,
+            matched: 0,
+            unbucketed: 0,
+            groups: HashMap::new(),
+        })
+    }
+
+    fn add(&mut self, event: &StoredEvent) {
+        self.matched += 1;
+        let bucket = match self.bucket {
+            None => None,
+            Some(bucket) => match event.ts {
+                Some(ts) => Some(bucket.floor(ts, self.tz)),
+                None => {
+                    self.unbucketed += 1;
+                    return;
+                }
+            },
+        };
+        let samples: Vec<Vec<f64>> = self
+            .metrics
+            .iter()
+            .map(|(_, field)| numbers(field, event))
+            .collect();
+        for keys in combinations(&self.group_by, event) {
+            let id = (
+                bucket.map(|b| b.timestamp()),
+                keys.iter().map(Value::to_string).collect(),
+            );
+            let acc = self.groups.entry(id).or_insert_with(|| Acc {
+                bucket,
+                keys,
+                count: 0,
+                samples: vec![Vec::new(); samples.len()],
+            });
+            acc.count += 1;
+            for (into, from) in acc.samples.iter_mut().zip(&samples) {
+                into.extend_from_slice(from);
+            }
+        }
+    }
+
+    fn finish(self) -> Aggregation {
+        let mut totals: HashMap<&Vec<String>, usize> = HashMap::new();
+        for ((_, keys), acc) in &self.groups {
+            *totals.entry(keys).or_default() += acc.count;
+        }
+        let group_count = totals.len();
+        let mut ranked: Vec<(&Vec<String>, usize)> = totals.into_iter().collect();
+        ranked.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(b.0)));
+        let rank: HashMap<Vec<String>, usize> = ranked
+            .into_iter()
+            .take(self.limit.unwrap_or(usize::MAX))
+            .enumerate()
+            .map(|(i, (keys, _))| (keys.clone(), i))
+            .collect();
+
+        let mut kept: Vec<(Option<i64>, usize, Acc)> = self
+            .groups
+            .into_iter()
+            .filter_map(|((bucket, keys), acc)| rank.get(&keys).map(|&r| (bucket, r, acc)))
+            .collect();
+        kept.sort_by_key(|(bucket, rank, _)| (*bucket, *rank));
+
+        let names: Vec<&String> = self.metrics.iter().map(|(name, _)| name).collect();
+        let groups = kept
+            .into_iter()
+            .map(|(_, _, acc)| Group {
+                bucket: acc.bucket.map(|b| b.to_rfc3339()),
+                keys: acc.keys,
+                count: acc.count,
+                metrics: names
+                    .iter()
+                    .zip(acc.samples)
+                    .filter_map(|(name, samples)| {
+                        stats(samples, &self.percentiles).map(|s| (name.to_string(), s))
+                    })
+                    .collect(),
+            })
+            .collect();
+        Aggregation {
+            matched: self.matched,
+            unbucketed: self.unbucketed,
+            group_count,
+            time_zone: self.tz.name().to_string(),
+            groups,
+        }
+    }
+}
+
+fn run<'e>(
+    events: impl Iterator<Item = &'e StoredEvent>,
+    filter: &EventFilter,
+    specs: &[AggregateSpec],
+    job: &Job,
+) -> Result<Vec<Aggregation>, String> {
+    let compiled = filter.compile()?;
+    let mut specs: Vec<Compiled> = specs.iter().map(Compiled::new).collect::<Result<_, _>>()?;
+    for (i, event) in events.filter(|e| compiled.matches(e)).enumerate() {
+        if i % 10_000 == 0 {
+            job.check()?;
+        }
+        for spec in &mut specs {
+            spec.add(event);
+        }
+    }
+    Ok(specs.into_iter().map(Compiled::finish).collect())
+}
+
+/// The last result, reused while filter, specs and the store's data are unchanged.
+pub(super) struct Cached {
+    key: String,
+    generation: u64,
+    result: Vec<Aggregation>,
+}
+
+fn cached(
+    store: &EventStore,
+    filter: &EventFilter,
+    specs: &[AggregateSpec],
+    job: &Job,
+) -> Result<Vec<Aggregation>, String> {
+    let key = serde_json::to_string(&(filter, specs)).map_err(|e| e.to_string())?;
+    let inner = store.inner.read().unwrap();
+    if let Some(cached) = store.aggregates.lock().unwrap().as_ref() {
+        if cached.key == key && cached.generation == inner.generation {
+            return Ok(cached.result.clone());
+        }
+    }
+    let result = run(inner.events.iter(), filter, specs, job)?;
+    *store.aggregates.lock().unwrap() = Some(Cached {
+        key,
+        generation: inner.generation,
+        result: result.clone(),
+    });
+    Ok(result)
+}
+
+/// Runs every spec in one pass over the stored events `filter` matches.
+#[tauri::command]
+pub async fn aggregate(
+    app: AppHandle,
+    results: bool,
+    filter: EventFilter,
+    specs: Vec<AggregateSpec>,
+    job_id: Option<String>,
+) -> Result<Vec<Aggregation>, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        cached(view(&handle, results), &filter, &specs, job)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{AuditEvent, EventRow};
+    use serde_json::json;
+
+    fn floor(bucket: Bucket, ts: &str, zone: &str) -> String {
+        let ts = DateTime::parse_from_rfc3339(ts)
+            .unwrap()
+            .with_timezone(&Utc);
+        bucket.floor(ts, zone.parse().unwrap()).to_rfc3339()
+    }
+
+    fn event(id: &str, ts: &str) -> StoredEvent {
+        let raw = json!({
+            "event_id": id,
+            "timestamp": ts,
+            "action": {"type": "READ", "category": "AUTH"},
+        });
+        let event = AuditEvent::from_value(&raw).unwrap();
+        StoredEvent::new(EventRow::new(&event, raw, "test.log"))
+    }
+
+    #[test]
+    fn the_repeated_hour_stays_two_buckets() {
+        // New York falls back from 02:00 EDT to 01:00 EST on 2025-11-02.
+        let zone = "America/New_York";
+        assert_eq!(
+            floor(Bucket::Hour, "2025-11-02T05:30:00Z", zone),
+            "2025-11-02T01:00:00-04:00"
+        );
+        assert_eq!(
+            floor(Bucket::Hour, "2025-11-02T06:30:00Z", zone),
+            "2025-11-02T01:00:00-05:00"
+        );
+        assert_eq!(
+            floor(Bucket::Minute, "2025-11-02T06:30:59Z", zone),
+            "2025-11-02T01:30:00-05:00"
+        );
+        // Both halves belong to the same 25-hour day.
+        assert_eq!(
+            floor(Bucket::Day, "2025-11-02T05:30:00Z", zone),
+            "2025-11-02T00:00:00-04:00"
+        );
+        assert_eq!(
+            floor(Bucket::Day, "2025-11-03T04:59:00Z", zone),
+            "2025-11-02T00:00:00-04:00"
+        );
+
+        let spec = AggregateSpec {
+            bucket: Some(Bucket::Hour),
+            time_zone: Some(zone.to_string()),
+            ..AggregateSpec::default()
+        };
+        let mut compiled = Compiled::new(&spec).unwrap();
+        for (id, ts) in [
+            ("a", "2025-11-02T05:10:00Z"),
+            ("b", "2025-11-02T05:50:00Z"),
+            ("c", "2025-11-02T06:10:00Z"),
+        ] {
+            compiled.add(&event(id, ts));
+        }
+        let groups: Vec<(Option<String>, usize)> = compiled
+            .finish()
+            .groups
+            .into_iter()
+            .map(|group| (group.bucket, group.count))
+            .collect();
+        assert_eq!(
+            groups,
+            vec![
+                (Some("2025-11-02T01:00:00-04:00".to_string()), 2),
+                (Some("2025-11-02T01:00:00-05:00".to_string()), 1),
+            ]
+        );
+    }
+
+    #[test]
+    fn a_skipped_midnight_starts_the_day_when_the_clock_resumes() {
+        // São Paulo jumped from 00:00 to 01:00 on 2018-11-04.
+        let zone = "America/Sao_Paulo";
+        assert_eq!(
+            floor(Bucket::Day, "2018-11-04T15:00:00Z", zone),
+            "2018-11-04T01:00:00-02:00"
+        );
+        assert_eq!(
+            floor(Bucket::Day, "2018-11-03T15:00:00Z", zone),
+            "2018-11-03T00:00:00-03:00"
+        );
+        // New York skips 02:00-03:00 on 2025-03-09; the hour after the gap is 03:00.
+        assert_eq!(
+            floor(Bucket::Hour, "2025-03-09T07:15:00Z", "America/New_York"),
+            "2025-03-09T03:00:00-04:00"
+        );
+    }
+
+    #[test]
+    fn weeks_start_on_local_monday() {
+        let zone = "America/New_York";
+        // Sunday of the fall-back weekend still belongs to the week that began in EDT.
+        assert_eq!(
+            floor(Bucket::Week, "2025-11-02T15:00:00Z", zone),
+            "2025-10-27T00:00:00-04:00"
+        );
+        assert_eq!(
+            floor(Bucket::Week, "2025-11-05T15:00:00Z", zone),
+            "2025-11-03T00:00:00-05:00"
+        );
+        // Monday 03:00 UTC is still Sunday evening in New York.
+        assert_eq!(
+            floor(Bucket::Week, "2025-11-10T03:00:00Z", zone),
+            "2025-11-03T00:00:00-05:00"
+        );
+        assert_eq!(
+            floor(Bucket::Week, "2025-11-10T03:00:00Z", "UTC"),
+            "2025-11-10T00:00:00+00:00"
+        );
+    }
+}
@@ -0,0 +1,98 @@
+//! Writes the filtered events, in display order, as JSON or CSV.
+
+use super::{view, EventFilter, EventStore, SortSpec};
+use crate::jobs::{run_blocking, Job};
+use serde::Deserialize;
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use tauri::AppHandle;
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ExportFormat {
+    /// The original events as a JSON array.
+    Json,
+    /// The table columns.
+    Csv,
+}
+
+const CSV_COLUMNS: [&str; 6] = [
+    "event_id",
+    "timestamp",
+    "action",
+    "category",
+    "user",
+    "status",
+];
+
+fn export(
+    store: &EventStore,
+    filter: &EventFilter,
+    sort: &SortSpec,
+    path: &str,
+    format: ExportFormat,
+    job: &Job,
+) -> Result<usize, String> {
+    let indices = store.matching(filter, sort)?;
+    let inner = store.inner.read().unwrap();
+    let file = File::create(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut out = BufWriter::new(file);
+    match format {
+        ExportFormat::Json => {
+            out.write_all(b"[").map_err(|e| e.to_string())?;
+            for (i, &index) in indices.iter().enumerate() {
+                if i % 10_000 == 0 {
+                    job.check()?;
+                }
+                let separator: &[u8] = if i == 0 { b"\n" } else { b",\n" };
+                out.write_all(separator).map_err(|e| e.to_string())?;
+                serde_json::to_writer(&mut out, &inner.events[index].row.raw)
+                    .map_err(|e| e.to_string())?;
+            }
+            out.write_all(b"\n]\n").map_err(|e| e.to_string())?;
+        }
+        ExportFormat::Csv => {
+            let mut writer = csv::Writer::from_writer(&mut out);
+            writer
+                .write_record(CSV_COLUMNS)
+                .map_err(|e| e.to_string())?;
+            for (i, &index) in indices.iter().enumerate() {
+                if i % 10_000 == 0 {
+                    job.check()?;
+                }
+                let row = &inner.events[index].row;
+                writer
+                    .write_record([
+                        &row.event_id,
+                        &row.timestamp,
+                        &row.action,
+                        &row.category,
+                        &row.user,
+                        &row.status,
+                    ])
+                    .map_err(|e| e.to_string())?;
+            }
+            writer.flush().map_err(|e| e.to_string())?;
+        }
+    }
+    out.flush().map_err(|e| e.to_string())?;
+    Ok(indices.len())
+}
+
+/// Writes every event the filter matches to `path` and returns how many there were.
+#[tauri::command]
+pub async fn export_events(
+    app: AppHandle,
+    results: bool,
+    filter: EventFilter,
+    sort: SortSpec,
+    path: String,
+    format: ExportFormat,
+    job_id: Option<String>,
+) -> Result<usize, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        export(view(&handle, results), &filter, &sort, &path, format, job)
+    })
+    .await
+}
@@ -0,0 +1,119 @@
+//! Filter panel semantics from `applyFilters` in `app.js`, evaluated in Rust.
+
+use super::query::Query;
+use super::StoredEvent;
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EventFilter {
+    pub search: String,
+    pub regex: bool,
+    pub query: String,
+    pub category: Option<String>,
+    pub status: Option<String>,
+    pub errors_only: bool,
+    pub date_from: Option<String>,
+    pub date_to: Option<String>,
+}
+
+pub struct CompiledFilter<'a> {
+    filter: &'a EventFilter,
+    search: Option<Regex>,
+    query: Query,
+    from: Option<DateTime<Utc>>,
+    to: Option<DateTime<Utc>>,
+}
+
+fn parse_day(value: &Option<String>) -> Result<Option<NaiveDate>, String> {
+    value
+        .as_deref()
+        .filter(|v| !v.is_empty())
+        .map(|v| NaiveDate::parse_from_str(v, "%Y-%m-%d").map_err(|e| format!("{}: {}", v, e)))
+        .transpose()
+}
+
+impl EventFilter {
+    pub fn compile(&self) -> Result<CompiledFilter<'_>, String> {
+        let search = if self.search.is_empty() {
+            None
+        } else {
+            let pattern = if self.regex {
+                self.search.clone()
+            } else {
+                regex::escape(&self.search)
+            };
+            let re = RegexBuilder::new(&pattern)
+                .case_insensitive(true)
+                .build()
+                .map_err(|e| format!("Invalid regex: {}", e))?;
+            Some(re)
+        };
+        let from = parse_day(&self.date_from)?
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc());
+        let to = parse_day(&self.date_to)?
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|d| d.and_utc() + Duration::days(1) - Duration::milliseconds(1));
+
+        Ok(CompiledFilter {
+            filter: self,
+            search,
+            query: Query::parse(&self.query)?,
+            from,
+            to,
+        })
+    }
+}
+
+impl CompiledFilter<'_> {
+    pub fn matches(&self, event: &StoredEvent) -> bool {
+        let row = &event.row;
+        if let Some(re) = &self.search {
+            let target = [
+                &row.event_id,
+                &row.action,
+                &row.user,
+                &row.category,
+                &row.status,
+            ]
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| s.as_str())
+            .collect::<Vec<_>>()
+            .join(" ");
+            if !re.is_match(&target) {
+                return false;
+            }
+        }
+        if !self.query.matches(event) {
+            return false;
+        }
+        if let Some(category) = self.filter.category.as_deref().filter(|c| !c.is_empty()) {
+            if row.category != category {
+                return false;
+            }
+        }
+        if let Some(status) = self.filter.status.as_deref().filter(|s| !s.is_empty()) {
+            if row.status != status {
+                return false;
+            }
+        }
+        if self.filter.errors_only && !event.error_occurred() {
+            return false;
+        }
+        if let Some(from) = self.from {
+            if event.ts.is_none_or(|ts| ts < from) {
+                return false;
+            }
+        }
+        if let Some(to) = self.to {
+            if event.ts.is_none_or(|ts| ts > to) {
+                return false;
+            }
+        }
+        true
+    }
+}
@@ -0,0 +1,151 @@
+//! SHA-256 hash chain over the filtered events, oldest first, checked against the
+//! `prev_hash`/`hash` fields the events carry.
+
+use super::{view, EventFilter, EventStore, SortDir, SortSpec};
+use crate::jobs::{run_blocking, Job};
+use ring::digest::{digest, SHA256};
+use serde::Serialize;
+use serde_json::Value;
+use tauri::AppHandle;
+
+/// Links listed in the panel; the chain itself covers every event.
+const RECENT_LINKS: usize = 100;
+
+#[derive(Debug, Serialize)]
+pub struct IntegrityLink {
+    pub timestamp: String,
+    pub prev: String,
+    pub hash: String,
+    pub ok: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegrityReport {
+    pub chain_hash: String,
+    pub events: usize,
+    pub mismatches: usize,
+    /// The last links of the chain, oldest first.
+    pub links: Vec<IntegrityLink>,
+}
+
+/// JSON with object keys sorted, so the hash does not depend on field order.
+fn canonical(value: &Value, out: &mut String) {
+    match value {
+        Value::Array(items) => {
+            out.push('[');
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                canonical(item, out);
+            }
+            out.push(']');
+        }
+        Value::Object(map) => {
+            let mut keys: Vec<&String> = map.keys().collect();
+            keys.sort();
+            out.push('{');
+            for (i, key) in keys.into_iter().enumerate() {
+                if i > 0 {
+                    out.push(',');
+                }
+                out.push_str(&Value::String(key.clone()).to_string());
+                out.push(':');
+                canonical(&map[key], out);
+            }
+            out.push('}');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+fn link_hash(prev: &str, event: &Value) -> String {
+    let mut payload = String::from("{\"event\":");
+    canonical(event, &mut payload);
+    payload.push_str(",\"prev\":");
+    payload.push_str(&Value::String(prev.to_string()).to_string());
+    payload.push('}');
+    digest(&SHA256, payload.as_bytes())
+        .as_ref()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn chain(store: &EventStore, filter: &EventFilter, job: &Job) -> Result<IntegrityReport, String> {
+    let oldest_first = SortSpec {
+        key: "timestamp".to_string(),
+        dir: SortDir::Asc,
+    };
+    let indices = store.matching(filter, &oldest_first)?;
+    let inner = store.inner.read().unwrap();
+
+    let mut prev = "0".to_string();
+    let mut mismatches = 0;
+    let mut links = Vec::new();
+    for (i, &index) in indices.iter().enumerate() {
+        if i % 10_000 == 0 {
+            job.check()?;
+        }
+        let row = &inner.events[index].row;
+        let hash = link_hash(&prev, &row.raw);
+        let claims = |key: &str, expected: &str| {
+            row.raw[key]
+                .as_str()
+                .is_some_and(|v| !v.is_empty() && v != expected)
+        };
+        let ok = !claims("prev_hash", &prev) && !claims("hash", &hash);
+        if !ok {
+            mismatches += 1;
+        }
+        if indices.len() - i <= RECENT_LINKS {
+            links.push(IntegrityLink {
+                timestamp: row.timestamp.clone(),
+                prev: prev.chars().take(12).collect(),
+                hash: hash.chars().take(12).collect(),
+                ok,
+            });
+        }
+        prev = hash;
+    }
+    Ok(IntegrityReport {
+        chain_hash: prev,
+        events: indices.len(),
+        mismatches,
+        links,
+    })
+}
+
+#[tauri::command]
+pub async fn integrity_chain(
+    app: AppHandle,
+    results: bool,
+    filter: EventFilter,
+    job_id: Option<String>,
+) -> Result<IntegrityReport, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        chain(view(&handle, results), &filter, job)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn hash_ignores_key_order() {
+        let event = json!({"event_id": "a", "timestamp": "2024-01-01T00:00:00Z", "z": [1, {"y": 2, "b": "x"}]});
+        let reordered = json!({"z": [1, {"b": "x", "y": 2}], "timestamp": "2024-01-01T00:00:00Z", "event_id": "a"});
+        let hash = link_hash("0", &event);
+        assert_eq!(hash, link_hash("0", &reordered));
+        // sha256 of {"event":{"event_id":"a","timestamp":"...","z":[1,{"b":"x","y":2}]},"prev":"0"}
+        assert_eq!(
+            hash,
+            "9279d861a8cf9d0376039b331c7b5f4f6aca72043e4d5d14424168c0703ab65a"
+        );
+        assert_ne!(hash, link_hash("1", &event));
+    }
+}
@@ -0,0 +1,334 @@
+//! Backend event store so the webview only ever holds the visible page.
+
+pub mod aggregate;
+pub mod export;
+mod filter;
+pub mod integrity;
+pub mod query;
+pub mod sql;
+
+pub use filter::EventFilter;
+
+use crate::event::{parse_timestamp, EventRow};
+use crate::jobs::{run_blocking, Job};
+use crate::loader::{load_path, Compression, LoadResult, LogFormat, RejectedLine};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::path::Path;
+use std::sync::{Mutex, RwLock};
+use tauri::{AppHandle, Manager, State};
+
+pub struct StoredEvent {
+    pub row: EventRow,
+    pub ts: Option<DateTime<Utc>>,
+}
+
+impl StoredEvent {
+    pub fn new(row: EventRow) -> Self {
+        let ts = parse_timestamp(&row.timestamp);
+        StoredEvent { row, ts }
+    }
+
+    pub fn error_occurred(&self) -> bool {
+        self.row.raw["error"]["occurred"].as_bool().unwrap_or(false)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortDir {
+    Asc,
+    #[default]
+    Desc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SortSpec {
+    pub key: String,
+    pub dir: SortDir,
+}
+
+impl Default for SortSpec {
+    fn default() -> Self {
+        SortSpec {
+            key: "timestamp".to_string(),
+            dir: SortDir::Desc,
+        }
+    }
+}
+
+#[derive(Default)]
+struct StoreInner {
+    events: Vec<StoredEvent>,
+    generation: u64,
+}
+
+struct QueryCache {
+    key: String,
+    generation: u64,
+    indices: Vec<usize>,
+}
+
+#[derive(Default)]
+pub struct EventStore {
+    inner: RwLock<StoreInner>,
+    cache: Mutex<Option<QueryCache>>,
+    aggregates: Mutex<Option<aggregate::Cached>>,
+}
+
+/// Pages read from a collector, a database, a large file or the search index. They get a
+/// view of their own so browsing never discards the events loaded into the store.
+#[derive(Default)]
+pub struct ResultView(pub EventStore);
+
+/// The events a command reads: the result view when `results` is set, otherwise the store.
+pub fn view(app: &AppHandle, results: bool) -> &EventStore {
+    match results {
+        true => &app.state::<ResultView>().inner().0,
+        false => app.state::<EventStore>().inner(),
+    }
+}
+
+fn compare(a: &StoredEvent, b: &StoredEvent, key: &str) -> Ordering {
+    if key == "timestamp" {
+        let at = a.ts.map(|t| t.timestamp_millis()).unwrap_or(0);
+        let bt = b.ts.map(|t| t.timestamp_millis()).unwrap_or(0);
+        return at.cmp(&bt);
+    }
+    let field = |e: &StoredEvent| match key {
+        "action" => e.row.action.clone(),
+        "category" => e.row.category.clone(),
+        "user" => e.row.user.clone(),
+        "status" => e.row.status.clone(),
+        "source" => e.row.source.clone(),
+        "event_id" => e.row.event_id.clone(),
+        _ => String::new(),
+    };
+    field(a).cmp(&field(b))
+}
+
+impl EventStore {
+    pub fn replace(&self, rows: Vec<EventRow>) {
+        let mut inner = self.inner.write().unwrap();
+        inner.events = rows.into_iter().map(StoredEvent::new).collect();
+        inner.generation += 1;
+    }
+
+    pub fn append(&self, rows: Vec<EventRow>) -> usize {
+        if rows.is_empty() {
+            return self.len();
+        }
+        let mut inner = self.inner.write().unwrap();
+        inner.events.extend(rows.into_iter().map(StoredEvent::new));
+        inner.generation += 1;
+        inner.events.len()
+    }
+
+    pub fn clear(&self) {
+        self.replace(Vec::new());
+    }
+
+    pub fn len(&self) -> usize {
+        self.inner.read().unwrap().events.len()
+    }
+
+    /// Stores the rows of `loaded` and keeps only a description of each file.
+    pub fn load(&self, loaded: Vec<LoadResult>, append: bool) -> StoreLoadSummary {
+        let mut rows = Vec::new();
+        let mut files = Vec::new();
+        for file in loaded {
+            files.push(LoadedFile {
+                path: file.path,
+                format: file.format,
+                compression: file.compression,
+                events: file.rows.len(),
+                rejects: file.rejects,
+            });
+            rows.extend(file.rows);
+        }
+        if append {
+            self.append(rows);
+        } else {
+            self.replace(rows);
+        }
+        StoreLoadSummary {
+            total: self.len(),
+            files,
+        }
+    }
+
+    /// Indices of matching events in display order, reused while filter, sort and data are unchanged.
+    fn matching(&self, filter: &EventFilter, sort: &SortSpec) -> Result<Vec<usize>, String> {
+        let key = serde_json::to_string(&(filter, sort)).map_err(|e| e.to_string())?;
+        let inner = self.inner.read().unwrap();
+        let mut cache = self.cache.lock().unwrap();
+        if let Some(cached) = cache.as_ref() {
+            if cached.key == key && cached.generation == inner.generation {
+                return Ok(cached.indices.clone());
+            }
+        }
+
+        let compiled = filter.compile()?;
+        let mut indices: Vec<usize> = inner
+            .events
+            .iter()
+            .enumerate()
+            .filter(|(_, event)| compiled.matches(event))
+            .map(|(i, _)| i)
+            .collect();
+        indices.sort_by(|&a, &b| {
+            let ord = compare(&inner.events[a], &inner.events[b], &sort.key);
+            match sort.dir {
+                SortDir::Asc => ord,
+                SortDir::Desc => ord.reverse(),
+            }
+        });
+
+        *cache = Some(QueryCache {
+            key,
+            generation: inner.generation,
+            indices: indices.clone(),
+        });
+        Ok(indices)
+    }
+
+    pub fn page(
+        &self,
+        filter: &EventFilter,
+        sort: &SortSpec,
+        page: usize,
+        page_size: usize,
+    ) -> Result<EventPage, String> {
+        let indices = self.matching(filter, sort)?;
+        let page = page.max(1);
+        let page_size = page_size.max(1);
+        let inner = self.inner.read().unwrap();
+        let rows = indices
+            .iter()
+            .skip((page - 1) * page_size)
+            .take(page_size)
+            .filter_map(|&i| inner.events.get(i).map(|e| e.row.clone()))
+            .collect();
+        Ok(EventPage {
+            total: indices.len(),
+            page,
+            page_size,
+            rows,
+        })
+    }
+
+    pub fn count(&self, filter: &EventFilter) -> Result<usize, String> {
+        let compiled = filter.compile()?;
+        let inner = self.inner.read().unwrap();
+        Ok(inner.events.iter().filter(|e| compiled.matches(e)).count())
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct EventPage {
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    pub rows: Vec<EventRow>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LoadedFile {
+    pub path: String,
+    pub format: LogFormat,
+    pub compression: Compression,
+    pub events: usize,
+    pub rejects: Vec<RejectedLine>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct StoreLoadSummary {
+    pub total: usize,
+    pub files: Vec<LoadedFile>,
+}
+
+fn open_files(
+    store: &EventStore,
+    paths: &[String],
+    append: bool,
+    job: &Job,
+) -> Result<StoreLoadSummary, String> {
+    let loaded = paths
+        .iter()
+        .map(|path| load_path(Path::new(path), job))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(store.load(loaded, append))
+}
+
+#[tauri::command]
+pub async fn store_open_files(
+    app: AppHandle,
+    paths: Vec<String>,
+    append: bool,
+    job_id: Option<String>,
+) -> Result<StoreLoadSummary, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        open_files(&handle.state::<EventStore>(), &paths, append, job)
+    })
+    .await
+}
+
+/// Rows the webview parsed itself (the bundled sample, files picked in the browser).
+#[tauri::command]
+pub async fn store_add_rows(
+    app: AppHandle,
+    rows: Vec<EventRow>,
+    append: bool,
+) -> Result<usize, String> {
+    let handle = app.clone();
+    run_blocking(app, None, move |_| {
+        let store = handle.state::<EventStore>();
+        Ok(match append {
+            true => store.append(rows),
+            false => {
+                store.replace(rows);
+                store.len()
+            }
+        })
+    })
+    .await
+}
+
+#[tauri::command]
+pub fn store_clear(store: State<'_, EventStore>) {
+    store.clear();
+}
+
+#[tauri::command]
+pub fn results_clear(results: State<'_, ResultView>) {
+    results.0.clear();
+}
+
+#[tauri::command]
+pub async fn query_page(
+    app: AppHandle,
+    results: bool,
+    filter: EventFilter,
+    sort: SortSpec,
+    page: usize,
+    page_size: usize,
+) -> Result<EventPage, String> {
+    let handle = app.clone();
+    run_blocking(app, None, move |_| {
+        view(&handle, results).page(&filter, &sort, page, page_size)
+    })
+    .await
+}
+
+#[tauri::command]
+pub async fn count_events(
+    app: AppHandle,
+    results: bool,
+    filter: EventFilter,
+) -> Result<usize, String> {
+    let handle = app.clone();
+    run_blocking(app, None, move |_| view(&handle, results).count(&filter)).await
+}
@@ -0,0 +1,982 @@
+//! The query box language: `field:value` terms joined by `AND`, `OR`, `NOT` and
+//! parentheses; terms next to each other are ANDed. Fields are the panel's short keys
+//! (`action`, `category`, `user`, `status`, `error`, `before`, `after`, `source`) or
+//! dotted paths into the raw event, checked against `schema/audit_event.schema.json`.
+
+use super::StoredEvent;
+use crate::event::parse_timestamp;
+use crate::validation::AUDIT_EVENT_SCHEMA;
+use chrono::{DateTime, NaiveDate, Utc};
+use regex::{Regex, RegexBuilder};
+use serde_json::Value;
+use std::cmp::Ordering;
+use std::fmt;
+use std::sync::OnceLock;
+
+static SCHEMA: OnceLock<Value> = OnceLock::new();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
+    /// `:` or `=`
+    Match,
+    NotMatch,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Match => ":",
+            Op::NotMatch => "!=",
+            Op::Gt => ">",
+            Op::Ge => ">=",
+            Op::Lt => "<",
+            Op::Le => "<=",
+        }
+    }
+
+    fn accepts(self, ordering: Ordering) -> bool {
+        match self {
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+            Op::Match | Op::NotMatch => false,
+        }
+    }
+}
+
+/// The short keys of the original DSL, which keep its row-based semantics.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Alias {
+    Action,
+    Category,
+    User,
+    Status,
+    Error,
+    Before,
+    After,
+    Source,
+}
+
+impl Alias {
+    fn parse(name: &str) -> Option<Alias> {
+        Some(match name.to_lowercase().as_str() {
+            "action" => Alias::Action,
+            "category" => Alias::Category,
+            "user" => Alias::User,
+            "status" => Alias::Status,
+            "error" => Alias::Error,
+            "before" => Alias::Before,
+            "after" => Alias::After,
+            "source" => Alias::Source,
+            _ => return None,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Field {
+    Alias(Alias),
+    Path(Vec<String>),
+}
+
+impl Field {
+    /// A short key or a dotted path checked against the schema. Errors carry the byte
+    /// offset of the offending segment.
+    fn resolve(name: &str) -> Result<Field, (usize, String)> {
+        if let Some(alias) = Alias::parse(name) {
+            return Ok(Field::Alias(alias));
+        }
+        let segments: Vec<String> = name.split('.').map(str::to_string).collect();
+        check_path(&segments).map_err(|(i, message)| {
+            let offset: usize = segments[..i].iter().map(|s| s.len() + 1).sum();
+            (offset, message)
+        })?;
+        Ok(Field::Path(segments))
+    }
+
+    /// Field names given outside a query, such as group-by keys.
+    pub fn parse(name: &str) -> Result<Field, String> {
+        Field::resolve(name).map_err(|(offset, message)| error_at(name, offset, &message))
+    }
+
+    /// What the field holds for `event`; arrays on the path contribute every element.
+    /// Short keys read the row, so `user` falls back to the actor id as it does in the table.
+    pub fn values(&self, event: &StoredEvent) -> Vec<Value> {
+        let row = &event.row;
+        match self {
+            Field::Alias(Alias::Action) => vec![Value::from(row.action.as_str())],
+            Field::Alias(Alias::Category) => vec![Value::from(row.category.as_str())],
+            Field::Alias(Alias::User) => vec![Value::from(row.user.as_str())],
+            Field::Alias(Alias::Status) => vec![Value::from(row.status.as_str())],
+            Field::Alias(Alias::Source) => vec![Value::from(row.source.as_str())],
+            Field::Alias(Alias::Error) => vec![Value::from(event.error_occurred())],
+            Field::Alias(Alias::Before | Alias::After) => vec![Value::from(row.timestamp.as_str())],
+            Field::Path(path) => {
+                let mut out = Vec::new();
+                leaves(&row.raw, path, &mut out);
+                out.into_iter().cloned().collect()
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+enum Operand {
+    /// `glob` is set when the value has `*` or `?` wildcards.
+    Text {
+        lower: String,
+        glob: Option<Regex>,
+    },
+    Number(f64),
+    Time(DateTime<Utc>),
+}
+
+#[derive(Debug, Clone)]
+pub struct Term {
+    pub field: Field,
+    pub op: Op,
+    /// The value as written, without quotes.
+    pub value: String,
+    /// The term as written, for messages.
+    pub text: String,
+    operand: Operand,
+}
+
+#[derive(Debug, Clone)]
+pub enum Expr {
+    Term(Term),
+    Not(Box<Expr>),
+    And(Vec<Expr>),
+    Or(Vec<Expr>),
+}
+
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let join = |f: &mut fmt::Formatter<'_>, items: &[Expr], sep: &str| {
+            write!(f, "(")?;
+            for (i, item) in items.iter().enumerate() {
+                if i > 0 {
+                    write!(f, " {} ", sep)?;
+                }
+                write!(f, "{}", item)?;
+            }
+            write!(f, ")")
+        };
+        match self {
+            Expr::Term(term) => write!(f, "{}", term.text),
+            Expr::Not(inner) => write!(f, "NOT {}", inner),
+            Expr::And(items) => join(f, items, "AND"),
+            Expr::Or(items) => join(f, items, "OR"),
+        }
+    }
+}
+
+/// A parsed query. The empty query matches everything.
+#[derive(Debug, Clone, Default)]
+pub struct Query {
+    expr: Option<Expr>,
+}
+
+/// `message` followed by the query and a caret under byte offset `pos`.
+fn error_at(query: &str, pos: usize, message: &str) -> String {
+    let column = query[..pos.min(query.len())].chars().count();
+    format!(
+        "{} at column {}\n{}\n{}^",
+        message,
+        column + 1,
+        query,
+        " ".repeat(column)
+    )
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Utc>> {
+    parse_timestamp(value).or_else(|| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .ok()?
+            .and_hms_opt(0, 0, 0)
+            .map(|d| d.and_utc())
+    })
+}
+
+fn glob(value: &str) -> Option<Regex> {
+    if !value.contains(['*', '?']) {
+        return None;
+    }
+    let pattern: String = value
+        .chars()
+        .map(|c| match c {
+            '*' => ".*".to_string(),
+            '?' => ".".to_string(),
+            c => regex::escape(&c.to_string()),
+        })
+        .collect();
+    RegexBuilder::new(&format!("^{}$", pattern))
+        .case_insensitive(true)
+        .build()
+        .ok()
+}
+
+fn schema() -> &'static Value {
+    SCHEMA.get_or_init(|| serde_json::from_str(AUDIT_EVENT_SCHEMA).unwrap_or(Value::Null))
+}
+
+/// Objects that allow any key, such as `action.parameters`, `system` and `custom`.
+fn is_free_form(node: &Value) -> bool {
+    match node.get("additionalProperties") {
+        Some(Value::Bool(allowed)) => *allowed,
+        Some(Value::Object(_)) => true,
+        _ => node.get("properties").is_none() && node["type"] == "object",
+    }
+}
+
+/// Walks `segments` through the schema. On failure returns the index of the offending
+/// segment and the message.
+fn check_path(segments: &[String]) -> Result<(), (usize, String)> {
+    let mut node = schema();
+    for (i, segment) in segments.iter().enumerate() {
+        while let Some(items) = node.get("items") {
+            node = items;
+        }
+        if let Some(child) = node.get("properties").and_then(|props| props.get(segment)) {
+            node = child;
+            continue;
+        }
+        if is_free_form(node) {
+            return Ok(());
+        }
+        let parent = segments[..i].join(".");
+        let message = match node.get("properties").and_then(Value::as_object) {
+            Some(props) => {
+                let known: Vec<&str> = props.keys().map(String::as_str).collect();
+                let full = segments[..=i].join(".");
+                format!(
+                    "Unknown field `{}` (expected one of {})",
+                    full,
+                    known.join(", ")
+                )
+            }
+            None => format!("`{}` has no fields", parent),
+        };
+        return Err((i, message));
+    }
+    Ok(())
+}
+
+#[derive(Debug)]
+enum Token {
+    LParen,
+    RParen,
+    And,
+    Or,
+    Not,
+    Term(Term),
+}
+
+struct Lexer<'a> {
+    src: &'a str,
+    pos: usize,
+}
+
+impl<'a> Lexer<'a> {
+    fn peek(&self) -> Option<char> {
+        self.src[self.pos..].chars().next()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += c.len_utf8();
+        Some(c)
+    }
+
+    fn eat(&mut self, s: &str) -> bool {
+        if self.src[self.pos..].starts_with(s) {
+            self.pos += s.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, pos: usize, message: &str) -> String {
+        error_at(self.src, pos, message)
+    }
+
+    fn next(&mut self) -> Result<Option<(Token, usize)>, String> {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.bump();
+        }
+        let start = self.pos;
+        let Some(c) = self.peek() else {
+            return Ok(None);
+        };
+        let token = match c {
+            '(' => {
+                self.bump();
+                Token::LParen
+            }
+            ')' => {
+                self.bump();
+                Token::RParen
+            }
+            '!' => {
+                self.bump();
+                Token::Not
+            }
+            _ => self.word(start)?,
+        };
+        Ok(Some((token, start)))
+    }
+
+    fn word(&mut self, start: usize) -> Result<Token, String> {
+        while self
+            .peek()
+            .is_some_and(|c| c.is_alphanumeric() || matches!(c, '_' | '.' | '-'))
+        {
+            self.bump();
+        }
+        let name = &self.src[start..self.pos];
+        if name.is_empty() {
+            let c = self.peek().unwrap_or_default();
+            return Err(self.error(start, &format!("Unexpected `{}`", c)));
+        }
+        let op_start = self.pos;
+        let op = if self.eat("!=") {
+            Op::NotMatch
+        } else if self.eat(">=") {
+            Op::Ge
+        } else if self.eat("<=") {
+            Op::Le
+        } else if self.eat(":") || self.eat("=") {
+            Op::Match
+        } else if self.eat(">") {
+            Op::Gt
+        } else if self.eat("<") {
+            Op::Lt
+        } else {
+            return match name.to_uppercase().as_str() {
+                "AND" => Ok(Token::And),
+                "OR" => Ok(Token::Or),
+                "NOT" => Ok(Token::Not),
+                _ => Err(self.error(
+                    start,
+                    &format!("Expected a term like `field:value`, found `{}`", name),
+                )),
+            };
+        };
+        let value_start = self.pos;
+        let value = self.value()?;
+        if value.is_empty() {
+            return Err(self.error(
+                value_start,
+                &format!("Expected a value after `{}`", op.symbol()),
+            ));
+        }
+        let term = self.term(name, start, op, op_start, value, value_start)?;
+        Ok(Token::Term(term))
+    }
+
+    fn value(&mut self) -> Result<String, String> {
+        let quote = self.pos;
+        if !self.eat("\"") {
+            while self.peek().is_some_and(|c| !c.is_whitespace() && c != ')') {
+                self.bump();
+            }
+            return Ok(self.src[quote..self.pos].to_string());
+        }
+        let mut value = String::new();
+        loop {
+            match self.bump() {
+                Some('"') => return Ok(value),
+                Some('\\') => match self.bump() {
+                    Some(c) => value.push(c),
+                    None => break,
+                },
+                Some(c) => value.push(c),
+                None => break,
+            }
+        }
+        Err(self.error(quote, "Unterminated quote"))
+    }
+
+    fn term(
+        &self,
+        name: &str,
+        start: usize,
+        op: Op,
+        op_start: usize,
+        value: String,
+        value_start: usize,
+    ) -> Result<Term, String> {
+        let text = self.src[start..self.pos].to_string();
+        let field = Field::resolve(name)
+            .map_err(|(offset, message)| self.error(start + offset, &message))?;
+
+        let operand = match (&field, op) {
+            (Field::Alias(Alias::Before | Alias::After), Op::Match) => match parse_time(&value) {
+                Some(time) => Operand::Time(time),
+                None => return Err(self.error(value_start, "Expected a date or timestamp")),
+            },
+            (Field::Alias(Alias::Error), Op::Match | Op::NotMatch)
+                if value != "true" && value != "false" =>
+            {
+                return Err(self.error(value_start, "Expected `true` or `false`"));
+            }
+            (Field::Alias(alias), op)
+                if op != Op::Match && op != Op::NotMatch
+                    || matches!(alias, Alias::Before | Alias::After) =>
+            {
+                return Err(self.error(
+                    op_start,
+                    &format!(
+                        "`{}` does not support `{}`; use a field path such as \
+                         `timestamp` or `action.category` to compare",
+                        name,
+                        op.symbol()
+                    ),
+                ));
+            }
+            (_, Op::Match | Op::NotMatch) => Operand::Text {
+                lower: value.to_lowercase(),
+                glob: glob(&value),
+            },
+            _ => match (value.parse::<f64>(), parse_time(&value)) {
+                (Ok(number), _) => Operand::Number(number),
+                (_, Some(time)) => Operand::Time(time),
+                _ => {
+                    return Err(self.error(
+                        value_start,
+                        &format!("Expected a number or a date after `{}`", op.symbol()),
+                    ))
+                }
+            },
+        };
+        Ok(Term {
+            field,
+            op,
+            value,
+            text,
+            operand,
+        })
+    }
+}
+
+struct Parser<'a> {
+    src: &'a str,
+    tokens: Vec<(Token, usize)>,
+    next: usize,
+}
+
+impl Parser<'_> {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.next).map(|(token, _)| token)
+    }
+
+    fn take(&mut self) -> Option<(Token, usize)> {
+        let (token, pos) = self.tokens.get_mut(self.next)?;
+        let token = std::mem::replace(token, Token::RParen);
+        self.next += 1;
+        Some((token, *pos))
+    }
+
+    fn flatten(mut items: Vec<Expr>, wrap: fn(Vec<Expr>) -> Expr) -> Expr {
+        if items.len() == 1 {
+            items.remove(0)
+        } else {
+            wrap(items)
+        }
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut items = vec![self.and()?];
+        while matches!(self.peek(), Some(Token::Or)) {
+            self.take();
+            items.push(self.and()?);
+        }
+        Ok(Self::flatten(items, Expr::Or))
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut items = vec![self.not()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => {
+                    self.take();
+                }
+                Some(Token::Term(_) | Token::Not | Token::LParen) => {}
+                _ => break,
+            }
+            items.push(self.not()?);
+        }
+        Ok(Self::flatten(items, Expr::And))
+    }
+
+    fn not(&mut self) -> Result<Expr, String> {
+        if matches!(self.peek(), Some(Token::Not)) {
+            self.take();
+            return Ok(Expr::Not(Box::new(self.not()?)));
+        }
+        self.atom()
+    }
+
+    fn atom(&mut self) -> Result<Expr, String> {
+        match self.take() {
+            Some((Token::Term(term), _)) => Ok(Expr::Term(term)),
+            Some((Token::LParen, pos)) => {
+                let expr = self.or()?;
+                match self.take() {
+                    Some((Token::RParen, _)) => Ok(expr),
+                    _ => Err(error_at(self.src, pos, "Missing `)` for this `(`")),
+                }
+            }
+            Some((Token::RParen, pos)) => Err(error_at(self.src, pos, "Unexpected `)`")),
+            Some((Token::And | Token::Or, pos)) => Err(error_at(
+                self.src,
+                pos,
+                "Expected a term before this operator",
+            )),
+            Some((Token::Not, pos)) => Err(error_at(self.src, pos, "Expected a term")),
+            None => Err(error_at(
+                self.src,
+                self.src.trim_end().len(),
+                "Expected a term at the end of the query",
+            )),
+        }
+    }
+}
+
+/// Values at `path` below `value`, looking into every element of the arrays on the way.
+fn leaves<'v>(value: &'v Value, path: &[String], out: &mut Vec<&'v Value>) {
+    if let Value::Array(items) = value {
+        for item in items {
+            leaves(item, path, out);
+        }
+        return;
+    }
+    match path.split_first() {
+        None => out.push(value),
+        Some((head, rest)) => {
+            if let Some(child) = value.get(head) {
+                leaves(child, rest, out);
+            }
+        }
+    }
+}
+
+fn text_matches(lower: &str, glob: &Option<Regex>, value: &str) -> bool {
+    match glob {
+        Some(re) => re.is_match(value),
+        None => value.to_lowercase() == lower,
+    }
+}
+
+impl Term {
+    fn matches(&self, event: &StoredEvent) -> bool {
+        match &self.field {
+            Field::Alias(alias) => self.matches_alias(*alias, event),
+            Field::Path(path) => {
+                let mut values = Vec::new();
+                leaves(&event.row.raw, path, &mut values);
+                let any = values.iter().any(|value| self.matches_value(value));
+                if self.op == Op::NotMatch {
+                    !any
+                } else {
+                    any
+                }
+            }
+        }
+    }
+
+    /// Substring for `action`, `user` and `source`, equality for `category` and
+    /// `status`, as in the original DSL; wildcards match the whole value instead.
+    fn matches_alias(&self, alias: Alias, event: &StoredEvent) -> bool {
+        let row = &event.row;
+        let matched = match (&self.operand, alias) {
+            (Operand::Time(limit), Alias::Before) => event.ts.is_some_and(|ts| ts <= *limit),
+            (Operand::Time(limit), Alias::After) => event.ts.is_some_and(|ts| ts >= *limit),
+            (_, Alias::Error) => (self.value == "true") == event.error_occurred(),
+            (Operand::Text { lower, glob }, alias) => {
+                let target = match alias {
+                    Alias::Action => &row.action,
+                    Alias::Category => &row.category,
+                    Alias::User => &row.user,
+                    Alias::Status => &row.status,
+                    _ => &row.source,
+                };
+                match (glob, alias) {
+                    (None, Alias::Action | Alias::User | Alias::Source) => {
+                        target.to_lowercase().contains(lower)
+                    }
+                    _ => text_matches(lower, glob, target),
+                }
+            }
+            _ => false,
+        };
+        matched != (self.op == Op::NotMatch)
+    }
+
+    fn matches_value(&self, value: &Value) -> bool {
+        match &self.operand {
+            Operand::Text { lower, glob } => {
+                if self.value == "*" {
+                    return !value.is_null();
+                }
+                match value {
+                    Value::String(s) => text_matches(lower, glob, s),
+                    Value::Number(n) => match (n.as_f64(), self.value.parse::<f64>()) {
+                        (Some(a), Ok(b)) if glob.is_none() => a == b,
+                        _ => text_matches(lower, glob, &n.to_string()),
+                    },
+                    Value::Bool(b) => text_matches(lower, glob, &b.to_string()),
+                    Value::Null => lower == "null",
+                    _ => false,
+                }
+            }
+            Operand::Number(limit) => {
+                let number = match value {
+                    Value::Number(n) => n.as_f64(),
+                    Value::String(s) => s.trim().parse().ok(),
+                    _ => None,
+                };
+                number
+                    .and_then(|n| n.partial_cmp(limit))
+                    .is_some_and(|ordering| self.op.accepts(ordering))
+            }
+            Operand::Time(limit) => value
+                .as_str()
+                .and_then(parse_time)
+                .is_some_and(|time| self.op.accepts(time.cmp(limit))),
+        }
+    }
+}
+
+impl Query {
+    pub fn parse(query: &str) -> Result<Query, String> {
+        let mut lexer = Lexer { src: query, pos: 0 };
+        let mut tokens = Vec::new();
+        while let Some(token) = lexer.next()? {
+            tokens.push(token);
+        }
+        if tokens.is_empty() {
+            return Ok(Query::default());
+        }
+        let mut parser = Parser {
+            src: query,
+            tokens,
+            next: 0,
+        };
+        let expr = parser.or()?;
+        if let Some((_, pos)) = parser.tokens.get(parser.next) {
+            return Err(error_at(query, *pos, "Unexpected `)`"));
+        }
+        Ok(Query { expr: Some(expr) })
+    }
+
+    /// The parts that must all hold: the items of a top-level AND, or the whole query.
+    pub fn conjuncts(&self) -> Vec<&Expr> {
+        match &self.expr {
+            None => Vec::new(),
+            Some(Expr::And(items)) => items.iter().collect(),
+            Some(expr) => vec![expr],
+        }
+    }
+
+    pub fn matches(&self, event: &StoredEvent) -> bool {
+        self.expr.as_ref().is_none_or(|expr| expr.matches(event))
+    }
+}
+
+impl Expr {
+    fn matches(&self, event: &StoredEvent) -> bool {
+        match self {
+            Expr::Term(term) => term.matches(event),
+            Expr::Not(inner) => !inner.matches(event),
+            Expr::And(items) => items.iter().all(|item| item.matches(event)),
+            Expr::Or(items) => items.iter().any(|item| item.matches(event)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The message line of a parse error, without the caret.
+    fn error(query: &str) -> String {
+        let err = Query::parse(query).expect_err(query);
+        err.lines().next().unwrap().to_string()
+    }
+
+    fn event(raw: serde_json::Value) -> StoredEvent {
+        let parsed = crate::event::AuditEvent::from_value(&raw).unwrap();
+        StoredEvent::new(crate::event::EventRow::new(&parsed, raw, "audit.log"))
+    }
+
+    /// Whether `query` matches each of `events`, in order.
+    fn hits(query: &str, events: &[StoredEvent]) -> Vec<bool> {
+        let query = Query::parse(query).unwrap();
+        events.iter().map(|event| query.matches(event)).collect()
+    }
+
+    fn shape(query: &str) -> String {
+        let query = Query::parse(query).unwrap();
+        query.expr.map(|expr| expr.to_string()).unwrap_or_default()
+    }
+
+    #[test]
+    fn errors_point_at_the_offending_column() {
+        assert_eq!(
+            Query::parse("status:failure user:").unwrap_err(),
+            "Expected a value after `:` at column 21\nstatus:failure user:\n                    ^"
+        );
+        assert_eq!(
+            error("error.message:\"abc"),
+            "Unterminated quote at column 15"
+        );
+        assert_eq!(
+            error("bogus status:x"),
+            "Expected a term like `field:value`, found `bogus` at column 1"
+        );
+        assert_eq!(error("status:x & user:y"), "Unexpected `&` at column 10");
+        // Columns count characters, not bytes.
+        assert_eq!(error("user:\"zoë\" ; x"), "Unexpected `;` at column 12");
+    }
+
+    #[test]
+    fn unbalanced_and_dangling_operators_are_errors() {
+        assert_eq!(
+            error("(status:x user:y"),
+            "Missing `)` for this `(` at column 1"
+        );
+        assert_eq!(error("status:x)"), "Unexpected `)` at column 9");
+        assert_eq!(error("()"), "Unexpected `)` at column 2");
+        assert_eq!(
+            error("AND status:x"),
+            "Expected a term before this operator at column 1"
+        );
+        assert_eq!(
+            error("status:x OR  "),
+            "Expected a term at the end of the query at column 12"
+        );
+        assert_eq!(
+            error("status:x NOT"),
+            "Expected a term at the end of the query at column 13"
+        );
+    }
+
+    #[test]
+    fn fields_and_values_are_checked() {
+        let unknown = error("action.nope:x");
+        assert!(
+            unknown.starts_with("Unknown field `action.nope` (expected one of "),
+            "{}",
+            unknown
+        );
+        assert!(unknown.ends_with("at column 8"), "{}", unknown);
+        assert!(Query::parse("action.parameters.anything:x").is_ok());
+        assert!(Query::parse("custom.deep.path:x").is_ok());
+
+        assert_eq!(
+            error("before:yesterday"),
+            "Expected a date or timestamp at column 8"
+        );
+        assert_eq!(
+            error("error:maybe"),
+            "Expected `true` or `false` at column 7"
+        );
+        assert!(error("user>3").starts_with("`user` does not support `>`; use a field path"));
+        assert!(error("after!=2024-01-01").starts_with("`after` does not support `!=`"));
+        assert_eq!(
+            error("performance.duration_ms>=slow"),
+            "Expected a number or a date after `>=` at column 26"
+        );
+        assert!(Query::parse("timestamp>=2024-01-01 performance.duration_ms<500").is_ok());
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        assert_eq!(shape(""), "");
+        assert_eq!(shape("  "), "");
+        assert_eq!(
+            shape("user:a OR user:b status:x"),
+            "(user:a OR (user:b AND status:x))"
+        );
+        assert_eq!(
+            shape("(user:a or user:b) and not status:x"),
+            "((user:a OR user:b) AND NOT status:x)"
+        );
+        assert_eq!(shape("!!error:true"), "NOT NOT error:true");
+        assert_eq!(
+            shape("error.message:\"two words\" status:x"),
+            "(error.message:\"two words\" AND status:x)"
+        );
+
+        let query = Query::parse("user:a status:x OR user:b").unwrap();
+        assert_eq!(query.conjuncts().len(), 1);
+        let query = Query::parse("user:a (status:x OR user:b) category:auth").unwrap();
+        let conjuncts: Vec<String> = query.conjuncts().iter().map(|e| e.to_string()).collect();
+        assert_eq!(
+            conjuncts,
+            vec!["user:a", "(status:x OR user:b)", "category:auth"]
+        );
+    }
+
+    #[test]
+    fn numbers_and_dates_compare_by_value() {
+        let events = [
+            event(serde_json::json!({
+                "event_id": "e1",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "performance": {"duration_ms": 250},
+                "custom": {"retries": 3},
+            })),
+            event(serde_json::json!({
+                "event_id": "e2",
+                "timestamp": "2024-03-02T08:30:00+02:00",
+                "performance": {"duration_ms": 1500.5},
+                "custom": {"retries": "12"},
+            })),
+            event(serde_json::json!({"event_id": "e3", "timestamp": "2024-02-28T23:59:59Z"})),
+        ];
+        assert_eq!(
+            hits("performance.duration_ms>1000", &events),
+            [false, true, false]
+        );
+        assert_eq!(
+            hits("performance.duration_ms<=250", &events),
+            [true, false, false]
+        );
+        // Numeric, not lexical: "12" > 9 although "12" < "9".
+        assert_eq!(hits("custom.retries>9", &events), [false, true, false]);
+        assert_eq!(
+            hits("performance.duration_ms:250.0", &events),
+            [true, false, false]
+        );
+
+        assert_eq!(hits("timestamp>=2024-03-01", &events), [true, true, false]);
+        // Offsets are compared in UTC: e2 is 06:30Z.
+        assert_eq!(
+            hits("timestamp<2024-03-02T07:00:00Z", &events),
+            [true, true, true]
+        );
+        assert_eq!(
+            hits("after:2024-03-02T06:30:00Z", &events),
+            [false, true, false]
+        );
+        assert_eq!(hits("before:2024-03-01", &events), [false, false, true]);
+    }
+
+    #[test]
+    fn wildcards_match_the_whole_value() {
+        let events = [
+            event(serde_json::json!({
+                "event_id": "e1",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "actor": {"username": "alice"},
+                "action": {"category": "AUTH"},
+                "error": {"message": "Connection timeout"},
+            })),
+            event(serde_json::json!({
+                "event_id": "e2",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "actor": {"username": "alicia"},
+                "action": {"category": "AUTHZ"},
+            })),
+        ];
+        assert_eq!(hits("actor.username:ali*", &events), [true, true]);
+        assert_eq!(hits("actor.username:alic?", &events), [true, false]);
+        assert_eq!(hits("actor.username:ALI?IA", &events), [false, true]);
+        assert_eq!(hits("category:auth?", &events), [false, true]);
+        assert_eq!(hits("error.message:*timeout", &events), [true, false]);
+        // A lone `*` means the field is present.
+        assert_eq!(hits("error.message:*", &events), [true, false]);
+        assert_eq!(hits("error.message!=*", &events), [false, true]);
+    }
+
+    #[test]
+    fn array_paths_match_any_element() {
+        let events = [
+            event(serde_json::json!({
+                "event_id": "e1",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "actor": {"roles": ["reader", "Admin"]},
+            })),
+            event(serde_json::json!({
+                "event_id": "e2",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "actor": {"roles": ["reader"]},
+            })),
+            event(serde_json::json!({"event_id": "e3", "timestamp": "2024-03-01T10:00:00Z"})),
+        ];
+        assert_eq!(hits("actor.roles:admin", &events), [true, false, false]);
+        assert_eq!(hits("actor.roles:read*", &events), [true, true, false]);
+        // `!=` holds when no element matches, including when there are none.
+        assert_eq!(hits("actor.roles!=admin", &events), [false, true, true]);
+        assert_eq!(
+            hits("NOT actor.roles:reader", &events),
+            [false, false, true]
+        );
+    }
+
+    #[test]
+    fn not_equal_holds_for_a_missing_field() {
+        let events = [
+            event(serde_json::json!({
+                "event_id": "e1",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "action": {"operation": "login"},
+            })),
+            event(serde_json::json!({"event_id": "e2", "timestamp": "2024-03-01T10:00:00Z"})),
+        ];
+        assert_eq!(hits("action.operation!=login", &events), [false, true]);
+        assert_eq!(hits("action.operation:login", &events), [true, false]);
+        // Comparisons need a value to compare.
+        assert_eq!(hits("performance.duration_ms<10", &events), [false, false]);
+    }
+
+    #[test]
+    fn aliases_match_substrings_or_exact_values() {
+        let events = [
+            event(serde_json::json!({
+                "event_id": "e1",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "actor": {"username": "Alice.Smith"},
+                "action": {
+                    "type": "EXECUTE",
+                    "category": "AUTH",
+                    "operation": "user_login",
+                    "result": {"status": "FAILURE"},
+                },
+                "error": {"occurred": true},
+            })),
+            event(serde_json::json!({
+                "event_id": "e2",
+                "timestamp": "2024-03-01T10:00:00Z",
+                "actor": {"id": "svc-smithy"},
+                "action": {
+                    "type": "EXECUTE",
+                    "category": "AUTHZ",
+                    "operation": "user_logout",
+                    "result": {"status": "SUCCESS"},
+                },
+            })),
+        ];
+        // `user`, `action` and `source` match substrings, ignoring case.
+        assert_eq!(hits("user:smith", &events), [true, true]);
+        assert_eq!(hits("user:alice.", &events), [true, false]);
+        assert_eq!(hits("action:log", &events), [true, true]);
+        assert_eq!(hits("action:out", &events), [false, true]);
+        assert_eq!(hits("source:audit", &events), [true, true]);
+        assert_eq!(hits("user!=svc", &events), [true, false]);
+        // `category` and `status` match the whole value.
+        assert_eq!(hits("category:auth", &events), [true, false]);
+        assert_eq!(hits("status:fail", &events), [false, false]);
+        assert_eq!(hits("status!=failure", &events), [false, true]);
+        assert_eq!(hits("error:true", &events), [true, false]);
+        assert_eq!(hits("error:false", &events), [false, true]);
+    }
+}
@@ -0,0 +1,519 @@
+//! SQL console: the loaded events as an in-memory SQLite `audit_events` table laid out
+//! like the collector's (`vigil/storage/table_defs.py`), plus a `source` column.
+
+use super::{EventStore, StoredEvent};
+use crate::jobs::{run_blocking, Job, ProgressKind, CANCELLED};
+use chrono::Utc;
+use rusqlite::limits::Limit;
+use rusqlite::types::ValueRef;
+use rusqlite::{params, Batch, Connection, Row, Statement};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+
+const MAX_PAGE_SIZE: usize = 5000;
+
+/// SQLite VM steps between cancellation checks while a statement runs.
+const PROGRESS_OPS: i32 = 100_000;
+
+/// How SQLAlchemy stores a `DateTime` in SQLite, so statements written against the
+/// collector's database compare timestamps the same way here.
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S%.6f";
+
+/// Indexed columns filled from the event the way `SQLStorageBackend.store` does.
+pub const EVENT_COLUMNS: [(&str, &str); 9] = [
+    ("version", "/version"),
+    ("actor_type", "/actor/type"),
+    ("actor_username", "/actor/username"),
+    ("action_type", "/action/type"),
+    ("action_category", "/action/category"),
+    ("action_operation", "/action/operation"),
+    ("result_status", "/action/result/status"),
+    ("application", "/metadata/application"),
+    ("environment", "/metadata/environment"),
+];
+
+const SCHEMA: &str = "
+CREATE TABLE audit_events (
+    event_id TEXT,
+    timestamp TEXT,
+    version TEXT,
+    actor_type TEXT,
+    actor_username TEXT,
+    action_type TEXT,
+    action_category TEXT,
+    action_operation TEXT,
+    result_status TEXT,
+    application TEXT,
+    environment TEXT,
+    event_data TEXT NOT NULL,
+    created_at TEXT,
+    source TEXT
+);
+CREATE INDEX ix_audit_events_timestamp ON audit_events (timestamp);
+CREATE INDEX ix_audit_events_actor_username ON audit_events (actor_username);
+CREATE INDEX ix_audit_events_action_category ON audit_events (action_category);
+CREATE INDEX ix_audit_events_action_type ON audit_events (action_type);
+CREATE INDEX ix_audit_events_result_status ON audit_events (result_status);
+CREATE INDEX ix_audit_events_application ON audit_events (application);
+CREATE INDEX ix_audit_events_environment ON audit_events (environment);
+";
+
+struct Table {
+    conn: Connection,
+    /// Store generation the table was built from.
+    generation: u64,
+    rows: usize,
+}
+
+/// The table last built, kept until the event store changes.
+#[derive(Default)]
+pub struct SqlConsole {
+    table: Mutex<Option<Table>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SqlPage {
+    pub columns: Vec<String>,
+    pub rows: Vec<Vec<Value>>,
+    pub total: usize,
+    pub page: usize,
+    pub page_size: usize,
+    /// Events in the table the statement ran against.
+    pub table_rows: usize,
+    pub elapsed_ms: u64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SqlExport {
+    pub path: String,
+    pub rows: usize,
+}
+
+fn build<'e>(
+    events: impl Iterator<Item = &'e StoredEvent>,
+    generation: u64,
+    mut progress: impl FnMut(usize) -> Result<(), String>,
+) -> Result<Table, String> {
+    let mut conn = Connection::open_in_memory().map_err(|e| e.to_string())?;
+    // ATTACH passes as read-only but would create files, so allow no other databases.
+    conn.set_limit(Limit::SQLITE_LIMIT_ATTACHED, 0);
+    conn.execute_batch(SCHEMA).map_err(|e| e.to_string())?;
+    let created_at = Utc::now().format(TIMESTAMP_FORMAT).to_string();
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut rows = 0;
+    {
+        let mut insert = tx
+            .prepare(
+                "INSERT INTO audit_events VALUES \
+                 (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+            )
+            .map_err(|e| e.to_string())?;
+        for event in events {
+            if rows % 10_000 == 0 {
+                progress(rows)?;
+            }
+            let raw = &event.row.raw;
+            let column = |i: usize| raw.pointer(EVENT_COLUMNS[i].1).and_then(Value::as_str);
+            // Normalised to UTC so `ORDER BY timestamp` and SQLite's date functions work.
+            let timestamp = event
+                .ts
+                .map(|ts| ts.format(TIMESTAMP_FORMAT).to_string())
+                .unwrap_or_else(|| event.row.timestamp.clone());
+            insert
+                .execute(params![
+                    event.row.event_id,
+                    timestamp,
+                    column(0),
+                    column(1),
+                    column(2),
+                    column(3),
+                    column(4),
+                    column(5),
+                    column(6),
+                    column(7),
+                    column(8),
+                    raw.to_string(),
+                    created_at,
+                    event.row.source,
+                ])
+                .map_err(|e| e.to_string())?;
+            rows += 1;
+        }
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    progress(rows)?;
+    Ok(Table {
+        conn,
+        generation,
+        rows,
+    })
+}
+
+impl SqlConsole {
+    /// Runs `f` on the table, rebuilding it first if the store changed since. The store is
+    /// only locked while the table is built, so a slow statement never holds up its writers.
+    fn with_table<T>(
+        &self,
+        store: &EventStore,
+        job: &Job,
+        f: impl FnOnce(&Table) -> Result<T, String>,
+    ) -> Result<T, String> {
+        let mut table = self.table.lock().unwrap();
+        {
+            let inner = store.inner.read().unwrap();
+            if table.as_ref().map(|t| t.generation) != Some(inner.generation) {
+                *table = Some(build(inner.events.iter(), inner.generation, |parsed| {
+                    job.check()?;
+                    job.report(ProgressKind::Events { parsed });
+                    Ok(())
+                })?);
+            }
+        }
+        let table = table.as_ref().expect("table was just built");
+        let cancelled = job.clone();
+        interruptible(&table.conn, move || cancelled.is_cancelled(), || f(table))
+    }
+}
+
+/// Runs `f` with a progress handler that interrupts the running statement once `cancelled`
+/// returns true, so long sorts and joins stop without waiting for their next row.
+fn interruptible<T>(
+    conn: &Connection,
+    cancelled: impl Fn() -> bool + Clone + Send + 'static,
+    f: impl FnOnce() -> Result<T, String>,
+) -> Result<T, String> {
+    conn.progress_handler(PROGRESS_OPS, Some(cancelled.clone()));
+    let result = f();
+    conn.progress_handler(0, None::<fn() -> bool>);
+    match result {
+        Err(_) if cancelled() => Err(CANCELLED.to_string()),
+        result => result,
+    }
+}
+
+fn prepare<'c>(conn: &'c Connection, sql: &str) -> Result<Statement<'c>, String> {
+    let mut batch = Batch::new(conn, sql);
+    let statement = batch
+        .next()
+        .map_err(|e| e.to_string())?
+        .ok_or("Enter a SQL statement")?;
+    if batch.next().map_err(|e| e.to_string())?.is_some() {
+        return Err("Run one statement at a time".to_string());
+    }
+    // Keeps the table as loaded; ATTACH is ruled out by the connection's limits.
+    if !statement.readonly() {
+        return Err("Only read-only statements (SELECT, WITH, EXPLAIN) can be run".to_string());
+    }
+    Ok(statement)
+}
+
+fn to_json(value: ValueRef<'_>) -> Value {
+    match value {
+        ValueRef::Null => Value::Null,
+        ValueRef::Integer(i) => Value::from(i),
+        ValueRef::Real(f) => Value::from(f),
+        ValueRef::Text(text) => Value::from(String::from_utf8_lossy(text).into_owned()),
+        ValueRef::Blob(bytes) => Value::from(
+            bytes
+                .iter()
+                .map(|b| format!("{:02x}", b))
+                .collect::<String>(),
+        ),
+    }
+}
+
+fn values(row: &Row<'_>, width: usize) -> Result<Vec<Value>, String> {
+    (0..width)
+        .map(|i| row.get_ref(i).map(to_json).map_err(|e| e.to_string()))
+        .collect()
+}
+
+/// Steps through the whole result, handing each row to `each` with its position.
+fn for_each_row(
+    statement: &mut Statement<'_>,
+    mut check: impl FnMut() -> Result<(), String>,
+    mut each: impl FnMut(usize, &Row<'_>) -> Result<(), String>,
+) -> Result<usize, String> {
+    let mut rows = statement.query([]).map_err(|e| e.to_string())?;
+    let mut count = 0;
+    while let Some(row) = rows.next().map_err(|e| e.to_string())? {
+        if count % 10_000 == 0 {
+            check()?;
+        }
+        each(count, row)?;
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn columns(statement: &Statement<'_>) -> Vec<String> {
+    statement
+        .column_names()
+        .into_iter()
+        .map(str::to_string)
+        .collect()
+}
+
+fn run_page(
+    table: &Table,
+    sql: &str,
+    page: usize,
+    page_size: usize,
+    check: impl FnMut() -> Result<(), String>,
+) -> Result<SqlPage, String> {
+    let started = Instant::now();
+    let page = page.max(1);
+    let page_size = page_size.clamp(1, MAX_PAGE_SIZE);
+    let start = (page - 1).saturating_mul(page_size);
+    let mut statement = prepare(&table.conn, sql)?;
+    let width = statement.column_count();
+    let mut rows = Vec::new();
+    // Rows outside the page are only counted.
+    let total = for_each_row(&mut statement, check, |i, row| {
+        if i >= start && rows.len() < page_size {
+            rows.push(values(row, width)?);
+        }
+        Ok(())
+    })?;
+    Ok(SqlPage {
+        columns: columns(&statement),
+        rows,
+        total,
+        page,
+        page_size,
+        table_rows: table.rows,
+        elapsed_ms: started.elapsed().as_millis() as u64,
+    })
+}
+
+/// CSV for `.csv`, a JSON array for `.json`, JSON lines otherwise.
+fn export(
+    table: &Table,
+    sql: &str,
+    path: &str,
+    mut check: impl FnMut() -> Result<(), String>,
+) -> Result<SqlExport, String> {
+    let mut statement = prepare(&table.conn, sql)?;
+    let names = columns(&statement);
+    let width = names.len();
+    let extension = Path::new(path)
+        .extension()
+        .map(|ext| ext.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let file = File::create(path).map_err(|e| e.to_string())?;
+
+    let rows = if extension == "csv" {
+        let mut writer = csv::Writer::from_writer(file);
+        writer.write_record(&names).map_err(|e| e.to_string())?;
+        let rows = for_each_row(&mut statement, &mut check, |_, row| {
+            let cells = values(row, width)?.into_iter().map(|value| match value {
+                Value::Null => String::new(),
+                Value::String(s) => s,
+                other => other.to_string(),
+            });
+            writer.write_record(cells).map_err(|e| e.to_string())
+        })?;
+        writer.flush().map_err(|e| e.to_string())?;
+        rows
+    } else {
+        let array = extension == "json";
+        let mut writer = BufWriter::new(file);
+        let mut write = |s: &str| writer.write_all(s.as_bytes()).map_err(|e| e.to_string());
+        if array {
+            write("[")?;
+        }
+        let rows = for_each_row(&mut statement, &mut check, |i, row| {
+            let object: Map<String, Value> =
+                names.iter().cloned().zip(values(row, width)?).collect();
+            let separator = match (array, i) {
+                (true, 0) => "\n",
+                (true, _) => ",\n",
+                (false, _) => "",
+            };
+            write(separator)?;
+            write(&Value::Object(object).to_string())?;
+            if !array {
+                write("\n")?;
+            }
+            Ok(())
+        })?;
+        if array {
+            write("\n]\n")?;
+        }
+        writer.flush().map_err(|e| e.to_string())?;
+        rows
+    };
+    Ok(SqlExport {
+        path: path.to_string(),
+        rows,
+    })
+}
+
+/// Runs one read-only statement against `audit_events` and returns a page of its result.
+#[tauri::command]
+pub async fn run_sql(
+    app: AppHandle,
+    sql: String,
+    page: usize,
+    page_size: usize,
+    job_id: Option<String>,
+) -> Result<SqlPage, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        handle
+            .state::<SqlConsole>()
+            .with_table(&handle.state::<EventStore>(), job, |table| {
+                run_page(table, &sql, page, page_size, || job.check())
+            })
+    })
+    .await
+}
+
+/// Writes the statement's full result to `path`.
+#[tauri::command]
+pub async fn export_sql(
+    app: AppHandle,
+    sql: String,
+    path: String,
+    job_id: Option<String>,
+) -> Result<SqlExport, String> {
+    let handle = app.clone();
+    run_blocking(app, job_id, move |job| {
+        handle
+            .state::<SqlConsole>()
+            .with_table(&handle.state::<EventStore>(), job, |table| {
+                export(table, &sql, &path, || job.check())
+            })
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::event::{AuditEvent, EventRow};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Arc;
+
+    fn table(count: usize) -> Table {
+        let events: Vec<StoredEvent> = (1..=count)
+            .map(|i| {
+                let raw = json!({
+                    "event_id": format!("e{}", i),
+                    "timestamp": format!("2024-01-01T00:00:{:02}Z", i),
+                    "actor": {"type": "user", "username": "alice"},
+                    "action": {"type": "READ", "category": "AUTH", "result": {"status": "SUCCESS"}},
+                });
+                let event = AuditEvent::from_value(&raw).unwrap();
+                StoredEvent::new(EventRow::new(&event, raw, "test.log"))
+            })
+            .collect();
+        build(events.iter(), 1, |_| Ok(())).unwrap()
+    }
+
+    fn run(table: &Table, sql: &str) -> Result<SqlPage, String> {
+        run_page(table, sql, 1, 50, || Ok(()))
+    }
+
+    #[test]
+    fn only_read_only_statements_run() {
+        let table = table(3);
+        for sql in [
+            "DELETE FROM audit_events",
+            "UPDATE audit_events SET actor_username = 'mallory'",
+            "INSERT INTO audit_events (event_data) VALUES ('{}')",
+            "DROP TABLE audit_events",
+            "CREATE TABLE copy AS SELECT * FROM audit_events",
+        ] {
+            let err = run(&table, sql).unwrap_err();
+            assert!(err.contains("read-only"), "{}: {}", sql, err);
+        }
+        assert_eq!(
+            run(&table, "SELECT count(*) FROM audit_events")
+                .unwrap()
+                .rows,
+            vec![vec![json!(3)]]
+        );
+        assert_eq!(table.rows, 3);
+    }
+
+    #[test]
+    fn attach_and_multiple_statements_are_refused() {
+        let table = table(1);
+        let path = std::env::temp_dir().join(format!("sql-attach-{}.db", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+        let attach = format!("ATTACH DATABASE '{}' AS other", path.display());
+        assert!(run(&table, &attach).is_err());
+        assert!(!path.exists());
+
+        let err = run(&table, "SELECT 1; DELETE FROM audit_events").unwrap_err();
+        assert!(err.contains("one statement"), "{}", err);
+        let err = run(&table, "SELECT 1; SELECT 2").unwrap_err();
+        assert!(err.contains("one statement"), "{}", err);
+        assert!(run(&table, "  ")
+            .unwrap_err()
+            .contains("Enter a SQL statement"));
+        // A trailing semicolon is still one statement.
+        assert_eq!(run(&table, "SELECT 1;").unwrap().total, 1);
+    }
+
+    #[test]
+    fn pages_count_the_whole_result() {
+        let table = table(5);
+        let sql = "SELECT event_id, actor_username FROM audit_events ORDER BY event_id";
+        let page = run_page(&table, sql, 2, 2, || Ok(())).unwrap();
+        assert_eq!(page.columns, vec!["event_id", "actor_username"]);
+        assert_eq!(
+            page.rows,
+            vec![
+                vec![json!("e3"), json!("alice")],
+                vec![json!("e4"), json!("alice")]
+            ]
+        );
+        assert_eq!(
+            (page.total, page.page, page.page_size, page.table_rows),
+            (5, 2, 2, 5)
+        );
+
+        let last = run_page(&table, sql, 3, 2, || Ok(())).unwrap();
+        assert_eq!(last.rows, vec![vec![json!("e5"), json!("alice")]]);
+        let past = run_page(&table, sql, 9, 2, || Ok(())).unwrap();
+        assert!(past.rows.is_empty());
+        assert_eq!(past.total, 5);
+        // Page 0 and an empty page size fall back to the first row.
+        let first = run_page(&table, sql, 0, 0, || Ok(())).unwrap();
+        assert_eq!((first.page, first.page_size, first.rows.len()), (1, 1, 1));
+    }
+
+    #[test]
+    fn cancelling_interrupts_a_running_statement() {
+        let table = table(1);
+        let endless = "WITH RECURSIVE n(i) AS (SELECT 1 UNION ALL SELECT i + 1 FROM n) \
+                       SELECT max(i) FROM n";
+        let cancelled = Arc::new(AtomicBool::new(true));
+        let flag = cancelled.clone();
+        let err = interruptible(
+            &table.conn,
+            move || flag.load(Ordering::Relaxed),
+            || run(&table, endless),
+        )
+        .unwrap_err();
+        assert_eq!(err, CANCELLED);
+
+        // The handler is removed again, so later statements run to the end.
+        cancelled.store(false, Ordering::Relaxed);
+        assert_eq!(
+            run(&table, "SELECT count(*) FROM audit_events")
+                .unwrap()
+                .total,
+            1
+        );
+    }
+}
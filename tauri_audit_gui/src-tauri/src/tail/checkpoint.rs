@@ -0,0 +1,265 @@
+//! Tail positions persisted in the app data directory, so following a file again after
+//! a restart continues at the last delivered line instead of the start. A busy tail
+//! saves after every chunk, so writes are coalesced: the file is rewritten at most every
+//! `WRITE_INTERVAL`, and whatever is left is flushed when a tail stops or the app exits.
+
+use super::TailCursor;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+const CHECKPOINT_FILE: &str = "tail-checkpoints.json";
+const WRITE_INTERVAL: Duration = Duration::from_secs(2);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Checkpoint {
+    pub path: String,
+    pub cursor: TailCursor,
+    pub last_event_id: Option<String>,
+    /// The `{date}` of the file, for session streams that roll over daily.
+    #[serde(default)]
+    pub date: Option<String>,
+    pub saved_at: String,
+}
+
+/// Reported when a follow starts from a checkpoint rather than a cursor from the UI.
+#[derive(Debug, Clone, Serialize)]
+pub struct Resumed {
+    pub saved_at: String,
+    pub last_event_id: Option<String>,
+    /// Lines written after the checkpoint, i.e. while nothing was following the file.
+    /// Blank and unparsable lines are counted too.
+    pub missed_lines: usize,
+}
+
+impl Checkpoint {
+    pub fn resumed(&self, missed_lines: usize) -> Resumed {
+        Resumed {
+            saved_at: self.saved_at.clone(),
+            last_event_id: self.last_event_id.clone(),
+            missed_lines,
+        }
+    }
+}
+
+#[derive(Default)]
+struct Entries {
+    /// `None` until read from disk.
+    checkpoints: Option<BTreeMap<String, Checkpoint>>,
+    /// Saved since the file was last written.
+    dirty: bool,
+    written_at: Option<Instant>,
+}
+
+/// Checkpoints keyed by file path, or by directory and stream label for session streams.
+/// Read from disk on first use.
+#[derive(Default)]
+pub struct TailCheckpoints {
+    entries: Mutex<Entries>,
+}
+
+fn checkpoint_file(app: &AppHandle) -> Result<PathBuf, String> {
+    Ok(app
+        .path()
+        .app_data_dir()
+        .map_err(|e| e.to_string())?
+        .join(CHECKPOINT_FILE))
+}
+
+// A missing or unreadable file only means every tail starts fresh.
+fn read(path: &Path) -> BTreeMap<String, Checkpoint> {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+impl TailCheckpoints {
+    fn entries(&self, path: &Path) -> MutexGuard<'_, Entries> {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.checkpoints.is_none() {
+            entries.checkpoints = Some(read(path));
+        }
+        entries
+    }
+
+    pub fn get(&self, app: &AppHandle, key: &str) -> Option<Checkpoint> {
+        self.get_at(&checkpoint_file(app).ok()?, key)
+    }
+
+    /// Records `updates`. The file is rewritten unless it was written less than
+    /// `WRITE_INTERVAL` ago, in which case the next save or `flush` writes them.
+    pub fn save(&self, app: &AppHandle, updates: Vec<(String, Checkpoint)>) -> Result<(), String> {
+        if updates.is_empty() {
+            return Ok(());
+        }
+        self.save_at(&checkpoint_file(app)?, updates)
+    }
+
+    /// Writes checkpoints still held back by `save`.
+    pub fn flush(&self, app: &AppHandle) -> Result<(), String> {
+        self.flush_at(&checkpoint_file(app)?)
+    }
+
+    fn get_at(&self, path: &Path, key: &str) -> Option<Checkpoint> {
+        self.entries(path).checkpoints.as_ref()?.get(key).cloned()
+    }
+
+    fn save_at(&self, path: &Path, updates: Vec<(String, Checkpoint)>) -> Result<(), String> {
+        let mut entries = self.entries(path);
+        entries
+            .checkpoints
+            .get_or_insert_with(BTreeMap::new)
+            .extend(updates);
+        entries.dirty = true;
+        if entries
+            .written_at
+            .is_some_and(|at| at.elapsed() < WRITE_INTERVAL)
+        {
+            return Ok(());
+        }
+        write(path, &mut entries)
+    }
+
+    fn flush_at(&self, path: &Path) -> Result<(), String> {
+        let mut entries = self.entries.lock().unwrap();
+        match entries.dirty {
+            true => write(path, &mut entries),
+            false => Ok(()),
+        }
+    }
+}
+
+fn write(path: &Path, entries: &mut Entries) -> Result<(), String> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    let checkpoints = entries.checkpoints.get_or_insert_with(BTreeMap::new);
+    let json = serde_json::to_vec(checkpoints).map_err(|e| e.to_string())?;
+    let tmp = path.with_extension("tmp");
+    fs::write(&tmp, json).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, path).map_err(|e| e.to_string())?;
+    entries.dirty = false;
+    entries.written_at = Some(Instant::now());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::jobs::Job;
+    use crate::tail::watcher::FollowedFile;
+    use crate::tail::{pending_lines, TailTransition};
+
+    fn temp(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("checkpoint-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn line(id: &str) -> String {
+        format!(
+            "{{\"event_id\":\"{}\",\"timestamp\":\"2026-01-01T00:00:00Z\"}}\n",
+            id
+        )
+    }
+
+    /// Reads `file` to its end, returning the event ids and transitions delivered.
+    fn follow_to_end(file: &mut FollowedFile) -> (Vec<String>, Vec<Option<TailTransition>>) {
+        let job = Job::detached();
+        let mut ids = Vec::new();
+        let mut transitions = Vec::new();
+        while file.read_next(&job, 1 << 20, "audit", None, |_, chunk| {
+            ids.extend(chunk.rows.iter().map(|row| row.event_id.clone()));
+            transitions.push(chunk.transition);
+        }) {}
+        (ids, transitions)
+    }
+
+    #[test]
+    fn saves_are_coalesced_and_flushed() {
+        let dir = temp("save");
+        let store = dir.join(CHECKPOINT_FILE);
+        let log = dir.join("audit.log");
+        fs::write(&log, line("e1") + &line("e2")).unwrap();
+        let mut file = FollowedFile::new(log.clone(), TailCursor::default());
+        follow_to_end(&mut file);
+        let first = file.checkpoint().unwrap();
+        assert!(
+            file.checkpoint().is_none(),
+            "an idle file is not saved again"
+        );
+
+        let checkpoints = TailCheckpoints::default();
+        checkpoints
+            .save_at(&store, vec![("a".to_string(), first.clone())])
+            .unwrap();
+        let written = fs::read_to_string(&store).unwrap();
+        assert!(!written.contains('\n'), "{}", written);
+
+        // Within the interval the update stays in memory until flushed.
+        let mut second = first.clone();
+        second.last_event_id = Some("e9".to_string());
+        checkpoints
+            .save_at(&store, vec![("b".to_string(), second)])
+            .unwrap();
+        assert_eq!(fs::read_to_string(&store).unwrap(), written);
+        assert_eq!(
+            checkpoints
+                .get_at(&store, "b")
+                .unwrap()
+                .last_event_id
+                .as_deref(),
+            Some("e9")
+        );
+        checkpoints.flush_at(&store).unwrap();
+
+        let reloaded = TailCheckpoints::default();
+        let a = reloaded.get_at(&store, "a").unwrap();
+        assert_eq!(a.cursor, first.cursor);
+        assert_eq!(a.last_event_id.as_deref(), Some("e2"));
+        assert_eq!(a.path, log.to_string_lossy());
+        assert!(reloaded.get_at(&store, "b").is_some());
+        assert!(reloaded.get_at(&store, "c").is_none());
+    }
+
+    #[test]
+    fn resuming_after_rotation_starts_on_the_new_file() {
+        let dir = temp("rotate");
+        let store = dir.join(CHECKPOINT_FILE);
+        let log = dir.join("audit.log");
+        fs::write(&log, line("old1") + &line("old2")).unwrap();
+        let mut file = FollowedFile::new(log.clone(), TailCursor::default());
+        follow_to_end(&mut file);
+        let checkpoints = TailCheckpoints::default();
+        let key = log.to_string_lossy().into_owned();
+        checkpoints
+            .save_at(&store, vec![(key.clone(), file.checkpoint().unwrap())])
+            .unwrap();
+
+        // Appended before the rotation, then a new file that is already longer.
+        let mut appended = fs::read_to_string(&log).unwrap();
+        appended.push_str(&line("old3"));
+        fs::write(&log, appended).unwrap();
+        fs::rename(&log, dir.join("audit.log.1")).unwrap();
+        fs::write(
+            &log,
+            line("new1") + &line("new2") + &line("new3") + &line("new4"),
+        )
+        .unwrap();
+
+        let checkpoint = TailCheckpoints::default().get_at(&store, &key).unwrap();
+        let missed_lines = pending_lines(&log, &checkpoint.cursor).unwrap();
+        assert_eq!(checkpoint.resumed(missed_lines).missed_lines, 4);
+        let mut file = FollowedFile::from_checkpoint(log.clone(), checkpoint);
+        let (ids, transitions) = follow_to_end(&mut file);
+        assert_eq!(ids, ["new1", "new2", "new3", "new4"]);
+        assert_eq!(transitions[0], Some(TailTransition::Replaced));
+        assert_eq!(file.cursor.line, 4);
+    }
+}
@@ -0,0 +1,128 @@
+//! Identity of a followed file, used to notice rotation and truncation between reads.
+
+use serde::{Deserialize, Serialize};
+use std::fs::{File, Metadata};
+use std::io::{self, Read, Seek, SeekFrom};
+
+/// How much of the head is hashed. Long enough to cover the first event of every format.
+const FINGERPRINT_BYTES: u64 = 1024;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct FileIdentity {
+    /// `dev:ino` on Unix, empty where the platform gives no stable file id. Kept as a
+    /// string so 64-bit inode numbers survive the round trip through JavaScript.
+    pub file_id: String,
+    pub fingerprint: String,
+    pub fingerprint_len: u64,
+}
+
+#[cfg(unix)]
+pub fn file_id(metadata: &Metadata) -> String {
+    use std::os::unix::fs::MetadataExt;
+    format!("{}:{}", metadata.dev(), metadata.ino())
+}
+
+#[cfg(not(unix))]
+pub fn file_id(_metadata: &Metadata) -> String {
+    String::new()
+}
+
+/// FNV-1a over the first `len` bytes; stable across builds so checkpoints can be persisted.
+fn fingerprint(file: &mut File, len: u64) -> io::Result<String> {
+    file.seek(SeekFrom::Start(0))?;
+    let mut head = Vec::with_capacity(len as usize);
+    file.take(len).read_to_end(&mut head)?;
+    let hash = head.iter().fold(0xcbf2_9ce4_8422_2325u64, |hash, &b| {
+        (hash ^ b as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    });
+    Ok(format!("{:016x}", hash))
+}
+
+impl FileIdentity {
+    pub fn probe(file: &mut File, metadata: &Metadata) -> io::Result<FileIdentity> {
+        FileIdentity::probe_prefix(file, metadata, u64::MAX)
+    }
+
+    /// Like `probe`, but hashes no more than the first `limit` bytes, so whatever is
+    /// written after them cannot change the identity.
+    pub fn probe_prefix(
+        file: &mut File,
+        metadata: &Metadata,
+        limit: u64,
+    ) -> io::Result<FileIdentity> {
+        let fingerprint_len = metadata.len().min(FINGERPRINT_BYTES).min(limit);
+        Ok(FileIdentity {
+            file_id: file_id(metadata),
+            fingerprint: fingerprint(file, fingerprint_len)?,
+            fingerprint_len,
+        })
+    }
+
+    /// True when the head that was fingerprinted is still byte-for-byte the same.
+    pub fn same_head(&self, file: &mut File, size: u64) -> io::Result<bool> {
+        if size < self.fingerprint_len {
+            return Ok(false);
+        }
+        Ok(fingerprint(file, self.fingerprint_len)? == self.fingerprint)
+    }
+
+    /// A fingerprint taken while the file was still short can be widened as it grows.
+    pub fn is_partial(&self, size: u64) -> bool {
+        self.fingerprint_len < FINGERPRINT_BYTES && size > self.fingerprint_len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+
+    fn probe(file: &mut File, limit: u64) -> FileIdentity {
+        let metadata = file.metadata().unwrap();
+        FileIdentity::probe_prefix(file, &metadata, limit).unwrap()
+    }
+
+    #[test]
+    fn fingerprint_covers_only_the_head() {
+        let path = std::env::temp_dir().join(format!("identity-head-{}.log", std::process::id()));
+        std::fs::write(&path, "first line\nsecond\n").unwrap();
+        let mut file = File::open(&path).unwrap();
+        let identity = probe(&mut file, u64::MAX);
+        assert_eq!(identity.fingerprint_len, 18);
+        assert!(identity.is_partial(19));
+        assert!(!identity.is_partial(18));
+
+        let prefix = probe(&mut file, 11);
+        assert_eq!(prefix.fingerprint_len, 11);
+        assert_eq!(prefix.file_id, identity.file_id);
+
+        // Appending leaves both heads intact; rewriting past the prefix only breaks the wider one.
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"third\n")
+            .unwrap();
+        assert!(identity.same_head(&mut file, 24).unwrap());
+        std::fs::write(&path, "first line\nSECOND\nthird\n").unwrap();
+        assert!(prefix.same_head(&mut file, 24).unwrap());
+        assert!(!identity.same_head(&mut file, 24).unwrap());
+        // Too short to hold the fingerprinted head at all.
+        assert!(!identity.same_head(&mut file, 10).unwrap());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn the_fingerprint_stops_at_its_cap() {
+        let path = std::env::temp_dir().join(format!("identity-cap-{}.log", std::process::id()));
+        std::fs::write(&path, vec![b'x'; 4096]).unwrap();
+        let mut file = File::open(&path).unwrap();
+        let identity = probe(&mut file, u64::MAX);
+        assert_eq!(identity.fingerprint_len, FINGERPRINT_BYTES);
+        assert!(!identity.is_partial(8192));
+        // Checkpoints persist it, so it is plain FNV-1a of the first 1024 bytes.
+        assert_eq!(identity.fingerprint, "51bfc41078b37325");
+        std::fs::remove_file(&path).unwrap();
+    }
+}
@@ -0,0 +1,306 @@
+//! Live tail of a growing log that survives rotation, replacement and truncation.
+
+pub mod checkpoint;
+mod identity;
+pub mod session;
+pub mod watcher;
+
+pub use identity::FileIdentity;
+
+use crate::event::{source_name, EventRow};
+use crate::jobs::{run_blocking, Job, ProgressReader};
+use crate::loader::{parse_line_bytes, RejectedLine};
+use serde::{Deserialize, Serialize};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Seek, SeekFrom};
+use std::path::Path;
+use tauri::AppHandle;
+
+/// Default cap on how much of a backlog one call consumes.
+pub const DEFAULT_CHUNK_BYTES: u64 = 4 << 20;
+
+/// Where the next read starts and which file that offset belongs to. `offset` always
+/// sits on a line boundary; a half-flushed last line is simply read again next time.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TailCursor {
+    pub offset: u64,
+    pub line: usize,
+    pub identity: Option<FileIdentity>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum TailTransition {
+    /// The path now points at a different file, e.g. after logrotate or a daily rollover.
+    Replaced,
+    /// Same file, but shorter than the saved offset or rewritten from the start.
+    Truncated { previous_offset: u64, size: u64 },
+    /// Nothing at the path right now; the cursor is kept until a file shows up again.
+    Missing,
+    /// A tail session moved on to the next `{date}` file after finishing `from`.
+    RolledOver { from: String },
+    /// A remote tail could not reach the collector; it keeps polling.
+    Unreachable { error: String },
+    /// Reading the file failed, e.g. on permissions; it keeps following.
+    Unreadable { error: String },
+}
+
+#[derive(Debug, Serialize)]
+pub struct TailChunk {
+    pub rows: Vec<EventRow>,
+    /// Line number and byte offset of each row, in step with `rows`.
+    #[serde(skip)]
+    pub positions: Vec<(usize, u64)>,
+    pub rejects: Vec<RejectedLine>,
+    pub cursor: TailCursor,
+    pub transition: Option<TailTransition>,
+    /// More complete lines are waiting beyond the byte budget.
+    pub has_more: bool,
+}
+
+/// Checks the open file against the cursor and returns where reading should resume,
+/// restarting from zero when the file was swapped or cut.
+pub fn resume(
+    file: &mut File,
+    cursor: &TailCursor,
+) -> Result<(TailCursor, Option<TailTransition>), String> {
+    let metadata = file.metadata().map_err(|e| e.to_string())?;
+    let size = metadata.len();
+    let truncated = TailTransition::Truncated {
+        previous_offset: cursor.offset,
+        size,
+    };
+
+    let transition = match &cursor.identity {
+        Some(previous) if previous.file_id != identity::file_id(&metadata) => {
+            Some(TailTransition::Replaced)
+        }
+        _ if size < cursor.offset => Some(truncated),
+        Some(previous) if !previous.same_head(file, size).map_err(|e| e.to_string())? => {
+            Some(truncated)
+        }
+        _ => None,
+    };
+
+    let identity = match &cursor.identity {
+        Some(previous) if transition.is_none() && !previous.is_partial(size) => previous.clone(),
+        _ => FileIdentity::probe(file, &metadata).map_err(|e| e.to_string())?,
+    };
+    let (offset, line) = match transition {
+        Some(_) => (0, 0),
+        None => (cursor.offset, cursor.line),
+    };
+    Ok((
+        TailCursor {
+            offset,
+            line,
+            identity: Some(identity),
+        },
+        transition,
+    ))
+}
+
+/// Counts the complete lines after the cursor, i.e. what the next reads will deliver,
+/// from zero if the file was replaced or cut in the meantime.
+pub fn pending_lines(path: &Path, cursor: &TailCursor) -> Result<usize, String> {
+    let mut file = File::open(path).map_err(|e| e.to_string())?;
+    let (cursor, _) = resume(&mut file, cursor)?;
+    file.seek(SeekFrom::Start(cursor.offset))
+        .map_err(|e| e.to_string())?;
+    let mut reader = BufReader::new(file);
+    let mut lines = 0;
+    loop {
+        let buf = reader.fill_buf().map_err(|e| e.to_string())?;
+        if buf.is_empty() {
+            return Ok(lines);
+        }
+        lines += buf.iter().filter(|&&b| b == b'\n').count();
+        let len = buf.len();
+        reader.consume(len);
+    }
+}
+
+/// Reads whole lines from the cursor until EOF or until `budget` bytes are consumed. A
+/// single line longer than the budget is still returned in one piece.
+pub fn read_chunk(
+    path: &Path,
+    cursor: &TailCursor,
+    budget: u64,
+    job: &Job,
+) -> Result<TailChunk, String> {
+    let mut chunk = TailChunk {
+        rows: Vec::new(),
+        positions: Vec::new(),
+        rejects: Vec::new(),
+        cursor: cursor.clone(),
+        transition: None,
+        has_more: false,
+    };
+    let mut file = match File::open(path) {
+        Ok(file) => file,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            chunk.transition = Some(TailTransition::Missing);
+            return Ok(chunk);
+        }
+        Err(e) => return Err(e.to_string()),
+    };
+    let (cursor, transition) = resume(&mut file, cursor)?;
+    chunk.cursor = cursor;
+    chunk.transition = transition;
+
+    let file_len = file.metadata().map_err(|e| e.to_string())?.len();
+    if chunk.cursor.offset >= file_len {
+        return Ok(chunk);
+    }
+    let budget = budget.max(1);
+    file.seek(SeekFrom::Start(chunk.cursor.offset))
+        .map_err(|e| e.to_string())?;
+    let expected = (file_len - chunk.cursor.offset).min(budget);
+    let mut reader = BufReader::new(ProgressReader::new(file, job, expected));
+    let source = source_name(path);
+    let mut consumed = 0u64;
+    let mut buf = Vec::new();
+
+    while consumed < budget {
+        buf.clear();
+        let read = reader
+            .read_until(b'\n', &mut buf)
+            .map_err(|e| e.to_string())?;
+        if read == 0 || buf.last() != Some(&b'\n') {
+            break;
+        }
+        let start = chunk.cursor.offset + consumed;
+        consumed += read as u64;
+        chunk.cursor.line += 1;
+        match parse_line_bytes(&buf, chunk.cursor.line, start, &source) {
+            Some(Ok(row)) => {
+                chunk.rows.push(row);
+                chunk.positions.push((chunk.cursor.line, start));
+            }
+            Some(Err(reject)) => chunk.rejects.push(reject),
+            None => {}
+        }
+    }
+    chunk.cursor.offset += consumed;
+    chunk.has_more = consumed >= budget && chunk.cursor.offset < file_len;
+    Ok(chunk)
+}
+
+#[tauri::command]
+pub async fn read_tail_chunk(
+    app: AppHandle,
+    path: String,
+    cursor: TailCursor,
+    max_bytes: Option<u64>,
+    job_id: Option<String>,
+) -> Result<TailChunk, String> {
+    let budget = max_bytes.unwrap_or(DEFAULT_CHUNK_BYTES);
+    run_blocking(app, job_id, move |job| {
+        read_chunk(Path::new(&path), &cursor, budget, job)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::OpenOptions;
+    use std::io::Write;
+    use std::path::PathBuf;
+
+    fn log(name: &str, body: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("{}-{}.log", name, std::process::id()));
+        std::fs::write(&path, body).unwrap();
+        path
+    }
+
+    /// The cursor after reading `lines` lines of `offset` bytes from `path`.
+    fn cursor_at(path: &Path, offset: u64, line: usize) -> TailCursor {
+        let (cursor, transition) =
+            resume(&mut File::open(path).unwrap(), &TailCursor::default()).unwrap();
+        assert_eq!(transition, None);
+        TailCursor {
+            offset,
+            line,
+            ..cursor
+        }
+    }
+
+    #[test]
+    fn appends_keep_the_offset() {
+        let path = log("tail-append", "one\ntwo\n");
+        let cursor = cursor_at(&path, 8, 2);
+        OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"three\n")
+            .unwrap();
+        let (next, transition) = resume(&mut File::open(&path).unwrap(), &cursor).unwrap();
+        assert_eq!(transition, None);
+        assert_eq!((next.offset, next.line), (8, 2));
+        // The head was hashed while the file was short, so it widens as the file grows.
+        assert_eq!(next.identity.unwrap().fingerprint_len, 14);
+        assert_eq!(pending_lines(&path, &cursor).unwrap(), 1);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_shorter_file_restarts_from_zero() {
+        let path = log("tail-truncate", "one\ntwo\n");
+        let cursor = cursor_at(&path, 8, 2);
+        std::fs::write(&path, "1\n").unwrap();
+        let (next, transition) = resume(&mut File::open(&path).unwrap(), &cursor).unwrap();
+        assert_eq!(
+            transition,
+            Some(TailTransition::Truncated {
+                previous_offset: 8,
+                size: 2
+            })
+        );
+        assert_eq!((next.offset, next.line), (0, 0));
+        assert_eq!(next.identity.unwrap().fingerprint_len, 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn a_rewritten_head_restarts_from_zero() {
+        // Truncated and written past the old offset before the next read.
+        let path = log("tail-rewrite", "one\ntwo\n");
+        let cursor = cursor_at(&path, 8, 2);
+        std::fs::write(&path, "uno\ndos\ntres\n").unwrap();
+        let (next, transition) = resume(&mut File::open(&path).unwrap(), &cursor).unwrap();
+        assert_eq!(
+            transition,
+            Some(TailTransition::Truncated {
+                previous_offset: 8,
+                size: 13
+            })
+        );
+        assert_eq!((next.offset, next.line), (0, 0));
+        assert_eq!(pending_lines(&path, &cursor).unwrap(), 3);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_new_file_at_the_path_is_a_replacement() {
+        let path = log("tail-rotate", "one\ntwo\n");
+        let cursor = cursor_at(&path, 8, 2);
+        // logrotate's create mode: the old file moves away and a new one takes its name.
+        let rotated = path.with_extension("log.1");
+        std::fs::rename(&path, &rotated).unwrap();
+        std::fs::write(&path, "one\ntwo\nthree\n").unwrap();
+        let (next, transition) = resume(&mut File::open(&path).unwrap(), &cursor).unwrap();
+        assert_eq!(transition, Some(TailTransition::Replaced));
+        assert_eq!((next.offset, next.line), (0, 0));
+        assert_ne!(next.identity, cursor.identity);
+
+        // The rotated file is still the one the cursor belongs to.
+        let (kept, transition) = resume(&mut File::open(&rotated).unwrap(), &cursor).unwrap();
+        assert_eq!((kept.offset, transition), (8, None));
+        std::fs::remove_file(&path).unwrap();
+        std::fs::remove_file(&rotated).unwrap();
+    }
+}
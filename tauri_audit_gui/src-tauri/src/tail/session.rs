@@ -0,0 +1,427 @@
+//! Multi-file tail sessions: one stream per category (and per host directory), each
+//! following the newest `FileStorage` file and switching over when the `{date}` rolls.
+
+use super::checkpoint::{Checkpoint, Resumed, TailCheckpoints};
+use super::watcher::{publish_chunk, spawn_reader, wakeup, FollowedFile, Wakeup};
+use super::{pending_lines, TailChunk, TailCursor, TailTransition, DEFAULT_CHUNK_BYTES};
+use crate::event::source_name;
+use crate::jobs::{run_blocking, Job};
+use crate::loader::directory::{
+    scan, select, DirectorySelection, DiscoveredFile, DEFAULT_FILENAME_PATTERN,
+};
+use notify::event::ModifyKind;
+use notify::{Event, EventKind};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Manager};
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionSource {
+    pub directory: String,
+    #[serde(default)]
+    pub filename_pattern: Option<String>,
+    /// Limits the session to these categories; empty follows every category found.
+    #[serde(default)]
+    pub categories: Vec<String>,
+    /// Host or other tag put in front of the category, e.g. `web-01/auth`.
+    #[serde(default)]
+    pub label: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StreamStatus {
+    pub stream: String,
+    pub path: String,
+    pub date: Option<String>,
+    pub cursor: TailCursor,
+    pub resumed: Option<Resumed>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SessionStatus {
+    pub session_id: String,
+    pub polling: bool,
+    pub streams: Vec<StreamStatus>,
+}
+
+struct Stream {
+    /// Checkpoint key: the source directory plus the label, stable across runs.
+    key: String,
+    label: String,
+    date: Option<String>,
+    file: FollowedFile,
+    resumed: Option<Resumed>,
+    /// Reported with the next batch, e.g. the switch to a new day's file.
+    transition: Option<TailTransition>,
+}
+
+/// The live files found for one stream label, oldest first.
+struct Found {
+    key: String,
+    files: Vec<DiscoveredFile>,
+}
+
+struct SessionState {
+    sources: Vec<SessionSource>,
+    streams: Vec<Stream>,
+    /// Lists on the next pump regardless of events: on the first one, and while a stream
+    /// still has newer `{date}` files to catch up on.
+    rescan: bool,
+}
+
+struct Session {
+    wakeup: Wakeup,
+    /// Locked by the reader for every pump; stopping takes it once to know nothing moves.
+    state: Arc<Mutex<SessionState>>,
+    /// Where the streams stood after the last pump, so status never waits for the reader.
+    status: Arc<Mutex<SessionStatus>>,
+}
+
+#[derive(Default)]
+pub struct TailSessions {
+    sessions: Mutex<HashMap<String, Session>>,
+}
+
+fn stream_label(source: &SessionSource, file: &DiscoveredFile) -> String {
+    let parts: Vec<&str> = [source.label.as_deref(), file.category.as_deref()]
+        .into_iter()
+        .flatten()
+        .collect();
+    if parts.is_empty() {
+        source_name(Path::new(&source.directory))
+    } else {
+        parts.join("/")
+    }
+}
+
+/// Only a file appearing or being renamed can start a stream or a new day; writes to the
+/// files already followed do not need a directory listing.
+fn changes_listing(event: &notify::Result<Event>) -> bool {
+    match event {
+        Ok(event) => {
+            event.need_rescan()
+                || matches!(
+                    event.kind,
+                    EventKind::Create(_)
+                        | EventKind::Modify(ModifyKind::Name(_))
+                        | EventKind::Any
+                        | EventKind::Other
+                )
+        }
+        Err(_) => true,
+    }
+}
+
+/// The live (not rotated or compressed) files for every stream label.
+fn discover(sources: &[SessionSource]) -> Result<BTreeMap<String, Found>, String> {
+    let mut streams = BTreeMap::new();
+    for source in sources {
+        let pattern = source
+            .filename_pattern
+            .as_deref()
+            .unwrap_or(DEFAULT_FILENAME_PATTERN);
+        let selection = DirectorySelection {
+            categories: source.categories.clone(),
+            ..DirectorySelection::default()
+        };
+        // `scan` sorts by date, so each stream's files stay in day order.
+        for file in select(scan(Path::new(&source.directory), pattern)?, &selection)? {
+            if !file.archived {
+                let label = stream_label(source, &file);
+                streams
+                    .entry(label.clone())
+                    .or_insert_with(|| Found {
+                        key: format!("{}#{}", source.directory, label),
+                        files: Vec::new(),
+                    })
+                    .files
+                    .push(file);
+            }
+        }
+    }
+    Ok(streams)
+}
+
+impl SessionState {
+    fn status(&self, session_id: &str, polling: bool) -> SessionStatus {
+        SessionStatus {
+            session_id: session_id.to_string(),
+            polling,
+            streams: self
+                .streams
+                .iter()
+                .map(|stream| StreamStatus {
+                    stream: stream.label.clone(),
+                    path: stream.file.path.to_string_lossy().into_owned(),
+                    date: stream.date.clone(),
+                    cursor: stream.file.cursor.clone(),
+                    resumed: stream.resumed.clone(),
+                })
+                .collect(),
+        }
+    }
+
+    /// On `rescan`, picks up new streams and rolls existing ones over to the next newer
+    /// `{date}` file; then reads one chunk of every stream. A file is only left once it
+    /// is read to the end, so its last lines are not lost at midnight. Returns whether
+    /// more is waiting.
+    fn pump<F>(&mut self, job: &Job, budget: u64, rescan: bool, emit: &mut F) -> bool
+    where
+        F: FnMut(&str, &str, &TailCursor, TailChunk),
+    {
+        let mut more = false;
+        let discovered = match rescan || self.rescan {
+            // A directory that is briefly unreadable just keeps the current files.
+            true => discover(&self.sources).unwrap_or_default(),
+            false => BTreeMap::new(),
+        };
+        self.rescan = false;
+        for (label, found) in discovered {
+            let Some(stream) = self.streams.iter_mut().find(|stream| stream.label == label) else {
+                self.streams.extend(Stream::start(label, found, None));
+                continue;
+            };
+            let date = stream.date.clone();
+            let mut newer = found.files.iter().filter(|file| file.date > date);
+            let Some(next) = newer.next() else {
+                continue;
+            };
+            if stream.read_next(job, budget, emit) {
+                more = true;
+                continue;
+            }
+            let from = stream.file.path.to_string_lossy().into_owned();
+            stream.file = FollowedFile::new(PathBuf::from(&next.path), TailCursor::default());
+            stream.date = next.date.clone();
+            stream.transition = Some(TailTransition::RolledOver { from });
+            // Further days are caught up one file at a time.
+            if newer.next().is_some() {
+                self.rescan = true;
+                more = true;
+            }
+        }
+        for stream in &mut self.streams {
+            more |= stream.read_next(job, budget, emit);
+        }
+        more
+    }
+
+    /// Checkpoints for the streams that moved since the last call.
+    fn checkpoints(&mut self) -> Vec<(String, Checkpoint)> {
+        self.streams
+            .iter_mut()
+            .filter_map(|stream| {
+                let mut checkpoint = stream.file.checkpoint()?;
+                checkpoint.date = stream.date.clone();
+                Some((stream.key.clone(), checkpoint))
+            })
+            .collect()
+    }
+}
+
+impl Stream {
+    /// Without a checkpoint the stream starts at the first line of the newest file. With
+    /// one it starts at the checkpointed file, or the first day after it if that file is
+    /// gone; `pump` then catches up day by day.
+    fn start(label: String, found: Found, checkpoint: Option<Checkpoint>) -> Option<Self> {
+        let Found { key, files } = found;
+        let Some(checkpoint) = checkpoint else {
+            let newest = files.last()?;
+            return Some(Stream {
+                key,
+                label,
+                date: newest.date.clone(),
+                file: FollowedFile::new(PathBuf::from(&newest.path), TailCursor::default()),
+                resumed: None,
+                transition: None,
+            });
+        };
+        let first = files
+            .iter()
+            .position(|file| file.path == checkpoint.path)
+            .or_else(|| files.iter().position(|file| file.date > checkpoint.date))
+            .unwrap_or(files.len().checked_sub(1)?);
+
+        let fresh = TailCursor::default();
+        let missed_lines = files[first..]
+            .iter()
+            .map(|file| {
+                let cursor = if file.path == checkpoint.path {
+                    &checkpoint.cursor
+                } else {
+                    &fresh
+                };
+                pending_lines(Path::new(&file.path), cursor).unwrap_or(0)
+            })
+            .sum();
+        let resumed = Some(checkpoint.resumed(missed_lines));
+        let start = &files[first];
+        let path = PathBuf::from(&start.path);
+        let file = if start.path == checkpoint.path {
+            FollowedFile::from_checkpoint(path, checkpoint)
+        } else {
+            FollowedFile::new(path, fresh)
+        };
+        Some(Stream {
+            key,
+            label,
+            date: start.date.clone(),
+            file,
+            resumed,
+            transition: None,
+        })
+    }
+
+    fn read_next<F>(&mut self, job: &Job, budget: u64, emit: &mut F) -> bool
+    where
+        F: FnMut(&str, &str, &TailCursor, TailChunk),
+    {
+        let path = self.file.path.to_string_lossy().into_owned();
+        let label = &self.label;
+        self.file
+            .read_next(job, budget, label, self.transition.take(), |from, chunk| {
+                emit(label, &path, from, chunk)
+            })
+    }
+}
+
+fn start(
+    app: AppHandle,
+    session_id: String,
+    sources: Vec<SessionSource>,
+    max_bytes: Option<u64>,
+    poll_interval_ms: Option<u64>,
+) -> Result<SessionStatus, String> {
+    let checkpoints = app.state::<TailCheckpoints>();
+    let mut state = SessionState {
+        sources,
+        streams: Vec::new(),
+        rescan: true,
+    };
+    for (label, found) in discover(&state.sources)? {
+        let checkpoint = checkpoints.get(&app, &found.key);
+        state
+            .streams
+            .extend(Stream::start(label, found, checkpoint));
+    }
+    let mut dirs: Vec<PathBuf> = state
+        .sources
+        .iter()
+        .map(|source| PathBuf::from(&source.directory))
+        .collect();
+    dirs.sort();
+    dirs.dedup();
+
+    let (wakeup, rx) = wakeup(&dirs, poll_interval_ms);
+    let poll = wakeup.poll;
+    let polling = poll.is_some();
+    let started = state.status(&session_id, polling);
+    let status = Arc::new(Mutex::new(started.clone()));
+    let state = Arc::new(Mutex::new(state));
+    app.state::<TailSessions>().sessions.lock().unwrap().insert(
+        session_id.clone(),
+        Session {
+            wakeup,
+            state: state.clone(),
+            status: status.clone(),
+        },
+    );
+
+    let budget = max_bytes.unwrap_or(DEFAULT_CHUNK_BYTES);
+    let job = Job::untracked(&app);
+    // Every event may be new lines, but only some can change the directory listing. A
+    // poll tick says neither, so it always lists.
+    let rescan = Arc::new(AtomicBool::new(false));
+    let relevant = {
+        let rescan = rescan.clone();
+        move |event: &notify::Result<Event>| {
+            if changes_listing(event) {
+                rescan.store(true, Ordering::SeqCst);
+            }
+            true
+        }
+    };
+    let app = app.clone();
+    spawn_reader(rx, poll, state, relevant, move |state| {
+        let mut emit = |stream: &str, path: &str, from: &TailCursor, chunk| {
+            let session = Some(session_id.clone());
+            publish_chunk(&app, &job, session, stream, path, from, chunk);
+        };
+        let listing = polling || rescan.swap(false, Ordering::SeqCst);
+        let more = state.pump(&job, budget, listing, &mut emit);
+        let checkpoints = app.state::<TailCheckpoints>();
+        let _ = checkpoints.save(&app, state.checkpoints());
+        *status.lock().unwrap() = state.status(&session_id, polling);
+        more
+    });
+    Ok(started)
+}
+
+/// Starts a session over every stream found in `sources`, pushing `tail://events` tagged
+/// with the session id and stream label. Streams with a checkpoint from an earlier run
+/// resume there.
+#[tauri::command]
+pub async fn tail_session_start(
+    app: AppHandle,
+    session_id: String,
+    sources: Vec<SessionSource>,
+    max_bytes: Option<u64>,
+    poll_interval_ms: Option<u64>,
+) -> Result<SessionStatus, String> {
+    let handle = app.clone();
+    // Listing the directories and counting missed lines both touch the disk.
+    run_blocking(app, None, move |_| {
+        start(handle, session_id, sources, max_bytes, poll_interval_ms)
+    })
+    .await
+}
+
+/// Where each stream stood after the last read.
+#[tauri::command]
+pub async fn tail_session_status(app: AppHandle, session_id: String) -> Option<SessionStatus> {
+    let sessions = app.state::<TailSessions>();
+    let sessions = sessions.sessions.lock().unwrap();
+    let status = sessions.get(&session_id)?.status.lock().unwrap().clone();
+    Some(status)
+}
+
+/// Stops the session and returns where each stream stopped. A read already under way
+/// finishes its chunk, but nothing is read after it, and its checkpoints are written.
+#[tauri::command]
+pub async fn tail_session_stop(app: AppHandle, session_id: String) -> Option<SessionStatus> {
+    let session = app
+        .state::<TailSessions>()
+        .sessions
+        .lock()
+        .unwrap()
+        .remove(&session_id)?;
+    session.wakeup.stop();
+    drop(session.state.lock().unwrap());
+    let _ = app.state::<TailCheckpoints>().flush(&app);
+    let status = session.status.lock().unwrap().clone();
+    Some(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use notify::event::{CreateKind, DataChange, RenameMode};
+
+    #[test]
+    fn only_creates_and_renames_list_the_directory() {
+        let event = |kind| Ok(Event::new(kind).add_path(PathBuf::from("audit_2026-01-02.log")));
+        assert!(changes_listing(&event(EventKind::Create(CreateKind::File))));
+        assert!(changes_listing(&event(EventKind::Modify(
+            ModifyKind::Name(RenameMode::To)
+        ))));
+        assert!(!changes_listing(&event(EventKind::Modify(
+            ModifyKind::Data(DataChange::Content)
+        ))));
+        assert!(changes_listing(&Err(notify::Error::generic(
+            "queue overflow"
+        ))));
+    }
+}
@@ -0,0 +1,550 @@
+//! Push-based following: file-system notifications drive the reads, with a timer only
+//! where the platform or filesystem cannot deliver events.
+
+use super::checkpoint::{Checkpoint, Resumed, TailCheckpoints};
+use super::{
+    pending_lines, read_chunk, TailChunk, TailCursor, TailTransition, DEFAULT_CHUNK_BYTES,
+};
+use crate::event::{source_name, EventRow};
+use crate::jobs::{run_blocking, Job};
+use crate::loader::RejectedLine;
+use crate::search::SearchIndexes;
+use crate::store::EventStore;
+use chrono::Utc;
+use notify::{Event, RecursiveMode, Watcher};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter, Manager, State};
+
+pub const TAIL_EVENT: &str = "tail://events";
+
+const DEFAULT_POLL_MS: u64 = 2000;
+/// Writers flush in bursts; wait this long for the burst to settle before reading.
+const DEBOUNCE: Duration = Duration::from_millis(50);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TailEvents {
+    /// Set when the file belongs to a multi-file tail session.
+    pub session_id: Option<String>,
+    /// Label the rows are tagged with: the session stream, the file name, or the
+    /// collector host for a remote tail.
+    pub stream: String,
+    /// The file read, or the collector URL.
+    pub path: String,
+    /// How many rows this batch appended to the event store.
+    pub events: usize,
+    pub rejects: Vec<RejectedLine>,
+    pub transition: Option<TailTransition>,
+    pub cursor: TailCursor,
+}
+
+/// Appends `rows` to the event store, then tells the webview about the batch.
+pub fn publish(app: &AppHandle, rows: Vec<EventRow>, batch: TailEvents) {
+    app.state::<EventStore>().append(rows);
+    let _ = app.emit(TAIL_EVENT, batch);
+}
+
+/// Hands a chunk read from `path` at `from` to the open search indexes, then publishes it.
+pub fn publish_chunk(
+    app: &AppHandle,
+    job: &Job,
+    session_id: Option<String>,
+    stream: &str,
+    path: &str,
+    from: &TailCursor,
+    chunk: TailChunk,
+) {
+    app.state::<SearchIndexes>().follow(path, from, &chunk, job);
+    let batch = TailEvents {
+        session_id,
+        stream: stream.to_string(),
+        path: path.to_string(),
+        events: chunk.rows.len(),
+        rejects: chunk.rejects,
+        transition: chunk.transition,
+        cursor: chunk.cursor,
+    };
+    publish(app, chunk.rows, batch);
+}
+
+#[derive(Debug, Serialize)]
+pub struct FollowStatus {
+    pub path: String,
+    pub polling: bool,
+    pub cursor: TailCursor,
+    /// Set when the follow picked up from a checkpoint saved by an earlier run.
+    pub resumed: Option<Resumed>,
+}
+
+pub struct Events {
+    rx: Receiver<notify::Result<Event>>,
+    stopped: Arc<AtomicBool>,
+}
+
+/// Keeps every sender of a reader's channel alive (the watcher's and our own). Stopping
+/// or dropping it ends the reader thread.
+pub struct Wakeup {
+    _watcher: Option<Box<dyn Watcher + Send>>,
+    _sender: Sender<notify::Result<Event>>,
+    stopped: Arc<AtomicBool>,
+    pub poll: Option<Duration>,
+}
+
+impl Wakeup {
+    /// The watcher's own thread may still deliver an event after it is dropped, so the
+    /// reader also checks this flag before every pump.
+    pub fn stop(&self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}
+
+impl Drop for Wakeup {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Watches `dirs` non-recursively, so rotation (rename away, create anew) is seen too.
+/// Falls back to polling when no notification backend accepts the watch, or when
+/// `poll_interval_ms` asks for it, e.g. on network shares that never deliver events.
+pub fn wakeup(dirs: &[PathBuf], poll_interval_ms: Option<u64>) -> (Wakeup, Events) {
+    let (sender, rx) = mpsc::channel();
+    let watcher = match poll_interval_ms {
+        Some(_) => None,
+        None => notify::recommended_watcher(sender.clone())
+            .ok()
+            .and_then(|mut watcher| {
+                dirs.iter()
+                    .try_for_each(|dir| watcher.watch(dir, RecursiveMode::NonRecursive))
+                    .ok()?;
+                Some(Box::new(watcher) as Box<dyn Watcher + Send>)
+            }),
+    };
+    let poll = watcher
+        .is_none()
+        .then(|| Duration::from_millis(poll_interval_ms.unwrap_or(DEFAULT_POLL_MS)));
+    let stopped = Arc::new(AtomicBool::new(false));
+    let wakeup = Wakeup {
+        _watcher: watcher,
+        _sender: sender,
+        stopped: stopped.clone(),
+        poll,
+    };
+    (wakeup, Events { rx, stopped })
+}
+
+pub fn parent_dir(path: &Path) -> PathBuf {
+    match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir.to_path_buf(),
+        _ => PathBuf::from("."),
+    }
+}
+
+/// Runs `pump` on `state` once, then again after every relevant notification (or poll
+/// tick) until the `Wakeup` is stopped. Bursts of notifications are coalesced into one
+/// wake-up. `pump` reads one chunk and returns whether more is already waiting; the
+/// lock is let go between chunks so stopping never waits behind a long backlog.
+pub fn spawn_reader<S, R, P>(
+    events: Events,
+    poll: Option<Duration>,
+    state: Arc<Mutex<S>>,
+    relevant: R,
+    mut pump: P,
+) where
+    S: Send + 'static,
+    R: Fn(&notify::Result<Event>) -> bool + Send + 'static,
+    P: FnMut(&mut S) -> bool + Send + 'static,
+{
+    let Events { rx, stopped } = events;
+    thread::spawn(move || {
+        // The flag is checked under the state lock, so once the owner has stopped the
+        // wakeup and taken the lock to read the final position, nothing moves any more.
+        let mut step = || loop {
+            let mut state = state.lock().unwrap();
+            if stopped.load(Ordering::SeqCst) {
+                return false;
+            }
+            if !pump(&mut state) {
+                return true;
+            }
+        };
+        if !step() {
+            return;
+        }
+        loop {
+            let event = match poll {
+                Some(interval) => match rx.recv_timeout(interval) {
+                    Ok(event) => Some(event),
+                    Err(RecvTimeoutError::Timeout) => None,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                },
+                None => match rx.recv() {
+                    Ok(event) => Some(event),
+                    Err(_) => return,
+                },
+            };
+            let mut wanted = true;
+            if let Some(event) = event {
+                wanted = relevant(&event);
+                loop {
+                    match rx.recv_timeout(DEBOUNCE) {
+                        Ok(event) => wanted |= relevant(&event),
+                        Err(RecvTimeoutError::Timeout) => break,
+                        Err(RecvTimeoutError::Disconnected) => return,
+                    }
+                }
+            }
+            if wanted && !step() {
+                return;
+            }
+        }
+    });
+}
+
+/// One followed file: its cursor plus whether the last read found it missing or failed,
+/// so a vanished or unreadable file is reported once rather than on every wake-up.
+pub struct FollowedFile {
+    pub path: PathBuf,
+    pub cursor: TailCursor,
+    missing: bool,
+    error: Option<String>,
+    last_event_id: Option<String>,
+    /// Cursor of the last checkpoint, so an idle file is not saved on every wake-up.
+    saved: Option<TailCursor>,
+}
+
+impl FollowedFile {
+    pub fn new(path: PathBuf, cursor: TailCursor) -> Self {
+        FollowedFile {
+            path,
+            cursor,
+            missing: false,
+            error: None,
+            last_event_id: None,
+            saved: None,
+        }
+    }
+
+    pub fn from_checkpoint(path: PathBuf, checkpoint: Checkpoint) -> Self {
+        FollowedFile {
+            path,
+            saved: Some(checkpoint.cursor.clone()),
+            cursor: checkpoint.cursor,
+            missing: false,
+            error: None,
+            last_event_id: checkpoint.last_event_id,
+        }
+    }
+
+    /// A checkpoint of the current position, if it moved since the last one was taken.
+    pub fn checkpoint(&mut self) -> Option<Checkpoint> {
+        if self.saved.as_ref() == Some(&self.cursor) {
+            return None;
+        }
+        self.saved = Some(self.cursor.clone());
+        Some(Checkpoint {
+            path: self.path.to_string_lossy().into_owned(),
+            cursor: self.cursor.clone(),
+            last_event_id: self.last_event_id.clone(),
+            date: None,
+            saved_at: Utc::now().to_rfc3339(),
+        })
+    }
+
+    /// Reads the next chunk, tags its rows with `stream` and hands a non-empty one to
+    /// `emit` with the cursor it was read from. The chunk carries `transition` instead of
+    /// its own when one is given. Returns whether more is waiting.
+    pub fn read_next<F>(
+        &mut self,
+        job: &Job,
+        budget: u64,
+        stream: &str,
+        mut transition: Option<TailTransition>,
+        mut emit: F,
+    ) -> bool
+    where
+        F: FnMut(&TailCursor, TailChunk),
+    {
+        let mut chunk = match read_chunk(&self.path, &self.cursor, budget, job) {
+            Ok(chunk) => chunk,
+            Err(error) => {
+                if self.error.as_ref() != Some(&error) {
+                    self.error = Some(error.clone());
+                    let failed = TailChunk {
+                        rows: Vec::new(),
+                        positions: Vec::new(),
+                        rejects: Vec::new(),
+                        cursor: self.cursor.clone(),
+                        transition: Some(TailTransition::Unreadable { error }),
+                        has_more: false,
+                    };
+                    emit(&self.cursor, failed);
+                }
+                return false;
+            }
+        };
+        self.error = None;
+        let from = std::mem::replace(&mut self.cursor, chunk.cursor.clone());
+        let is_missing = chunk.transition == Some(TailTransition::Missing);
+        let repeated = is_missing && self.missing;
+        self.missing = is_missing;
+        if transition.is_none() && !repeated {
+            transition = chunk.transition;
+        }
+        for row in &mut chunk.rows {
+            row.source = stream.to_string();
+        }
+        if let Some(row) = chunk.rows.last() {
+            self.last_event_id = Some(row.event_id.clone());
+        }
+        let more = chunk.has_more;
+        chunk.transition = transition;
+        if !chunk.rows.is_empty() || !chunk.rejects.is_empty() || chunk.transition.is_some() {
+            emit(&from, chunk);
+        }
+        more
+    }
+}
+
+struct Follow {
+    wakeup: Wakeup,
+    file: Arc<Mutex<FollowedFile>>,
+    polling: bool,
+}
+
+#[derive(Default)]
+pub struct TailWatcher {
+    follows: Mutex<HashMap<PathBuf, Follow>>,
+}
+
+fn follow(
+    app: AppHandle,
+    path: String,
+    cursor: Option<TailCursor>,
+    max_bytes: Option<u64>,
+    poll_interval_ms: Option<u64>,
+) -> FollowStatus {
+    let watcher = app.state::<TailWatcher>();
+    let checkpoints = app.state::<TailCheckpoints>();
+    let file_path = PathBuf::from(&path);
+    let (wakeup, rx) = wakeup(&[parent_dir(&file_path)], poll_interval_ms);
+    let poll = wakeup.poll;
+    let (file, resumed) = match cursor {
+        Some(cursor) => (FollowedFile::new(file_path.clone(), cursor), None),
+        None => match checkpoints.get(&app, &path) {
+            Some(checkpoint) => {
+                let missed_lines = pending_lines(&file_path, &checkpoint.cursor).unwrap_or(0);
+                let resumed = checkpoint.resumed(missed_lines);
+                (
+                    FollowedFile::from_checkpoint(file_path.clone(), checkpoint),
+                    Some(resumed),
+                )
+            }
+            None => (
+                FollowedFile::new(file_path.clone(), TailCursor::default()),
+                None,
+            ),
+        },
+    };
+    let cursor = file.cursor.clone();
+    let file = Arc::new(Mutex::new(file));
+
+    // Replacing an existing follow drops its watcher and stops the old thread.
+    watcher.follows.lock().unwrap().insert(
+        file_path.clone(),
+        Follow {
+            wakeup,
+            file: file.clone(),
+            polling: poll.is_some(),
+        },
+    );
+
+    let budget = max_bytes.unwrap_or(DEFAULT_CHUNK_BYTES);
+    let job = Job::untracked(&app);
+    let stream = source_name(&file_path);
+    // Only the parent directory is watched, so the file name identifies our events.
+    let name = file_path.file_name().map(|n| n.to_os_string());
+    let relevant = move |event: &notify::Result<Event>| match event {
+        Ok(event) => {
+            event.need_rescan()
+                || event.paths.is_empty()
+                || event.paths.iter().any(|p| p.file_name() == name.as_deref())
+        }
+        Err(_) => true,
+    };
+    let key = path.clone();
+    let app = app.clone();
+    spawn_reader(rx, poll, file, relevant, move |file| {
+        let path = file.path.to_string_lossy().into_owned();
+        let more = file.read_next(&job, budget, &stream, None, |from, chunk| {
+            publish_chunk(&app, &job, None, &stream, &path, from, chunk);
+        });
+        if let Some(checkpoint) = file.checkpoint() {
+            let checkpoints = app.state::<TailCheckpoints>();
+            let _ = checkpoints.save(&app, vec![(key.clone(), checkpoint)]);
+        }
+        more
+    });
+
+    FollowStatus {
+        path,
+        polling: poll.is_some(),
+        cursor,
+        resumed,
+    }
+}
+
+/// Starts pushing `tail://events` for `path`. Without a `cursor` it resumes from the
+/// saved checkpoint, if any. Passing `poll_interval_ms` forces polling.
+#[tauri::command]
+pub async fn tail_follow(
+    app: AppHandle,
+    path: String,
+    cursor: Option<TailCursor>,
+    max_bytes: Option<u64>,
+    poll_interval_ms: Option<u64>,
+) -> Result<FollowStatus, String> {
+    let handle = app.clone();
+    // Counting what was missed since a checkpoint reads the rest of the file.
+    run_blocking(app, None, move |_| {
+        Ok(follow(handle, path, cursor, max_bytes, poll_interval_ms))
+    })
+    .await
+}
+
+/// Stops following `path` and hands back the cursor so the UI can resume later. The
+/// checkpoint is written before returning.
+#[tauri::command]
+pub fn tail_unfollow(
+    app: AppHandle,
+    watcher: State<'_, TailWatcher>,
+    path: String,
+) -> Option<FollowStatus> {
+    let follow = watcher.follows.lock().unwrap().remove(Path::new(&path))?;
+    follow.wakeup.stop();
+    let cursor = follow.file.lock().unwrap().cursor.clone();
+    let _ = app.state::<TailCheckpoints>().flush(&app);
+    Some(FollowStatus {
+        path,
+        polling: follow.polling,
+        cursor,
+        resumed: None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn line(id: &str) -> String {
+        format!(
+            "{{\"event_id\":\"{}\",\"timestamp\":\"2026-01-01T00:00:00Z\"}}\n",
+            id
+        )
+    }
+
+    fn append(path: &Path, text: &str) {
+        use std::io::Write;
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .unwrap();
+        file.write_all(text.as_bytes()).unwrap();
+    }
+
+    /// One `read_next` with `budget`: the chunks it emitted, each with the offset it was
+    /// read from, and whether more is waiting.
+    fn read(file: &mut FollowedFile, budget: u64) -> (Vec<(u64, TailChunk)>, bool) {
+        let mut chunks = Vec::new();
+        let more = file.read_next(&Job::detached(), budget, "web", None, |from, chunk| {
+            chunks.push((from.offset, chunk))
+        });
+        (chunks, more)
+    }
+
+    fn ids(chunk: &TailChunk) -> Vec<&str> {
+        chunk.rows.iter().map(|row| row.event_id.as_str()).collect()
+    }
+
+    #[test]
+    fn reads_follow_appends() {
+        let dir = std::env::temp_dir().join(format!("watcher-appends-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.log");
+        append(&path, &(line("e1") + &line("e2")));
+        let mut file = FollowedFile::new(path.clone(), TailCursor::default());
+
+        let (chunks, more) = read(&mut file, DEFAULT_CHUNK_BYTES);
+        assert!(!more);
+        assert_eq!(chunks.len(), 1);
+        let (from, chunk) = &chunks[0];
+        assert_eq!(*from, 0);
+        assert_eq!(ids(chunk), ["e1", "e2"]);
+        assert!(chunk.rows.iter().all(|row| row.source == "web"));
+        assert_eq!(file.cursor.line, 2);
+        let first = file.checkpoint().unwrap();
+        assert_eq!(first.last_event_id.as_deref(), Some("e2"));
+
+        // Nothing new: nothing emitted and no new checkpoint.
+        assert!(read(&mut file, DEFAULT_CHUNK_BYTES).0.is_empty());
+        assert!(file.checkpoint().is_none());
+
+        // A line still being written waits for its newline.
+        let e3 = line("e3");
+        let (head, tail) = e3.split_at(10);
+        append(&path, head);
+        assert!(read(&mut file, DEFAULT_CHUNK_BYTES).0.is_empty());
+        append(&path, tail);
+        let (chunks, _) = read(&mut file, DEFAULT_CHUNK_BYTES);
+        assert_eq!(chunks[0].0, first.cursor.offset);
+        assert_eq!(ids(&chunks[0].1), ["e3"]);
+
+        // A backlog over the budget comes in several reads, without gaps.
+        let backlog: String = (4..10).map(|i| line(&format!("e{}", i))).collect();
+        append(&path, &backlog);
+        let budget = line("e4").len() as u64 * 2;
+        let mut delivered = Vec::new();
+        let mut offset = file.cursor.offset;
+        loop {
+            let (chunks, more) = read(&mut file, budget);
+            for (from, chunk) in &chunks {
+                assert_eq!(*from, offset);
+                offset = chunk.cursor.offset;
+                delivered.extend(ids(chunk).into_iter().map(str::to_string));
+            }
+            if !more {
+                break;
+            }
+        }
+        assert_eq!(delivered, ["e4", "e5", "e6", "e7", "e8", "e9"]);
+        assert_eq!(file.cursor.offset, std::fs::metadata(&path).unwrap().len());
+        assert_eq!(file.cursor.line, 9);
+
+        // A vanished file is reported once; the new file at the path is read from the top.
+        std::fs::remove_file(&path).unwrap();
+        let (chunks, _) = read(&mut file, DEFAULT_CHUNK_BYTES);
+        assert_eq!(chunks[0].1.transition, Some(TailTransition::Missing));
+        assert!(read(&mut file, DEFAULT_CHUNK_BYTES).0.is_empty());
+        append(&path, &line("n1"));
+        let (chunks, _) = read(&mut file, DEFAULT_CHUNK_BYTES);
+        // The file system may hand the new file the old inode, which reads as truncation.
+        assert!(
+            matches!(
+                chunks[0].1.transition,
+                Some(TailTransition::Replaced | TailTransition::Truncated { .. })
+            ),
+            "{:?}",
+            chunks[0].1.transition
+        );
+        assert_eq!(ids(&chunks[0].1), ["n1"]);
+        assert_eq!(file.cursor.line, 1);
+    }
+}